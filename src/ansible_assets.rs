@@ -24,7 +24,7 @@ impl AnsibleAssets {
     fn prepare_impl(dev_mode: bool) -> Result<Self> {
         if dev_mode {
             let dev_dir = PathBuf::from("ansible");
-            if dev_dir.join("playbooks").exists() && dev_dir.join("roles").exists() {
+            if is_ansible_tree(&dev_dir) {
                 return Ok(Self {
                     ansible_dir: dev_dir,
                 });
@@ -48,6 +48,19 @@ impl AnsibleAssets {
         Ok(Self { ansible_dir })
     }
 
+    /// The `ansible/` tree of the checkout in the working directory — the
+    /// committed source, never the extracted copy `prepare` may hand out —
+    /// for commands that edit what the repo declares.
+    pub fn checkout() -> Result<Self> {
+        let ansible_dir = PathBuf::from("ansible");
+        if !is_ansible_tree(&ansible_dir) {
+            eyre::bail!(
+                "No ansible/playbooks and ansible/roles in the working directory; run from the root of an auberge checkout"
+            );
+        }
+        Ok(Self { ansible_dir })
+    }
+
     pub fn ansible_dir(&self) -> &Path {
         &self.ansible_dir
     }
@@ -98,6 +111,10 @@ impl AnsibleAssets {
     }
 }
 
+fn is_ansible_tree(dir: &Path) -> bool {
+    dir.join("playbooks").exists() && dir.join("roles").exists()
+}
+
 fn embedded_fingerprint() -> String {
     fingerprint(hash_dir(&EMBEDDED_ANSIBLE))
 }
@@ -1,11 +1,15 @@
 use crate::config::{Config, Preflight};
 use crate::hosts::{HOST_FLAG, HOST_POSITIONAL};
+use crate::key_registry::KeyRegistry;
 use crate::output;
 use crate::playbook_meta::{app_memory_vars, app_version_vars};
 use crate::prompt::{Choice, select_item};
 use crate::services::ansible_runner::{InventoryHost, run_bootstrap, run_playbook};
 use crate::services::dependency_resolver::{
-    find_standalone_playbook, resolve_tags_to_playbook_runs,
+    PlaybookRun, find_standalone_playbook, resolve_tags_to_playbook_runs,
+};
+use crate::services::deploy_journal::{
+    DeployEntry, DeploySource, RecordedRun, apps_in, journaled, playbook_stem,
 };
 use crate::services::inventory::{Host, get_playbooks, hosts_ignoreip_var, select_or_arg};
 use clap::Subcommand;
@@ -74,9 +78,13 @@ pub enum AnsibleCommands {
     },
 }
 
-fn validate_config_for_playbook(playbook_name: &str, tags: Option<&[String]>) -> Result<Preflight> {
-    let config = Config::load()?;
-    config.preflight_for(playbook_name, tags)
+fn validate_config_for_playbook(
+    host: &str,
+    playbook_name: &str,
+    tags: Option<&[String]>,
+) -> Result<Preflight> {
+    let config = Config::load()?.for_host(host);
+    config.preflight_for(&KeyRegistry::bundled()?, playbook_name, tags)
 }
 
 fn resolve_playbook_name(arg: &Path, playbooks: &[PathBuf]) -> Result<PathBuf> {
@@ -207,21 +215,46 @@ fn run_auto_resolved(
         tags.join(", ")
     ));
 
-    let assets = crate::ansible_assets::AnsibleAssets::prepare()?;
-    let app_versions = app_version_vars(&assets.playbooks_dir())?;
-    let memory_budgets = app_memory_vars(&assets.playbooks_dir())?;
-    let hosts_ignoreip = hosts_ignoreip_var()?;
-    let mut extra_vars: Vec<(&str, &str)> = app_versions
-        .iter()
-        .chain(memory_budgets.iter())
-        .chain(std::iter::once(&hosts_ignoreip))
-        .map(|(name, value)| (name.as_str(), value.as_str()))
-        .collect();
-    if let Some(user) = user {
-        extra_vars.push(("ansible_user", user));
-    }
+    let injected = InjectedVars::load()?;
+    let extra_vars = injected.extra_vars(user);
+    let mut recorded: Vec<RecordedRun> = runs.iter().map(RecordedRun::from).collect();
+    recorded.extend(standalone_playbooks.iter().map(|p| RecordedRun {
+        playbook: playbook_stem(p),
+        tags: vec![],
+    }));
+    let entry = injected.journal_entry(host, apps_in(&runs), recorded, check);
+
+    journaled(entry, |_| {
+        execute_resolved(
+            host,
+            &runs,
+            &standalone_playbooks,
+            check,
+            skip_tags,
+            user,
+            &extra_vars,
+            ask_pass,
+            force,
+        )
+    })?;
+
+    output::success("All playbook runs completed successfully");
+    Ok(())
+}
 
-    for run in &runs {
+#[allow(clippy::too_many_arguments)]
+fn execute_resolved(
+    host: &Host,
+    runs: &[PlaybookRun],
+    standalone_playbooks: &[PathBuf],
+    check: bool,
+    skip_tags: Option<&[String]>,
+    user: Option<&str>,
+    extra_vars: &[(&str, &str)],
+    ask_pass: bool,
+    force: bool,
+) -> Result<()> {
+    for run in runs {
         let playbook_file = run.path.file_name().and_then(|n| n.to_str()).unwrap_or("");
         let playbook_stem = run
             .path
@@ -235,7 +268,7 @@ fn run_auto_resolved(
             Some(run.tags.as_slice())
         };
 
-        let preflight = validate_config_for_playbook(playbook_file, run_tags_ref)?;
+        let preflight = validate_config_for_playbook(&host.name, playbook_file, run_tags_ref)?;
         show_playbook_warnings(playbook_file, force)?;
 
         let run_tags = if run.tags.is_empty() {
@@ -267,7 +300,7 @@ fn run_auto_resolved(
             check,
             run_tags,
             skip_tags,
-            Some(&extra_vars),
+            Some(extra_vars),
             false,
             ask_pass,
             &mut progress,
@@ -293,16 +326,67 @@ fn run_auto_resolved(
         output::success(&format!("{} completed successfully", playbook_stem));
     }
 
-    for playbook in &standalone_playbooks {
-        run_single_playbook(
-            host, playbook, check, None, skip_tags, user, ask_pass, force,
+    for playbook in standalone_playbooks {
+        execute_single_playbook(
+            host, playbook, check, None, skip_tags, user, extra_vars, ask_pass, force,
         )?;
     }
 
-    output::success("All playbook runs completed successfully");
     Ok(())
 }
 
+/// The extra vars every `ansible run` injects, kept apart so the deploy
+/// journal can record which App Versions and memory budgets a run was given.
+struct InjectedVars {
+    app_versions: Vec<(String, String)>,
+    memory_budgets: Vec<(String, String)>,
+    hosts_ignoreip: (String, String),
+}
+
+impl InjectedVars {
+    fn load() -> Result<Self> {
+        let playbooks_dir = crate::ansible_assets::AnsibleAssets::prepare()?.playbooks_dir();
+        Ok(Self {
+            app_versions: app_version_vars(&playbooks_dir)?,
+            memory_budgets: app_memory_vars(&playbooks_dir)?,
+            hosts_ignoreip: hosts_ignoreip_var()?,
+        })
+    }
+
+    fn extra_vars<'a>(&'a self, user: Option<&'a str>) -> Vec<(&'a str, &'a str)> {
+        let mut extra_vars: Vec<(&str, &str)> = self
+            .app_versions
+            .iter()
+            .chain(self.memory_budgets.iter())
+            .chain(std::iter::once(&self.hosts_ignoreip))
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        if let Some(user) = user {
+            extra_vars.push(("ansible_user", user));
+        }
+        extra_vars
+    }
+
+    fn journal_entry(
+        &self,
+        host: &Host,
+        apps: Vec<String>,
+        runs: Vec<RecordedRun>,
+        check: bool,
+    ) -> DeployEntry {
+        DeployEntry {
+            source: DeploySource::AnsibleRun,
+            host: host.name.clone(),
+            apps,
+            runs,
+            app_versions: self.app_versions.clone(),
+            memory_budgets: self.memory_budgets.clone(),
+            check,
+            pre_upgrade_backup: None,
+        }
+    }
+}
+
 fn split_standalone_redirects(tags: Vec<String>) -> Result<(Vec<PathBuf>, Vec<String>)> {
     let mut playbooks = Vec::new();
     let mut unknown = Vec::new();
@@ -332,6 +416,46 @@ fn run_single_playbook(
     user: Option<&str>,
     ask_pass: bool,
     force: bool,
+) -> Result<()> {
+    let injected = InjectedVars::load()?;
+    let extra_vars = injected.extra_vars(user);
+    let run = PlaybookRun {
+        path: playbook.to_path_buf(),
+        tags: tags.map(<[String]>::to_vec).unwrap_or_default(),
+    };
+    let entry = injected.journal_entry(
+        host,
+        apps_in(std::slice::from_ref(&run)),
+        vec![RecordedRun::from(&run)],
+        check,
+    );
+
+    journaled(entry, |_| {
+        execute_single_playbook(
+            host,
+            playbook,
+            check,
+            tags,
+            skip_tags,
+            user,
+            &extra_vars,
+            ask_pass,
+            force,
+        )
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_single_playbook(
+    host: &Host,
+    playbook: &Path,
+    check: bool,
+    tags: Option<&[String]>,
+    skip_tags: Option<&[String]>,
+    user: Option<&str>,
+    extra_vars: &[(&str, &str)],
+    ask_pass: bool,
+    force: bool,
 ) -> Result<()> {
     let playbook_file = playbook.file_name().and_then(|n| n.to_str()).unwrap_or("");
     let playbook_stem = playbook
@@ -339,7 +463,7 @@ fn run_single_playbook(
         .and_then(|s| s.to_str())
         .unwrap_or("unknown");
 
-    let preflight = validate_config_for_playbook(playbook_file, tags)?;
+    let preflight = validate_config_for_playbook(&host.name, playbook_file, tags)?;
     let is_fresh_bootstrap = playbook_file == "bootstrap.yml";
 
     if is_fresh_bootstrap {
@@ -364,20 +488,6 @@ fn run_single_playbook(
         groups: host.groups.clone(),
     };
 
-    let assets = crate::ansible_assets::AnsibleAssets::prepare()?;
-    let app_versions = app_version_vars(&assets.playbooks_dir())?;
-    let memory_budgets = app_memory_vars(&assets.playbooks_dir())?;
-    let hosts_ignoreip = hosts_ignoreip_var()?;
-    let mut extra_vars: Vec<(&str, &str)> = app_versions
-        .iter()
-        .chain(memory_budgets.iter())
-        .chain(std::iter::once(&hosts_ignoreip))
-        .map(|(name, value)| (name.as_str(), value.as_str()))
-        .collect();
-    if let Some(user) = user {
-        extra_vars.push(("ansible_user", user));
-    }
-
     let mut progress = crate::services::progress::TerminalProgress::new("");
     let result = run_playbook(
         &preflight,
@@ -386,7 +496,7 @@ fn run_single_playbook(
         check,
         tags,
         skip_tags,
-        Some(&extra_vars),
+        Some(extra_vars),
         false,
         ask_pass,
         &mut progress,
@@ -573,10 +683,9 @@ pub fn run_ansible_bootstrap(
     user: Option<String>,
     force: bool,
 ) -> Result<()> {
-    let preflight = validate_config_for_playbook("bootstrap.yml", None)?;
-
     let host = select_or_arg(host_arg, HOST_POSITIONAL)?;
     let host_name = host.name.clone();
+    let preflight = validate_config_for_playbook(&host_name, "bootstrap.yml", None)?;
     let assets = crate::ansible_assets::AnsibleAssets::prepare()?;
     let bootstrap_playbook = assets.playbooks_dir().join("bootstrap.yml");
 
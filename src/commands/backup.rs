@@ -1,17 +1,23 @@
 use crate::config::Config;
 use crate::hosts::{HOST_FLAG, Host, HostManager, select_or_arg as hosts_select_or_arg};
+use crate::key_registry::KeyRegistry;
 use crate::output;
 use crate::playbook_meta::BackupRecipe;
+use crate::profile::Profile;
 use crate::prompt::confirm;
 use crate::services::backup::executor::{RecipeExecutor, staged_parameters, staged_paths};
 use crate::services::backup::recipe::{
     assets_playbooks_dir, discover_backuppable_apps, load_app_recipe,
 };
+use crate::services::backup::repository::{self, Repository, RepositorySpec};
 use crate::services::backup::restic;
 use crate::services::backup::session::{
     BackupSession, CreateOutcome, SessionOpts, restic_prune, restic_push,
 };
-use crate::services::backup::verify::{self, MaxAge, Status, Verdict, VerifyRequest};
+use crate::services::backup::verify::{
+    self, DeepProbe, MaxAge, ProbeOutcome, RestoreOutcome, Snapshot, Status, Verdict, VerifyRequest,
+};
+use crate::services::progress::Progress;
 use crate::services::ssh::{LiveSshSession, resolve_ssh_key_path};
 use crate::ssh_session::SshSession;
 use chrono::Utc;
@@ -99,6 +105,12 @@ pub enum BackupCommands {
             help = "Dry run (runs create in preview mode, skips push/prune/cleanup)"
         )]
         dry_run: bool,
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Only this restic repository (default: all configured)"
+        )]
+        repo: Option<String>,
     },
     #[command(visible_alias = "ls", about = "List available backups")]
     List {
@@ -119,6 +131,22 @@ pub enum BackupCommands {
     Restore {
         #[arg(help = "Backup timestamp (YYYY-MM-DD_HH-MM-SS) or 'latest' (omit to be prompted)")]
         backup_id: Option<String>,
+        #[arg(
+            long,
+            value_name = "SNAPSHOT",
+            num_args = 0..=1,
+            default_missing_value = "latest",
+            conflicts_with = "backup_id",
+            help = "Restore from the offsite restic repository: a snapshot id or 'latest'"
+        )]
+        from_restic: Option<String>,
+        #[arg(
+            long,
+            value_name = "NAME",
+            requires = "from_restic",
+            help = "Restic repository to restore from (default: the sole configured one)"
+        )]
+        repo: Option<String>,
         #[arg(short = 'H', long, help = "Target host")]
         host: Option<String>,
         #[arg(
@@ -159,11 +187,23 @@ pub enum BackupCommands {
         host: Option<String>,
         #[arg(short, long, help = "Specific backup timestamp (default: latest)")]
         backup_id: Option<String>,
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Only this restic repository (default: all configured)"
+        )]
+        repo: Option<String>,
     },
     #[command(about = "Prune old snapshots from offsite restic repository")]
     Prune {
         #[arg(short = 'n', long, help = "Show what would be pruned without removing")]
         dry_run: bool,
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Only this restic repository (default: all configured)"
+        )]
+        repo: Option<String>,
     },
     #[command(
         visible_alias = "v",
@@ -178,12 +218,33 @@ pub enum BackupCommands {
         host: Option<String>,
         #[arg(short, long, help = "Also assert this app is in the latest snapshot")]
         app: Option<String>,
+        #[arg(
+            long,
+            value_name = "APP",
+            conflicts_with = "app",
+            help = "Also test-restore this app's latest snapshot and check it against its Backup Recipe"
+        )]
+        deep: Option<String>,
+        #[arg(
+            long,
+            value_name = "SUBSET",
+            default_value = "5%",
+            requires = "deep",
+            help = "Share of the repository's data `restic check` reads back with --deep (5%, 1/10)"
+        )]
+        read_data_subset: String,
         #[arg(
             long,
             default_value = "24h",
             help = "Freshness threshold as <number><s|m|h|d>"
         )]
         max_age: String,
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Only this restic repository (default: all configured)"
+        )]
+        repo: Option<String>,
         #[arg(
             short = 'o',
             long,
@@ -237,6 +298,8 @@ struct RestoreTarget {
 
 pub struct RestoreOptions {
     pub backup_id: Option<String>,
+    pub from_restic: Option<String>,
+    pub repo: Option<String>,
     pub host_arg: Option<String>,
     pub from_host_arg: Option<String>,
     pub apps: Option<Vec<String>>,
@@ -402,6 +465,7 @@ pub fn run_backup_sync(
     ssh_key: Option<PathBuf>,
     include_music: bool,
     dry_run: bool,
+    repo: Option<String>,
 ) -> Result<()> {
     let resolved = get_host_or_select(host)?;
     let host_name = resolved.name.clone();
@@ -443,9 +507,13 @@ pub fn run_backup_sync(
         ));
     }
 
-    run_backup_push(Some(host_name), Some(outcome.timestamp.clone()))?;
+    run_backup_push(
+        Some(host_name),
+        Some(outcome.timestamp.clone()),
+        repo.clone(),
+    )?;
 
-    if let Err(e) = run_backup_prune(false) {
+    if let Err(e) = run_backup_prune(false, repo) {
         output::warn(&format!("Prune failed (push succeeded): {}", e));
     }
 
@@ -692,18 +760,38 @@ pub fn run_backup_restore(opts: RestoreOptions) -> Result<()> {
         None => (host.name.clone(), false),
     };
 
-    let host_backup_dir = backup_root.join(&source_host_name);
+    // Held until the restore is done: dropping it deletes the fetched snapshot.
+    let restic_staging = match &opts.from_restic {
+        Some(snapshot) => Some(stage_restic_snapshot(
+            &source_host_name,
+            opts.repo.as_deref(),
+            snapshot,
+            opts.apps.as_deref().unwrap_or_default(),
+        )?),
+        None => None,
+    };
 
-    if !host_backup_dir.exists() {
-        eyre::bail!("No backups found for host: {}", source_host_name);
-    }
+    let (backup_id, timestamp_dir) = match &restic_staging {
+        Some(staging) => (
+            format!("restic snapshot {}", staging.short_id),
+            staging.timestamp_dir.clone(),
+        ),
+        None => {
+            let host_backup_dir = backup_root.join(&source_host_name);
 
-    let backup_id = match opts.backup_id {
-        Some(id) => id,
-        None => select_backup_id(&host_backup_dir)?,
-    };
+            if !host_backup_dir.exists() {
+                eyre::bail!("No backups found for host: {}", source_host_name);
+            }
+
+            let backup_id = match opts.backup_id {
+                Some(id) => id,
+                None => select_backup_id(&host_backup_dir)?,
+            };
 
-    let timestamp_dir = resolve_timestamp_dir(&host_backup_dir, &backup_id)?;
+            let timestamp_dir = resolve_timestamp_dir(&host_backup_dir, &backup_id)?;
+            (backup_id, timestamp_dir)
+        }
+    };
 
     let app_names = match opts.apps {
         Some(apps) => apps,
@@ -897,8 +985,13 @@ pub fn run_backup_restore(opts: RestoreOptions) -> Result<()> {
             };
 
             // Build a Preflight — best-effort; if config is incomplete we warn and skip.
-            let preflight_result =
-                Config::load().and_then(|cfg| cfg.preflight_for("apps.yml", Some(&tags)));
+            let preflight_result = Config::load().and_then(|cfg| {
+                cfg.for_host(&host.name).preflight_for(
+                    &KeyRegistry::bundled()?,
+                    "apps.yml",
+                    Some(&tags),
+                )
+            });
 
             match preflight_result {
                 Err(e) => {
@@ -1019,6 +1112,64 @@ pub fn run_backup_restore(opts: RestoreOptions) -> Result<()> {
     Ok(())
 }
 
+/// A restic snapshot fetched into a temporary directory, deleted on drop.
+struct ResticStaging {
+    _dir: tempfile::TempDir,
+    /// The snapshot's backup directory inside the temporary one, laid out
+    /// like a local timestamp directory.
+    timestamp_dir: PathBuf,
+    short_id: String,
+}
+
+/// Fetch `host`'s snapshot (an id or `latest`) from the offsite repository,
+/// only `apps` of it when given, so the local restore path can take over.
+fn stage_restic_snapshot(
+    host: &str,
+    repo: Option<&str>,
+    snapshot: &str,
+    apps: &[String],
+) -> Result<ResticStaging> {
+    let repository = repository::load_one(&Config::load()?, repo)?;
+    let (restic_repo, restic_password) = (&repository.location, &repository.password);
+    let snapshots: Vec<Snapshot> =
+        serde_json::from_str(&restic::snapshots_json(restic_repo, restic_password)?)
+            .wrap_err("Unreadable restic snapshot list")?;
+
+    let source = verify::restore_source(&snapshots, host, snapshot, apps, |snapshot, path| {
+        restic::snapshot_contains_path(restic_repo, restic_password, &snapshot.id, path)
+    })?;
+    let root = source.root.trim_end_matches('/');
+    let includes: Vec<String> = apps.iter().map(|app| format!("{root}/{app}")).collect();
+
+    let dir = tempfile::Builder::new()
+        .prefix("auberge-restore-")
+        .tempdir()
+        .wrap_err("Failed to create a staging dir for the restic snapshot")?;
+    output::info(&format!(
+        "Fetching restic snapshot {} ({}) from '{}' into {}",
+        source.snapshot.short_id(),
+        source.snapshot.time.format("%Y-%m-%dT%H:%MZ"),
+        repository.name,
+        dir.path().display()
+    ));
+    let mut progress = crate::services::progress::TerminalProgress::new("Fetching snapshot");
+    progress.task_started(&format!("restic restore {}", source.snapshot.short_id()));
+    restic::restore(
+        restic_repo,
+        restic_password,
+        &source.snapshot.id,
+        &includes,
+        dir.path(),
+    )?;
+    progress.task_done();
+
+    Ok(ResticStaging {
+        timestamp_dir: dir.path().join(root.trim_start_matches('/')),
+        short_id: source.snapshot.short_id().to_string(),
+        _dir: dir,
+    })
+}
+
 fn restore_app(host: &Host, target: &RestoreTarget, ssh_key: &Path) -> Result<()> {
     eprintln!("\n--- Restoring {} ---", target.app);
 
@@ -1032,6 +1183,101 @@ fn restore_app(host: &Host, target: &RestoreTarget, ssh_key: &Path) -> Result<()
     Ok(())
 }
 
+/// IDs of the staged backups holding `app` for `host_name`, oldest first.
+pub fn staged_backup_ids(host_name: &str, app: &str) -> Result<Vec<String>> {
+    let host_backup_dir = default_backup_dir().join(host_name);
+    if !host_backup_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut ids: Vec<String> = sorted_timestamp_entries(&host_backup_dir)?
+        .into_iter()
+        .filter(|e| e.path().join(app).is_dir())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+    ids.reverse();
+    Ok(ids)
+}
+
+/// Take a staged backup of `apps` on `host_name` ahead of a deploy that
+/// changes their App Versions, returning its backup ID. Unlike `backup
+/// create`, one failed app fails the whole call: the deploy it guards must
+/// not go ahead without it.
+pub fn create_pre_upgrade_backup(host_name: &str, apps: &[String]) -> Result<String> {
+    let host = HostManager::get_host(host_name)?;
+    let ssh_key_path = resolve_ssh_key_path(&host, None)?;
+    let playbooks_dir = assets_playbooks_dir()?;
+    let recipes: Vec<(String, _)> = apps
+        .iter()
+        .map(|name| {
+            Ok::<_, eyre::Report>((
+                name.clone(),
+                load_app_recipe(&playbooks_dir, name, &host.user)?,
+            ))
+        })
+        .collect::<Result<_>>()?;
+
+    let opts = SessionOpts {
+        host_name: host.name.clone(),
+        dest: default_backup_dir(),
+        timestamp: Utc::now().format("%Y-%m-%d_%H-%M-%S").to_string(),
+        parameters: create_parameters(false),
+    };
+    let ssh = LiveSshSession::new(&host, &ssh_key_path);
+    let outcome = BackupSession::new(&ssh, recipes, opts).create()?;
+
+    let failed = outcome.failed_apps();
+    if !failed.is_empty() {
+        eyre::bail!(
+            "Pre-upgrade backup failed:\n{}",
+            failed
+                .iter()
+                .map(|(app, err)| format!("  {app}: {err}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+    Ok(outcome.timestamp)
+}
+
+/// Put one app's data back from one of `host_name`'s staged backups ahead
+/// of a rollback, without the plan and confirmation `backup restore` wraps
+/// around it. The app's services are stopped and left stopped, even on
+/// failure: the version being rolled back from must never run against the
+/// restored data. [`finish_staged_restore`] completes it once the old
+/// version is deployed.
+pub fn restore_staged_app(host_name: &str, backup_id: &str, app: &str) -> Result<()> {
+    let host = HostManager::get_host(host_name)?;
+    let ssh_key_path = resolve_ssh_key_path(&host, None)?;
+    let backup_path =
+        resolve_timestamp_dir(&default_backup_dir().join(host_name), backup_id)?.join(app);
+    if !backup_path.exists() {
+        eyre::bail!("Backup {backup_id} holds no {app} data");
+    }
+    let recipe = load_app_recipe(&assets_playbooks_dir()?, app, &host.user)
+        .wrap_err_with(|| format!("Unknown or non-backuppable app: {}", app))?;
+
+    let session = LiveSshSession::new(&host, &ssh_key_path);
+    let mut progress =
+        crate::services::progress::TerminalProgress::new(&format!("Restoring {app}"));
+    RecipeExecutor::new(&session).restore_stopped(&recipe, &backup_path, &mut progress)
+}
+
+/// Run `app`'s `post_restore_command` after [`restore_staged_app`] and the
+/// redeploy that started its services again.
+pub fn finish_staged_restore(host_name: &str, app: &str) -> Result<()> {
+    let host = HostManager::get_host(host_name)?;
+    let ssh_key_path = resolve_ssh_key_path(&host, None)?;
+    let recipe = load_app_recipe(&assets_playbooks_dir()?, app, &host.user)
+        .wrap_err_with(|| format!("Unknown or non-backuppable app: {}", app))?;
+
+    let session = LiveSshSession::new(&host, &ssh_key_path);
+    let mut progress =
+        crate::services::progress::TerminalProgress::new(&format!("Restoring {app}"));
+    let result = RecipeExecutor::new(&session).post_restore(&recipe, &mut progress);
+    progress.task_done();
+    result
+}
+
 pub fn run_export_opml(
     host_arg: Option<String>,
     output: PathBuf,
@@ -1119,27 +1365,49 @@ pub fn run_import_opml(
     Ok(())
 }
 
-fn load_restic_config() -> Result<(String, String)> {
-    let config = Config::load()?;
-    let missing = config.validate_required(&["restic_repository", "restic_password"]);
-    if !missing.is_empty() {
+/// The restic repositories a command fans out to: every configured one, or
+/// only `--repo`, each resolved only once the fan-out reaches it.
+fn load_repositories(only: Option<&str>) -> Result<Vec<RepositorySpec>> {
+    repository::load(&Config::load()?, only)
+}
+
+/// Resolve and run `step` against every repository, carrying on past a
+/// failure — a password that does not resolve included — so one unreachable
+/// copy never holds back the others; fails if any of them did.
+fn for_each_repository(
+    repositories: &[RepositorySpec],
+    step: &str,
+    mut run: impl FnMut(&Repository) -> Result<()>,
+) -> Result<()> {
+    let mut resolve_and_run =
+        |spec: &RepositorySpec| spec.resolve().and_then(|repository| run(&repository));
+    if let [only] = repositories {
+        return resolve_and_run(only);
+    }
+    let mut failed: Vec<&str> = Vec::new();
+    for repository in repositories {
+        if let Err(e) = resolve_and_run(repository) {
+            output::warn(&format!("{step} failed for '{}': {e:#}", repository.name));
+            failed.push(&repository.name);
+        }
+    }
+    if !failed.is_empty() {
         eyre::bail!(
-            "Missing restic config: {}. Set with `auberge config set <key> <value>`",
-            missing.join(", ")
+            "{step} failed for {} of {} restic repositories: {}",
+            failed.len(),
+            repositories.len(),
+            failed.join(", ")
         );
     }
-    Ok((
-        config
-            .get_resolved("restic_repository")?
-            .ok_or_else(|| eyre::eyre!("restic_repository is missing or not a valid value"))?,
-        config
-            .get_resolved("restic_password")?
-            .ok_or_else(|| eyre::eyre!("restic_password is missing or not a valid value"))?,
-    ))
+    Ok(())
 }
 
-pub fn run_backup_push(host_filter: Option<String>, backup_id: Option<String>) -> Result<()> {
-    let (restic_repo, restic_password) = load_restic_config()?;
+pub fn run_backup_push(
+    host_filter: Option<String>,
+    backup_id: Option<String>,
+    repo: Option<String>,
+) -> Result<()> {
+    let repositories = load_repositories(repo.as_deref())?;
 
     let backup_root = default_backup_dir();
     if !backup_root.exists() {
@@ -1160,18 +1428,25 @@ pub fn run_backup_push(host_filter: Option<String>, backup_id: Option<String>) -
             )
         })?;
 
-    restic_push(&restic_repo, &restic_password, &backup_dir, &host)
+    for_each_repository(&repositories, "Push", |repository| {
+        restic_push(repository, &backup_dir, &host)
+    })
 }
 
-pub fn run_backup_prune(dry_run: bool) -> Result<()> {
-    let (restic_repo, restic_password) = load_restic_config()?;
-    restic_prune(&restic_repo, &restic_password, dry_run)
+pub fn run_backup_prune(dry_run: bool, repo: Option<String>) -> Result<()> {
+    let repositories = load_repositories(repo.as_deref())?;
+    for_each_repository(&repositories, "Prune", |repository| {
+        restic_prune(repository, dry_run)
+    })
 }
 
 pub struct VerifyOptions {
     pub host: Option<String>,
     pub app: Option<String>,
     pub max_age: String,
+    pub deep: Option<String>,
+    pub read_data_subset: String,
+    pub repo: Option<String>,
     pub format: OutputFormat,
 }
 
@@ -1194,37 +1469,77 @@ fn verify_exit_code(result: Result<Status>) -> i32 {
 
 fn verify_and_report(opts: VerifyOptions) -> Result<Status> {
     let max_age = MaxAge::parse(&opts.max_age)?;
-    let (restic_repo, restic_password) = load_restic_config()?;
+    let repositories = load_repositories(opts.repo.as_deref())?;
     let host = resolve_snapshot_host(opts.host)?;
 
+    let deep = match &opts.deep {
+        Some(app) => {
+            let user = HostManager::get_host(&host)?.user;
+            Some((app, load_app_recipe(&assets_playbooks_dir()?, app, &user)?))
+        }
+        None => None,
+    };
+
     let request = VerifyRequest {
         host: &host,
-        app: opts.app.as_deref(),
+        app: opts.deep.as_deref().or(opts.app.as_deref()),
         max_age: &max_age,
         now: Utc::now(),
     };
 
-    let verdict = match restic::snapshots_json(&restic_repo, &restic_password) {
-        Ok(snapshots_json) => verify::verdict(&request, &snapshots_json, |snapshot, path| {
-            restic::snapshot_contains_path(&restic_repo, &restic_password, &snapshot.id, path)
-        }),
-        Err(e) => Verdict::unreachable(&format!("{e:#}")),
-    };
+    let verdicts: Vec<(&RepositorySpec, Verdict)> = repositories
+        .iter()
+        .map(|spec| {
+            let repository = match spec.resolve() {
+                Ok(repository) => repository,
+                Err(e) => return (spec, Verdict::unreachable(&format!("{e:#}"))),
+            };
+            let verdict = verify_repository(&request, &repository);
+            let verdict = match &deep {
+                Some((app, recipe)) => {
+                    let mut probe = ResticProbe {
+                        repository: &repository,
+                        read_data_subset: &opts.read_data_subset,
+                        scratch: None,
+                    };
+                    verify::deepen(verdict, &host, app, recipe, &mut probe)
+                }
+                None => verdict,
+            };
+            (spec, verdict)
+        })
+        .collect();
+    let status = Status::worst(verdicts.iter().map(|(_, verdict)| verdict.status));
 
     match opts.format {
-        OutputFormat::Human => print_verify_checklist(&verdict),
-        OutputFormat::Json => print_verify_json(&request, &verdict)?,
+        OutputFormat::Human => print_verify_checklist(&verdicts, status),
+        OutputFormat::Json => print_verify_json(&request, &verdicts, status)?,
     }
 
-    for remediation in verdict
-        .checks
-        .iter()
-        .filter_map(|check| check.remediation.as_deref())
-    {
-        eprintln!("{}", remediation);
+    for (repository, verdict) in &verdicts {
+        for remediation in verdict
+            .checks
+            .iter()
+            .filter_map(|check| check.remediation.as_deref())
+        {
+            match verdicts.len() {
+                1 => eprintln!("{}", remediation),
+                _ => eprintln!("{}: {}", repository.name, remediation),
+            }
+        }
     }
 
-    Ok(verdict.status)
+    Ok(status)
+}
+
+fn verify_repository(request: &VerifyRequest<'_>, repository: &Repository) -> Verdict {
+    let (restic_repo, restic_password) = (&repository.location, &repository.password);
+    match restic::snapshots_json(restic_repo, restic_password) {
+        Ok(snapshots_json) => verify::verdict(request, &snapshots_json, |snapshot, path| {
+            restic::snapshot_contains_path(restic_repo, restic_password, &snapshot.id, path)
+        }),
+        Err(e) => Verdict::unreachable(&format!("{e:#}")),
+    }
 }
 
 fn resolve_snapshot_host(host_arg: Option<String>) -> Result<String> {
@@ -1253,20 +1568,128 @@ fn sole_configured_host(hosts: &[Host]) -> Result<String> {
     }
 }
 
-fn print_verify_checklist(verdict: &Verdict) {
-    for check in &verdict.checks {
-        println!("{} {}", if check.passed { "✓" } else { "✗" }, check.message);
+/// `backup verify --deep` against one repository. The test restore lands in
+/// a scratch directory deleted when the probe is dropped.
+struct ResticProbe<'a> {
+    repository: &'a Repository,
+    read_data_subset: &'a str,
+    scratch: Option<tempfile::TempDir>,
+}
+
+impl DeepProbe for ResticProbe<'_> {
+    fn check_repository(&mut self) -> Result<ProbeOutcome> {
+        let mut progress =
+            crate::services::progress::TerminalProgress::new("Checking repository data");
+        progress.task_started(&format!(
+            "restic check --read-data-subset={} ({})",
+            self.read_data_subset, self.repository.name
+        ));
+        let damage = restic::check(
+            &self.repository.location,
+            &self.repository.password,
+            self.read_data_subset,
+        )?;
+        progress.task_done();
+        Ok(damage.map_or(ProbeOutcome::Passed, ProbeOutcome::Failed))
+    }
+
+    fn restore_app(&mut self, snapshot_id: &str, app_path: &str) -> Result<RestoreOutcome> {
+        let scratch = tempfile::Builder::new()
+            .prefix("auberge-verify-")
+            .tempdir()
+            .wrap_err("Failed to create a scratch dir for the test restore")?;
+        let mut progress = crate::services::progress::TerminalProgress::new("Test restore");
+        progress.task_started(&format!(
+            "restic restore {} ({})",
+            snapshot_id.get(..8).unwrap_or(snapshot_id),
+            self.repository.name
+        ));
+        let failure = restic::try_restore(
+            &self.repository.location,
+            &self.repository.password,
+            snapshot_id,
+            &[app_path.to_string()],
+            scratch.path(),
+        )?;
+        progress.task_done();
+        if let Some(reason) = failure {
+            return Ok(RestoreOutcome::Failed(reason));
+        }
+        let restored = scratch.path().join(app_path.trim_start_matches('/'));
+        self.scratch = Some(scratch);
+        Ok(RestoreOutcome::Restored(restored))
+    }
+
+    fn list_dump(&mut self, dump: &Path) -> Result<ProbeOutcome> {
+        let output = std::process::Command::new("pg_restore")
+            .arg("--list")
+            .arg(dump)
+            .stdout(std::process::Stdio::null())
+            .output()
+            .wrap_err("Failed to run pg_restore")?;
+        Ok(match output.status.success() {
+            true => ProbeOutcome::Passed,
+            false => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                ProbeOutcome::Failed(match stderr.trim() {
+                    "" => format!("pg_restore failed ({})", output.status),
+                    reason => reason.to_string(),
+                })
+            }
+        })
+    }
+}
+
+/// One checklist per repository, each headed by its name when there are
+/// several, then the overall verdict naming the repositories that failed.
+fn print_verify_checklist(verdicts: &[(&RepositorySpec, Verdict)], status: Status) {
+    for (repository, verdict) in verdicts {
+        if verdicts.len() > 1 {
+            println!("{}", repository.name);
+        }
+        for check in &verdict.checks {
+            println!("{} {}", if check.passed { "✓" } else { "✗" }, check.message);
+        }
     }
+    let failed: Vec<&str> = verdicts
+        .iter()
+        .filter(|(_, verdict)| !verdict.is_verified())
+        .map(|(repository, _)| repository.name.as_str())
+        .collect();
     println!(
         "{}",
-        match verdict.is_verified() {
-            true => "verified",
-            false => "not verified",
+        match (status, verdicts.len()) {
+            (Status::Verified, _) => "verified".to_string(),
+            (_, 1) => "not verified".to_string(),
+            _ => format!("not verified: {}", failed.join(", ")),
         }
     );
 }
 
-fn print_verify_json(request: &VerifyRequest<'_>, verdict: &Verdict) -> Result<()> {
+fn print_verify_json(
+    request: &VerifyRequest<'_>,
+    verdicts: &[(&RepositorySpec, Verdict)],
+    status: Status,
+) -> Result<()> {
+    let repositories: Vec<_> = verdicts
+        .iter()
+        .map(|(repository, verdict)| verdict_json(&repository.name, verdict))
+        .collect();
+
+    let json = serde_json::to_string_pretty(&serde_json::json!({
+        "verified": status == Status::Verified,
+        "status": status.as_str(),
+        "host": request.host,
+        "app": request.app,
+        "max_age": request.max_age.label(),
+        "repositories": repositories,
+    }))?;
+
+    println!("{}", json);
+    Ok(())
+}
+
+fn verdict_json(name: &str, verdict: &Verdict) -> serde_json::Value {
     let checks: Vec<_> = verdict
         .checks
         .iter()
@@ -1289,18 +1712,13 @@ fn print_verify_json(request: &VerifyRequest<'_>, verdict: &Verdict) -> Result<(
         })
     });
 
-    let json = serde_json::to_string_pretty(&serde_json::json!({
+    serde_json::json!({
+        "name": name,
         "verified": verdict.is_verified(),
         "status": verdict.status.as_str(),
-        "host": request.host,
-        "app": request.app,
-        "max_age": request.max_age.label(),
         "snapshot": snapshot,
         "checks": checks,
-    }))?;
-
-    println!("{}", json);
-    Ok(())
+    })
 }
 
 fn resolve_backup_dir(
@@ -1443,9 +1861,7 @@ fn get_host_or_select(host_arg: Option<String>) -> Result<Host> {
 }
 
 fn default_backup_dir() -> PathBuf {
-    dirs::data_local_dir()
-        .map(|d| d.join("auberge").join("backups"))
-        .unwrap_or_else(|| PathBuf::from("~/.local/share/auberge/backups"))
+    Profile::active().backup_dir()
 }
 
 /// Template instances (`syncthing@alice`) have no unit file of their own —
@@ -1597,12 +2013,16 @@ mod tests {
         let _push = BackupCommands::Push {
             host: None,
             backup_id: None,
+            repo: Some("usb".to_string()),
         };
     }
 
     #[test]
     fn test_prune_variant_exists() {
-        let _prune = BackupCommands::Prune { dry_run: true };
+        let _prune = BackupCommands::Prune {
+            dry_run: true,
+            repo: None,
+        };
     }
 
     #[test]
@@ -1611,10 +2031,68 @@ mod tests {
             host: None,
             app: Some("bichon".to_string()),
             max_age: "24h".to_string(),
+            deep: None,
+            read_data_subset: "5%".to_string(),
+            repo: None,
             output: OutputFormat::Human,
         };
     }
 
+    fn test_repository(name: &str) -> RepositorySpec {
+        RepositorySpec {
+            name: name.to_string(),
+            location: format!("/mnt/{name}"),
+            password: String::new(),
+            retention: Default::default(),
+        }
+    }
+
+    #[test]
+    fn for_each_repository_carries_on_past_a_failure() {
+        let repositories = [test_repository("nas"), test_repository("usb")];
+        let mut visited = Vec::new();
+        let err = for_each_repository(&repositories, "Push", |repository| {
+            visited.push(repository.name.clone());
+            match repository.name.as_str() {
+                "nas" => eyre::bail!("unreachable"),
+                _ => Ok(()),
+            }
+        })
+        .unwrap_err();
+        assert_eq!(visited, ["nas", "usb"]);
+        assert_eq!(
+            err.to_string(),
+            "Push failed for 1 of 2 restic repositories: nas"
+        );
+    }
+
+    #[test]
+    fn for_each_repository_fails_only_the_repository_whose_password_does_not_resolve() {
+        let mut nas = test_repository("nas");
+        nas.password = "env:AUBERGE_TEST_UNSET_REPOSITORY_PASSWORD".to_string();
+        let repositories = [nas, test_repository("usb")];
+        let mut visited = Vec::new();
+        let err = for_each_repository(&repositories, "Push", |repository| {
+            visited.push(repository.name.clone());
+            Ok(())
+        })
+        .unwrap_err();
+        assert_eq!(visited, ["usb"]);
+        assert_eq!(
+            err.to_string(),
+            "Push failed for 1 of 2 restic repositories: nas"
+        );
+    }
+
+    #[test]
+    fn for_each_repository_passes_a_sole_failure_through() {
+        let err = for_each_repository(&[test_repository("default")], "Prune", |_| {
+            eyre::bail!("restic prune failed: locked")
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "restic prune failed: locked");
+    }
+
     #[test]
     fn sole_configured_host_is_implied() {
         let hosts = vec![test_host()];
@@ -1845,6 +2323,7 @@ mod tests {
             ssh_key: None,
             include_music: false,
             dry_run: true,
+            repo: None,
         };
     }
 
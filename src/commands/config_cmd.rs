@@ -1,16 +1,26 @@
 use crate::ansible_assets::AnsibleAssets;
-use crate::config::Config;
-use crate::key_registry::KeyRegistry;
-use crate::output;
-use crate::playbook_meta::PlaybookMeta;
+use crate::commands::deploy::redeploy_with_config;
+use crate::config::{Config, KeyMigration};
+use crate::config_import::{self, ImportFormat, ImportPlan, SecretStore};
+use crate::hosts::{HOST_FLAG, HostManager};
+use crate::key_registry::{Generator, KeyRegistry};
+use crate::output::{self, OutputFormat};
+use crate::playbook_meta::{PlaybookMeta, load_all_metas};
 use crate::prompt::{Choice, select_item};
+use crate::secrets::{SecretRef, resolve_value};
+use crate::services::dependency_resolver::{get_app_names, get_infrastructure_role_names};
+use crate::services::deploy_journal::{DeployJournal, DeployRecord, DeploySource};
+use crate::services::inventory;
+use crate::signal;
 use clap::{Args, Subcommand};
 use dialoguer::{Input, theme::ColorfulTheme};
 use eyre::{Result, WrapErr};
-use std::collections::HashSet;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
+use tabled::Tabled;
 
 #[derive(Subcommand)]
 pub enum ConfigCommands {
@@ -19,27 +29,82 @@ pub enum ConfigCommands {
         about = "Print a config.toml scaffold derived from the Key Registry"
     )]
     Init(InitArgs),
+    #[command(
+        visible_alias = "gen",
+        about = "Generate a secret from its Key Registry generator and save it"
+    )]
+    Generate {
+        #[arg(help = "Key name (e.g. immich_db_password)")]
+        key: Option<String>,
+        #[arg(short = 'f', long, help = "Replace a value that is already set")]
+        force: bool,
+    },
+    #[command(
+        visible_alias = "rot",
+        about = "Replace a secret, redeploy every app that requires it, then save it"
+    )]
+    Rotate(RotateArgs),
+    #[command(
+        visible_alias = "im",
+        about = "Import values from a .env, Ansible vars (YAML) or TOML file"
+    )]
+    Import(ImportArgs),
     #[command(visible_alias = "s", about = "Set a config value")]
     Set {
         #[arg(help = "Key name (e.g. admin_user_name)")]
         key: Option<String>,
         #[arg(help = "Value to set")]
         value: Option<String>,
+        #[arg(short = 'H', long, help = "Set the override in [hosts.<HOST>]")]
+        host: Option<String>,
     },
     #[command(visible_alias = "g", about = "Get a config value")]
     Get {
         #[arg(help = "Key name")]
         key: Option<String>,
+        #[arg(
+            short = 'H',
+            long,
+            help = "Get the effective value for HOST, overrides applied"
+        )]
+        host: Option<String>,
     },
     #[command(
         visible_alias = "l",
         about = "List all config keys (sensitive values redacted)"
     )]
-    List,
+    List {
+        #[arg(
+            short = 'H',
+            long,
+            help = "List the effective config for HOST, overrides marked"
+        )]
+        host: Option<String>,
+    },
+    #[command(
+        visible_alias = "sc",
+        about = "Print a JSON Schema of config.toml for editors"
+    )]
+    Schema {
+        #[arg(short = 'o', long, help = "Write the schema to FILE")]
+        output: Option<PathBuf>,
+    },
+    #[command(
+        visible_alias = "v",
+        about = "Check config against every Playbook Meta and the Key Registry"
+    )]
+    Validate(ValidateArgs),
+    #[command(
+        visible_alias = "m",
+        about = "Rewrite renamed keys in config.toml to their current names"
+    )]
+    Migrate,
     #[command(visible_alias = "rm", about = "Remove a key from config")]
     Remove {
         #[arg(help = "Key name")]
         key: Option<String>,
+        #[arg(short = 'H', long, help = "Remove the override from [hosts.<HOST>]")]
+        host: Option<String>,
     },
     #[command(visible_alias = "e", about = "Open config in $EDITOR")]
     Edit,
@@ -63,8 +128,76 @@ pub struct InitArgs {
     pub output: Option<PathBuf>,
     #[arg(short = 'f', long, help = "Overwrite the output file if it exists")]
     pub force: bool,
+    #[arg(
+        long,
+        help = "Fill in every key that has a generator (random passwords, tokens, hashes)"
+    )]
+    pub generate_secrets: bool,
+}
+
+#[derive(Args)]
+pub struct ValidateArgs {
+    #[arg(
+        short = 'a',
+        long = "app",
+        value_delimiter = ',',
+        help = "Check only the required keys of these apps (repeatable)"
+    )]
+    pub apps: Vec<String>,
+    #[arg(
+        short = 'o',
+        long,
+        value_enum,
+        default_value = "human",
+        help = "Output format"
+    )]
+    pub output: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct RotateArgs {
+    #[arg(help = "Key name (e.g. immich_db_password)")]
+    pub key: Option<String>,
+    #[arg(short = 'H', long, help = "Host to redeploy with the new value")]
+    pub host: Option<String>,
+    #[arg(long, help = "New value, instead of one from the key's generator")]
+    pub value: Option<String>,
+    #[arg(short = 'f', long, help = "Skip confirmation prompt")]
+    pub force: bool,
 }
 
+#[derive(Args)]
+pub struct ImportArgs {
+    #[arg(help = "File to import (.env, group_vars YAML or TOML)")]
+    pub file: PathBuf,
+    #[arg(
+        long,
+        value_enum,
+        help = "File format, when the file name does not tell [env, yaml, toml]"
+    )]
+    pub format: Option<ImportFormat>,
+    #[arg(short = 'H', long, help = "Import into [hosts.<HOST>] as overrides")]
+    pub host: Option<String>,
+    #[arg(
+        long,
+        value_name = "STORE",
+        default_value = "literal",
+        help = "Where secret values go: literal, pass:PREFIX or file:DIR"
+    )]
+    pub secrets: SecretStore,
+    #[arg(
+        long,
+        help = "Keep values such as `!cmd` or `pass:name` as secret backend references"
+    )]
+    pub keep_references: bool,
+    #[arg(short = 'f', long, help = "Replace values that are already set")]
+    pub force: bool,
+}
+
+/// File name of the JSON Schema `config init --output` writes beside the
+/// scaffold, which the scaffold's `#:schema` directive points at.
+const SCHEMA_FILE: &str = "config.schema.json";
+
 fn key_choice(prompt: &str) -> Choice {
     Choice::new("config key")
         .with_prompt(prompt)
@@ -107,24 +240,90 @@ fn resolve_key(key: Option<String>, config: &Config, prompt: &str) -> Result<Str
 pub fn run_config_init(args: InitArgs) -> Result<()> {
     let assets = AnsibleAssets::prepare()?;
     let registry = KeyRegistry::load(&assets.ansible_dir().join("keys.yml"))?;
-    let scaffold = build_scaffold(&registry, &args.playbooks, &assets.playbooks_dir())?;
+    let scaffold = build_scaffold(
+        &registry,
+        &args.playbooks,
+        &assets.playbooks_dir(),
+        args.generate_secrets,
+    )?;
 
     match args.output {
         None => {
             print!("{scaffold}");
             Ok(())
         }
-        Some(path) => write_scaffold(&path, &scaffold, args.force),
+        Some(path) => {
+            let schema = config_schema(&registry, &assets.playbooks_dir())?;
+            write_init_files(&path, &scaffold, &schema, args.force)
+        }
     }
 }
 
+/// Write the scaffold at `path` and the schema beside it, pointing the
+/// scaffold at the schema. Neither file is written if either exists
+/// without `force`.
+fn write_init_files(path: &Path, scaffold: &str, schema: &str, force: bool) -> Result<()> {
+    let schema_path = path.with_file_name(SCHEMA_FILE);
+    if schema_path.exists() && !force {
+        eyre::bail!(
+            "Refusing to overwrite {}; pass --force to override",
+            schema_path.display()
+        );
+    }
+    write_scaffold(
+        path,
+        &format!("#:schema ./{SCHEMA_FILE}\n\n{scaffold}"),
+        force,
+    )?;
+    fs::write(&schema_path, schema)
+        .wrap_err_with(|| format!("Failed to write {}", schema_path.display()))?;
+    output::success(&format!("Wrote schema to {}", schema_path.display()));
+    Ok(())
+}
+
+/// The JSON Schema of `config.toml`, with the apps that require each key.
+pub fn run_config_schema(output: Option<PathBuf>) -> Result<()> {
+    let assets = AnsibleAssets::prepare()?;
+    let registry = KeyRegistry::load(&assets.ansible_dir().join("keys.yml"))?;
+    let schema = config_schema(&registry, &assets.playbooks_dir())?;
+    match output {
+        None => println!("{schema}"),
+        Some(path) => {
+            fs::write(&path, format!("{schema}\n"))
+                .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+            output::success(&format!("Wrote schema to {}", path.display()));
+        }
+    }
+    Ok(())
+}
+
+fn config_schema(registry: &KeyRegistry, playbooks_dir: &Path) -> Result<String> {
+    let mut required_by: HashMap<String, Vec<String>> = HashMap::new();
+    for (app, meta) in load_all_metas(playbooks_dir)? {
+        for key in meta.required_keys {
+            required_by.entry(key).or_default().push(app.clone());
+        }
+    }
+    for apps in required_by.values_mut() {
+        apps.sort();
+    }
+    Ok(serde_json::to_string_pretty(
+        &registry.json_schema(&required_by),
+    )?)
+}
+
 fn build_scaffold(
     registry: &KeyRegistry,
     playbooks: &[String],
     playbooks_dir: &Path,
+    generate: bool,
 ) -> Result<String> {
     if playbooks.is_empty() {
-        return Ok(registry.scaffold());
+        return if generate {
+            registry.scaffold_generated(None)
+        } else {
+            Ok(registry.scaffold())
+        };
     }
     let mut keys: HashSet<String> = HashSet::new();
     for playbook in playbooks {
@@ -133,7 +332,11 @@ fn build_scaffold(
             .wrap_err_with(|| format!("Unknown playbook '{playbook}'"))?;
         keys.extend(meta.required_keys);
     }
-    Ok(registry.scaffold_filtered(&keys))
+    if generate {
+        registry.scaffold_generated(Some(&keys))
+    } else {
+        Ok(registry.scaffold_filtered(&keys))
+    }
 }
 
 fn write_scaffold(path: &Path, scaffold: &str, force: bool) -> Result<()> {
@@ -156,20 +359,181 @@ fn write_scaffold(path: &Path, scaffold: &str, force: bool) -> Result<()> {
     Ok(())
 }
 
-pub fn run_config_set(key: Option<String>, value: Option<String>) -> Result<()> {
+/// Fill `key` in from its Key Registry generator. A value that is already
+/// set is kept unless `force`: the services deployed with it still use it.
+pub fn run_config_generate(key: Option<String>, force: bool) -> Result<()> {
     let mut config = Config::load()?;
+    let registry = KeyRegistry::bundled()?;
     let key = match key {
         Some(k) => k,
         None => {
-            let assets = AnsibleAssets::prepare()?;
-            let registry = KeyRegistry::load(&assets.ansible_dir().join("keys.yml"))?;
-            select_registry_key(&registry, "Select key to set")?
+            let keys: Vec<String> = sorted_registry_keys(&registry)
+                .into_iter()
+                .filter(|k| registry.get(k).is_some_and(|e| e.generate.is_some()))
+                .collect();
+            select_item(
+                &keys,
+                |s: &String| s.clone(),
+                key_choice("Select key to generate"),
+            )?
+        }
+    };
+    if !force && config.get(&key).is_some_and(|v| !v.trim().is_empty()) {
+        eyre::bail!("Key '{key}' is already set; pass --force to replace it");
+    }
+    let value = generate_value(&registry, &config, &key)?;
+    config.set(&key, &value)?;
+    output::success(&format!("Generated '{key}'"));
+    Ok(())
+}
+
+/// A fresh value for `key` from its generator, hashing the resolved value
+/// of the generator's source key in `config` if it has one.
+fn generate_value(registry: &KeyRegistry, config: &Config, key: &str) -> Result<String> {
+    let source = registry
+        .get(key)
+        .and_then(|entry| entry.generate.as_ref())
+        .and_then(Generator::source);
+    let source_value = source
+        .and_then(|source| config.get(source))
+        .map(|value| resolve_value(&value))
+        .transpose()?;
+    registry.generate(key, |_| source_value.clone())
+}
+
+/// Replace `key` on one Host and redeploy exactly the apps whose Playbook
+/// Meta requires it. `config.toml` keeps the old value until that deploy
+/// succeeds, so a failed rotation leaves nothing half-written.
+pub fn run_config_rotate(args: RotateArgs) -> Result<()> {
+    let mut config = Config::load()?;
+    let registry = KeyRegistry::bundled()?;
+    let key = match args.key {
+        Some(k) => k,
+        None => select_registry_key(&registry, "Select key to rotate")?,
+    };
+    let host = inventory::select_or_arg(args.host, HOST_FLAG)?;
+
+    let metas = load_all_metas(&AnsibleAssets::prepare()?.playbooks_dir())?;
+    let mut deployable = get_app_names()?;
+    deployable.extend(get_infrastructure_role_names()?);
+    let apps = rotation_apps(&key, &metas, &deployable)?;
+
+    let value = match args.value {
+        Some(value) => {
+            if let SecretRef::Literal(literal) = SecretRef::parse(&value) {
+                registry.check(&key, &literal)?;
+            }
+            value
+        }
+        None => {
+            let current = config.for_host(&host.name).get(&key).unwrap_or_default();
+            if let Some(backend) = SecretRef::parse(&current).backend() {
+                eyre::bail!(
+                    "'{key}' is read from the {backend} backend; rotate it there, or pass --value"
+                );
+            }
+            generate_value(&registry, &config, &key)
+                .wrap_err("Pass --value to supply the new value yourself")?
         }
     };
+
+    output::info(&format!(
+        "Rotating '{key}' on {}: redeploying {}",
+        host.name,
+        apps.join(", ")
+    ));
+    let rotated = config.with_host_value(&host.name, &key, &value)?;
+    signal::with_ctrlc(|| {
+        redeploy_with_config(
+            apps.clone(),
+            &host,
+            rotated,
+            DeploySource::Rotate,
+            args.force,
+        )
+    })
+    .wrap_err_with(|| format!("Rotation aborted; config.toml still holds the old '{key}'"))?;
+
+    if config.host_overrides(&host.name).contains(&key) {
+        config.set_for_host(&host.name, &key, &value)?;
+        output::success(&format!("Rotated '{key}' in [hosts.{}]", host.name));
+        return Ok(());
+    }
+    config.set(&key, &value)?;
+    output::success(&format!("Rotated '{key}'"));
+    let records = DeployJournal::open()?.records()?;
+    for other in hosts_running(&records, &apps, &host.name) {
+        output::warn(&format!(
+            "{other} still runs {} with the old '{key}'; redeploy there: auberge deploy {} -H {other}",
+            apps.join(", "),
+            apps.join(" ")
+        ));
+    }
+    Ok(())
+}
+
+/// The apps to redeploy when `key` changes: every Playbook Meta requiring
+/// it. A playbook-wide meta (`apps`, `bootstrap`, ...) cannot be redeployed
+/// for one key, so requiring the key there refuses the rotation.
+fn rotation_apps(
+    key: &str,
+    metas: &[(String, PlaybookMeta)],
+    deployable: &[String],
+) -> Result<Vec<String>> {
+    let mut requiring: Vec<&str> = metas
+        .iter()
+        .filter(|(_, meta)| meta.required_keys.iter().any(|k| k == key))
+        .map(|(name, _)| name.as_str())
+        .collect();
+    requiring.sort_unstable();
+    if requiring.is_empty() {
+        eyre::bail!("No Playbook Meta requires '{key}'; change it with `auberge config set`");
+    }
+    let (apps, playbooks): (Vec<&str>, Vec<&str>) = requiring
+        .into_iter()
+        .partition(|name| deployable.iter().any(|d| d == name));
+    if !playbooks.is_empty() {
+        eyre::bail!(
+            "'{key}' is required by the {} playbook(s), which a rotation cannot redeploy app by app; change it with `auberge config set` and run a full deploy",
+            playbooks.join(", ")
+        );
+    }
+    Ok(apps.into_iter().map(str::to_string).collect())
+}
+
+/// Hosts other than `host` with a successful deploy that included any of
+/// `apps`, sorted.
+fn hosts_running(records: &[DeployRecord], apps: &[String], host: &str) -> BTreeSet<String> {
+    records
+        .iter()
+        .filter(|record| record.success && !record.check && record.host != host)
+        .filter(|record| record.apps.iter().any(|app| apps.contains(app)))
+        .map(|record| record.host.clone())
+        .collect()
+}
+
+pub fn run_config_set(
+    key: Option<String>,
+    value: Option<String>,
+    host: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    if let Some(host) = &host {
+        HostManager::get_host(host)?;
+    }
+    let registry = KeyRegistry::bundled()?;
+    let key = match key {
+        Some(k) => k,
+        None => select_registry_key(&registry, "Select key to set")?,
+    };
     let value = match value {
         Some(v) => v,
         None => {
-            let current = config.get(&key).unwrap_or_default();
+            let current = match &host {
+                Some(host) => config.for_host(host).get(&key),
+                None => config.get(&key),
+            }
+            .unwrap_or_default();
             Input::<String>::with_theme(&ColorfulTheme::default())
                 .with_prompt(format!("Value for '{}'", key))
                 .default(current)
@@ -177,13 +541,149 @@ pub fn run_config_set(key: Option<String>, value: Option<String>) -> Result<()>
                 .interact_text()?
         }
     };
-    config.set(&key, &value)?;
-    output::success(&format!("{} = {}", key, value));
+    // A backend reference is checked once resolved, at preflight.
+    if let SecretRef::Literal(literal) = SecretRef::parse(&value) {
+        registry.check(&key, &literal)?;
+    }
+    match &host {
+        Some(host) => {
+            config.set_for_host(host, &key, &value)?;
+            output::success(&format!("[hosts.{}] {} = {}", host, key, value));
+        }
+        None => {
+            config.set(&key, &value)?;
+            output::success(&format!("{} = {}", key, value));
+        }
+    }
     Ok(())
 }
 
-pub fn run_config_get(key: Option<String>) -> Result<()> {
-    let config = Config::load()?;
+/// Import the single values of `args.file`, mapped through the Key
+/// Registry. Nothing is written while a key already holds a different
+/// value, unless `--force`.
+pub fn run_config_import(args: ImportArgs) -> Result<()> {
+    let mut config = Config::load()?;
+    if let Some(host) = &args.host {
+        HostManager::get_host(host)?;
+    }
+    let format = match args.format {
+        Some(format) => format,
+        None => ImportFormat::detect(&args.file).ok_or_else(|| {
+            eyre::eyre!(
+                "Cannot tell the format of {}; pass --format env, yaml or toml",
+                args.file.display()
+            )
+        })?,
+    };
+    let imported = config_import::read(&args.file, format)?;
+    let registry = KeyRegistry::bundled()?;
+
+    let existing: HashMap<String, String> = match &args.host {
+        Some(host) => {
+            let effective = config.for_host(host);
+            config
+                .host_overrides(host)
+                .into_iter()
+                .filter_map(|key| effective.get(&key).map(|value| (key, value)))
+                .collect()
+        }
+        None => config
+            .keys()
+            .into_iter()
+            .filter_map(|key| config.get(&key).map(|value| (key, value)))
+            .collect(),
+    };
+    let plan = ImportPlan::new(
+        &registry,
+        &imported,
+        |key| existing.get(key).cloned(),
+        args.keep_references,
+    );
+    report_import(&imported.skipped, &plan);
+
+    if !plan.conflicts.is_empty() && !args.force {
+        eyre::bail!(
+            "Refusing to overwrite {} existing value(s): {}; pass --force to replace them",
+            plan.conflicts.len(),
+            plan.conflicts.join(", ")
+        );
+    }
+    let held: Vec<&str> = plan
+        .entries
+        .iter()
+        .filter(|entry| entry.store_secret && args.secrets.holds(&entry.key))
+        .map(|entry| entry.key.as_str())
+        .collect();
+    if !held.is_empty() && !args.force {
+        eyre::bail!(
+            "Refusing to overwrite secrets the {} store already holds: {}; pass --force to replace them",
+            args.secrets.backend().unwrap_or_default(),
+            held.join(", ")
+        );
+    }
+    if plan.entries.is_empty() {
+        output::success(&format!("Nothing to import from {}", args.file.display()));
+        return Ok(());
+    }
+
+    let mut values = Vec::new();
+    for entry in &plan.entries {
+        let value = match SecretRef::parse(&entry.value) {
+            SecretRef::Literal(secret) if entry.store_secret => {
+                args.secrets.store(&entry.key, &secret)?
+            }
+            _ => entry.value.clone(),
+        };
+        values.push((entry.key.clone(), value));
+    }
+    config.set_all(args.host.as_deref(), &values)?;
+
+    let target = match &args.host {
+        Some(host) => format!(" into [hosts.{host}]"),
+        None => String::new(),
+    };
+    for entry in &plan.entries {
+        match args.secrets.backend().filter(|_| entry.store_secret) {
+            Some(backend) => output::success(&format!("{} ({backend})", entry.key)),
+            None => output::success(&entry.key),
+        }
+    }
+    output::success(&format!(
+        "Imported {} key(s) from {}{target}",
+        plan.entries.len(),
+        args.file.display()
+    ));
+    Ok(())
+}
+
+fn report_import(skipped: &[(String, String)], plan: &ImportPlan) {
+    for (key, reason) in skipped {
+        output::warn(&format!("Skipped '{key}': {reason}"));
+    }
+    for key in &plan.unknown {
+        output::warn(&format!("Skipped '{key}': not in the Key Registry"));
+    }
+    for (key, detail) in &plan.invalid {
+        output::warn(&format!("Skipped '{key}': {detail}"));
+    }
+    for (from, to) in &plan.renamed {
+        output::info(&format!(
+            "'{from}' was renamed to '{to}'; importing it as '{to}'"
+        ));
+    }
+    if !plan.unchanged.is_empty() {
+        output::info(&format!(
+            "Already set to the imported value: {}",
+            plan.unchanged.join(", ")
+        ));
+    }
+}
+
+pub fn run_config_get(key: Option<String>, host: Option<String>) -> Result<()> {
+    let config = match host {
+        Some(host) => Config::load()?.for_host(&host),
+        None => Config::load()?,
+    };
     let key = resolve_key(key, &config, "Select key to get")?;
     match config.get(&key) {
         Some(value) => println!("{}", value),
@@ -192,21 +692,365 @@ pub fn run_config_get(key: Option<String>) -> Result<()> {
     Ok(())
 }
 
-pub fn run_config_list() -> Result<()> {
+pub fn run_config_list(host: Option<String>) -> Result<()> {
     let config = Config::load()?;
-    for (key, value) in config.keys_redacted() {
-        println!("{} = {}", key, value);
+    let Some(host) = host else {
+        for (key, value) in config.keys_redacted() {
+            println!("{} = {}", key, value);
+        }
+        return Ok(());
+    };
+    let overrides = config.host_overrides(&host);
+    for (key, value) in config.for_host(&host).keys_redacted() {
+        if overrides.contains(&key) {
+            println!("{} = {}  [hosts.{}]", key, value, host);
+        } else {
+            println!("{} = {}", key, value);
+        }
+    }
+    Ok(())
+}
+
+/// Which Playbook Metas a validation checked the required keys of.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ValidationScope {
+    /// Every Playbook Meta.
+    All,
+    /// The apps named with `--app`.
+    Apps,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Problem {
+    /// A Playbook Meta requires the key; config leaves it unset.
+    Missing,
+    /// The value's secret backend failed.
+    Unresolvable,
+    /// The (resolved) value breaks its Key Registry entry.
+    Invalid,
+    /// The key is a former name of a registry key.
+    Renamed,
+    /// The registry deprecates the key.
+    Deprecated,
+    /// The key is not in the Key Registry: a typo or a stale key.
+    Unknown,
+}
+
+impl Problem {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Missing => "missing",
+            Self::Unresolvable => "unresolvable",
+            Self::Invalid => "invalid",
+            Self::Renamed => "renamed",
+            Self::Deprecated => "deprecated",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct KeyIssue {
+    key: String,
+    problem: Problem,
+    detail: String,
+    /// The apps whose Playbook Meta requires a missing key.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    apps: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ValidationReport {
+    scope: ValidationScope,
+    apps: Vec<String>,
+    checked_keys: usize,
+    issues: Vec<KeyIssue>,
+}
+
+#[derive(Tabled)]
+struct IssueRow<'a> {
+    #[tabled(rename = "KEY")]
+    key: &'a str,
+    #[tabled(rename = "PROBLEM")]
+    problem: &'static str,
+    #[tabled(rename = "DETAIL")]
+    detail: &'a str,
+    #[tabled(rename = "APPS")]
+    apps: String,
+}
+
+/// Check `config.toml` the way every deploy would, all at once: the
+/// required keys of each Playbook Meta in scope, every secret backend, every
+/// value against the Key Registry, and keys the registry does not know.
+/// Exits like a Backup Verdict: 0 valid, 1 problems found, 2 could not check.
+pub fn run_config_validate(args: ValidateArgs) -> i32 {
+    let output = args.output;
+    let result = validation_report(&args).and_then(|report| {
+        render_validation(&report, output)?;
+        Ok(report)
+    });
+    validation_exit_code(result)
+}
+
+fn validation_exit_code(result: Result<ValidationReport>) -> i32 {
+    match result {
+        Ok(report) => i32::from(!report.issues.is_empty()),
+        Err(e) => {
+            eprintln!("✗ {e:#}");
+            2
+        }
+    }
+}
+
+fn validation_report(args: &ValidateArgs) -> Result<ValidationReport> {
+    let config = Config::load_quiet()?;
+    let assets = AnsibleAssets::prepare()?;
+    let registry = KeyRegistry::load(&assets.ansible_dir().join("keys.yml"))?;
+    let metas = load_all_metas(&assets.playbooks_dir())?;
+    let (scope, metas) = metas_in_scope(metas, args)?;
+    let mut hosts: Vec<String> = HostManager::load_hosts()?
+        .into_iter()
+        .map(|host| host.name)
+        .chain(config.override_hosts())
+        .collect();
+    hosts.sort();
+    hosts.dedup();
+    Ok(ValidationReport {
+        scope,
+        apps: metas.iter().map(|(app, _)| app.clone()).collect(),
+        checked_keys: config.keys().len(),
+        issues: validate_config(&config, &registry, &metas, &hosts),
+    })
+}
+
+fn metas_in_scope(
+    mut metas: Vec<(String, PlaybookMeta)>,
+    args: &ValidateArgs,
+) -> Result<(ValidationScope, Vec<(String, PlaybookMeta)>)> {
+    metas.sort_by(|a, b| a.0.cmp(&b.0));
+    if args.apps.is_empty() {
+        return Ok((ValidationScope::All, metas));
+    }
+    if let Some(unknown) = args
+        .apps
+        .iter()
+        .find(|app| !metas.iter().any(|(name, _)| name == *app))
+    {
+        eyre::bail!("Unknown app '{unknown}': no {unknown}.meta.yml in the playbooks");
+    }
+    metas.retain(|(app, _)| args.apps.contains(app));
+    Ok((ValidationScope::Apps, metas))
+}
+
+/// Check `config` as each of `hosts` sees it: a required key is missing
+/// only for the Hosts whose view leaves it unset, and every `[hosts.<name>]`
+/// override is checked like a base key, reported as `hosts.<name>.<key>`.
+/// With no Hosts, the base keys alone are checked.
+fn validate_config(
+    config: &Config,
+    registry: &KeyRegistry,
+    metas: &[(String, PlaybookMeta)],
+    hosts: &[String],
+) -> Vec<KeyIssue> {
+    let views: Vec<(&str, Config)> = hosts
+        .iter()
+        .map(|host| (host.as_str(), config.for_host(host)))
+        .collect();
+    let unset = |view: &Config, key: &str| view.get(key).is_none_or(|v| v.trim().is_empty());
+    let mut missing: BTreeMap<&str, (Vec<String>, Vec<&str>)> = BTreeMap::new();
+    for (app, meta) in metas {
+        for key in &meta.required_keys {
+            if registry.default_for(key).is_some() {
+                continue;
+            }
+            let lacking: Vec<&str> = views
+                .iter()
+                .filter(|(_, view)| unset(view, key))
+                .map(|(host, _)| *host)
+                .collect();
+            let is_missing = if views.is_empty() {
+                unset(config, key)
+            } else {
+                !lacking.is_empty()
+            };
+            if is_missing {
+                let (apps, on) = missing.entry(key).or_default();
+                apps.push(app.clone());
+                *on = lacking;
+            }
+        }
+    }
+    let mut issues: Vec<KeyIssue> = missing
+        .into_iter()
+        .map(|(key, (apps, on))| KeyIssue {
+            key: key.to_string(),
+            problem: Problem::Missing,
+            detail: if on.len() == views.len() {
+                "not set".to_string()
+            } else {
+                format!("not set for {}", on.join(", "))
+            },
+            apps,
+        })
+        .collect();
+
+    for key in config.keys() {
+        if !config.is_section(&key)
+            && let Some(raw) = config.get(&key)
+        {
+            check_value(registry, &key, key.clone(), &raw, &mut issues);
+        }
+    }
+    for host in config.override_hosts() {
+        let view = config.for_host(&host);
+        for key in config.host_overrides(&host) {
+            if !view.is_section(&key)
+                && let Some(raw) = view.get(&key)
+            {
+                let label = format!("hosts.{host}.{key}");
+                check_value(registry, &key, label, &raw, &mut issues);
+            }
+        }
+    }
+    issues.sort_by(|a, b| a.key.cmp(&b.key).then(a.problem.cmp(&b.problem)));
+    issues
+}
+
+/// Check one set value against the Key Registry, reporting it as `label`.
+fn check_value(
+    registry: &KeyRegistry,
+    key: &str,
+    label: String,
+    raw: &str,
+    issues: &mut Vec<KeyIssue>,
+) {
+    let issue = |problem, detail| KeyIssue {
+        key: label.clone(),
+        problem,
+        detail,
+        apps: Vec::new(),
+    };
+    if let Some(to) = registry.renamed_to(key) {
+        issues.push(issue(
+            Problem::Renamed,
+            format!("renamed to {to}; run `auberge config migrate`"),
+        ));
+        return;
+    }
+    let Some(entry) = registry.get(key) else {
+        issues.push(issue(
+            Problem::Unknown,
+            "not in the Key Registry".to_string(),
+        ));
+        return;
+    };
+    if let Some(note) = &entry.deprecated {
+        issues.push(issue(Problem::Deprecated, note.clone()));
+    }
+    match resolve_value(raw) {
+        Err(e) => issues.push(issue(Problem::Unresolvable, format!("{e:#}"))),
+        Ok(value) => {
+            if let Err(reason) = entry.check(&value) {
+                issues.push(issue(Problem::Invalid, reason));
+            }
+        }
+    }
+}
+
+fn render_validation(report: &ValidationReport, format: OutputFormat) -> Result<()> {
+    if matches!(format, OutputFormat::Json) {
+        println!("{}", serde_json::to_string_pretty(report)?);
+        return Ok(());
+    }
+    let scope = match report.scope {
+        ValidationScope::All => "every playbook",
+        ValidationScope::Apps => "selected apps",
+    };
+    output::info(&format!(
+        "Checked {} config keys and the required keys of {} ({scope})",
+        report.checked_keys,
+        report.apps.len()
+    ));
+    if report.issues.is_empty() {
+        output::success("Config is valid");
+        return Ok(());
+    }
+    let rows: Vec<IssueRow> = report
+        .issues
+        .iter()
+        .map(|issue| IssueRow {
+            key: &issue.key,
+            problem: issue.problem.as_str(),
+            detail: &issue.detail,
+            apps: if issue.apps.is_empty() {
+                "-".to_string()
+            } else {
+                issue.apps.join(", ")
+            },
+        })
+        .collect();
+    output::print_table(&rows);
+    output::warn(&format!("{} config problem(s) found", report.issues.len()));
+    Ok(())
+}
+
+pub fn run_config_migrate() -> Result<()> {
+    let mut config = Config::load_quiet()?;
+    let migrations = config.migrate(&KeyRegistry::bundled()?)?;
+    if migrations.is_empty() {
+        output::success("config.toml already uses current key names");
+        return Ok(());
+    }
+    for migration in migrations {
+        match migration {
+            KeyMigration::Renamed { from, to } => {
+                output::success(&format!("Renamed '{from}' to '{to}'"));
+            }
+            KeyMigration::Dropped { from, to } => output::success(&format!(
+                "Removed '{from}': '{to}' already holds the same value"
+            )),
+            KeyMigration::Conflict { from, to } => output::warn(&format!(
+                "Left '{from}' in place: '{to}' is already set to a different value; remove the one you don't want"
+            )),
+            KeyMigration::Deprecated { key, note } => {
+                output::warn(&format!("'{key}' is deprecated: {note}"));
+            }
+        }
     }
     Ok(())
 }
 
-pub fn run_config_remove(key: Option<String>) -> Result<()> {
+pub fn run_config_remove(key: Option<String>, host: Option<String>) -> Result<()> {
     let mut config = Config::load()?;
-    let key = resolve_key(key, &config, "Select key to remove")?;
-    if config.remove(&key)? {
-        output::success(&format!("Removed '{}'", key));
+    let Some(host) = host else {
+        let key = resolve_key(key, &config, "Select key to remove")?;
+        if config.remove(&key)? {
+            output::success(&format!("Removed '{}'", key));
+        } else {
+            eyre::bail!("Key '{}' not found", key);
+        }
+        return Ok(());
+    };
+    let key = match key {
+        Some(k) => k,
+        None => {
+            let overrides = config.host_overrides(&host);
+            if overrides.is_empty() {
+                eyre::bail!("No overrides for host '{}'", host);
+            }
+            select_item(
+                &overrides,
+                |s: &String| s.clone(),
+                key_choice("Select override to remove"),
+            )?
+        }
+    };
+    if config.remove_for_host(&host, &key)? {
+        output::success(&format!("Removed '{}' from [hosts.{}]", key, host));
     } else {
-        eyre::bail!("Key '{}' not found", key);
+        eyre::bail!("Key '{}' not overridden for host '{}'", key, host);
     }
     Ok(())
 }
@@ -252,6 +1096,7 @@ keys:
   paperless_admin_password:
     secret: true
     doc: "Paperless admin password"
+    generate: { alnum: 32 }
 "#;
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("keys.yml");
@@ -272,7 +1117,7 @@ keys:
     fn test_build_scaffold_without_playbooks_includes_all_keys() {
         let (_keys_dir, registry) = fixture_registry();
         let dir = fixture_playbooks_dir(&[]);
-        let scaffold = build_scaffold(&registry, &[], dir.path()).unwrap();
+        let scaffold = build_scaffold(&registry, &[], dir.path(), false).unwrap();
         assert!(scaffold.contains("admin_user_name"));
         assert!(scaffold.contains("domain"));
         assert!(scaffold.contains("tailscale_authkey"));
@@ -293,6 +1138,7 @@ keys:
             &registry,
             &["infra".to_string(), "apps".to_string()],
             dir.path(),
+            false,
         )
         .unwrap();
         assert!(scaffold.contains("admin_user_name"));
@@ -301,11 +1147,28 @@ keys:
         assert!(!scaffold.contains("paperless_admin_password"));
     }
 
+    #[test]
+    fn test_build_scaffold_generating_secrets_fills_keys_with_generators() {
+        let (_keys_dir, registry) = fixture_registry();
+        let dir = fixture_playbooks_dir(&[(
+            "paperless",
+            "required_keys: [paperless_admin_password, domain]\n",
+        )]);
+        let scaffold =
+            build_scaffold(&registry, &["paperless".to_string()], dir.path(), true).unwrap();
+        assert!(scaffold.contains("domain = \"\""));
+        let line = scaffold
+            .lines()
+            .find(|l| l.starts_with("paperless_admin_password = "))
+            .unwrap();
+        assert_eq!(line.len(), "paperless_admin_password = \"\"".len() + 32);
+    }
+
     #[test]
     fn test_build_scaffold_with_unknown_playbook_errors() {
         let (_keys_dir, registry) = fixture_registry();
         let dir = fixture_playbooks_dir(&[]);
-        let err = build_scaffold(&registry, &["nope".to_string()], dir.path()).unwrap_err();
+        let err = build_scaffold(&registry, &["nope".to_string()], dir.path(), false).unwrap_err();
         assert!(err.to_string().contains("Unknown playbook 'nope'"));
     }
 
@@ -313,7 +1176,7 @@ keys:
     fn test_build_scaffold_with_playbook_having_empty_required_keys_emits_empty() {
         let (_keys_dir, registry) = fixture_registry();
         let dir = fixture_playbooks_dir(&[("solo", "required_keys: []\n")]);
-        let scaffold = build_scaffold(&registry, &["solo".to_string()], dir.path()).unwrap();
+        let scaffold = build_scaffold(&registry, &["solo".to_string()], dir.path(), false).unwrap();
         assert!(scaffold.is_empty());
     }
 
@@ -345,6 +1208,26 @@ keys:
         assert_eq!(fs::read_to_string(&path).unwrap(), "fresh");
     }
 
+    #[test]
+    fn test_write_init_files_refuses_an_existing_schema_without_force() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let schema_path = dir.path().join(SCHEMA_FILE);
+        fs::write(&schema_path, "{}").unwrap();
+
+        let err = write_init_files(&path, "domain = \"\"\n", "{\"new\": 1}", false).unwrap_err();
+        assert!(err.to_string().contains("Refusing to overwrite"));
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(&schema_path).unwrap(), "{}");
+
+        write_init_files(&path, "domain = \"\"\n", "{\"new\": 1}", true).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("#:schema ./{SCHEMA_FILE}\n\ndomain = \"\"\n")
+        );
+        assert_eq!(fs::read_to_string(&schema_path).unwrap(), "{\"new\": 1}");
+    }
+
     #[test]
     fn test_sorted_registry_keys_returns_all_keys_alphabetically() {
         let (_keys_dir, registry) = fixture_registry();
@@ -367,4 +1250,264 @@ keys:
         write_scaffold(&path, "domain = \"\"\n", false).unwrap();
         assert!(path.exists());
     }
+
+    fn typed_registry() -> (tempfile::TempDir, KeyRegistry) {
+        let yaml = r#"
+keys:
+  domain:
+    secret: false
+    doc: "Primary domain"
+  ssh_port:
+    secret: false
+    doc: "SSH port"
+    type: int
+  gokapi_subdomain:
+    secret: false
+    doc: "Gokapi subdomain"
+    default: share
+  restic_password:
+    secret: true
+    doc: "Restic password"
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.yml");
+        fs::write(&path, yaml).unwrap();
+        let registry = KeyRegistry::load(&path).unwrap();
+        (dir, registry)
+    }
+
+    fn meta(app: &str, required: &str) -> (String, PlaybookMeta) {
+        let meta = serde_yaml::from_str(&format!("required_keys: [{required}]")).unwrap();
+        (app.to_string(), meta)
+    }
+
+    fn problems(issues: &[KeyIssue]) -> Vec<(&str, Problem)> {
+        issues
+            .iter()
+            .map(|issue| (issue.key.as_str(), issue.problem))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_config_passes_a_complete_config() {
+        let (_dir, registry) = typed_registry();
+        let config = Config::from_toml_str(
+            r#"
+            domain = "example.com"
+            ssh_port = 22022
+            [bichon]
+            hosts = {}
+        "#,
+        )
+        .unwrap();
+        let metas = [meta("gokapi", "domain, gokapi_subdomain")];
+        assert!(validate_config(&config, &registry, &metas, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_validate_config_reports_each_problem_against_its_key() {
+        let (_dir, registry) = typed_registry();
+        let config = Config::from_toml_str(
+            r#"
+            ssh_port = "22o22"
+            restic_password = "env:AUBERGE_TEST_VALIDATE_UNSET"
+            domian = "example.com"
+        "#,
+        )
+        .unwrap();
+        let metas = [meta("apps", "domain"), meta("gokapi", "domain")];
+
+        let issues = validate_config(&config, &registry, &metas, &[]);
+
+        assert_eq!(
+            problems(&issues),
+            vec![
+                ("domain", Problem::Missing),
+                ("domian", Problem::Unknown),
+                ("restic_password", Problem::Unresolvable),
+                ("ssh_port", Problem::Invalid),
+            ]
+        );
+        assert_eq!(issues[0].apps, vec!["apps", "gokapi"]);
+        assert_eq!(
+            issues[2].detail,
+            "env backend: AUBERGE_TEST_VALIDATE_UNSET is not set"
+        );
+        assert_eq!(issues[3].detail, "expected an integer, got `22o22`");
+    }
+
+    #[test]
+    fn test_validate_config_flags_renamed_and_deprecated_keys() {
+        let yaml = r#"
+keys:
+  domain:
+    secret: false
+    doc: "Primary domain"
+    replaces: [base_domain]
+  bichon_subdomain:
+    secret: false
+    doc: "Bichon subdomain"
+    deprecated: "remove it"
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.yml");
+        fs::write(&path, yaml).unwrap();
+        let registry = KeyRegistry::load(&path).unwrap();
+        let config = Config::from_toml_str(
+            r#"
+            base_domain = "example.com"
+            bichon_subdomain = "mail"
+        "#,
+        )
+        .unwrap();
+
+        let issues = validate_config(&config, &registry, &[meta("apps", "domain")], &[]);
+
+        assert_eq!(
+            problems(&issues),
+            vec![
+                ("base_domain", Problem::Renamed),
+                ("bichon_subdomain", Problem::Deprecated),
+                ("domain", Problem::Missing),
+            ]
+        );
+        assert_eq!(
+            issues[0].detail,
+            "renamed to domain; run `auberge config migrate`"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_validate_config_checks_the_resolved_value() {
+        let (_dir, registry) = typed_registry();
+        let config = Config::from_toml_str(r#"ssh_port = "!echo twenty""#).unwrap();
+        let issues = validate_config(&config, &registry, &[], &[]);
+        assert_eq!(problems(&issues), vec![("ssh_port", Problem::Invalid)]);
+    }
+
+    #[test]
+    fn test_validate_config_checks_each_host_view_and_its_overrides() {
+        let (_dir, registry) = typed_registry();
+        let config = Config::from_toml_str(
+            r#"
+            [hosts.prod]
+            domain = "example.com"
+            ssh_port = "22o22"
+            [hosts.lab]
+            domian = "lab.example.com"
+        "#,
+        )
+        .unwrap();
+        let hosts = ["lab".to_string(), "prod".to_string()];
+
+        let issues = validate_config(&config, &registry, &[meta("apps", "domain")], &hosts);
+
+        assert_eq!(
+            problems(&issues),
+            vec![
+                ("domain", Problem::Missing),
+                ("hosts.lab.domian", Problem::Unknown),
+                ("hosts.prod.ssh_port", Problem::Invalid),
+            ]
+        );
+        assert_eq!(issues[0].detail, "not set for lab");
+    }
+
+    fn validate_args(apps: &[&str]) -> ValidateArgs {
+        ValidateArgs {
+            apps: apps.iter().map(|a| a.to_string()).collect(),
+            output: OutputFormat::Human,
+        }
+    }
+
+    fn apps_of(metas: &[(String, PlaybookMeta)]) -> Vec<&str> {
+        metas.iter().map(|(app, _)| app.as_str()).collect()
+    }
+
+    #[test]
+    fn test_metas_in_scope_defaults_to_every_meta() {
+        let metas = vec![meta("paperless", ""), meta("immich", "")];
+        let (scope, scoped) = metas_in_scope(metas, &validate_args(&[])).unwrap();
+        assert_eq!(scope, ValidationScope::All);
+        assert_eq!(apps_of(&scoped), vec!["immich", "paperless"]);
+    }
+
+    #[test]
+    fn test_metas_in_scope_narrows_to_named_apps_and_rejects_unknown_ones() {
+        let metas = || vec![meta("paperless", ""), meta("immich", "")];
+        let (scope, scoped) = metas_in_scope(metas(), &validate_args(&["immich"])).unwrap();
+        assert_eq!(scope, ValidationScope::Apps);
+        assert_eq!(apps_of(&scoped), vec!["immich"]);
+
+        let err = metas_in_scope(metas(), &validate_args(&["imich"])).unwrap_err();
+        assert!(err.to_string().contains("Unknown app 'imich'"));
+    }
+
+    #[test]
+    fn test_validation_exit_code_follows_the_verdict_convention() {
+        let report = |issues| ValidationReport {
+            scope: ValidationScope::All,
+            apps: Vec::new(),
+            checked_keys: 1,
+            issues,
+        };
+        assert_eq!(validation_exit_code(Ok(report(Vec::new()))), 0);
+        let unknown = KeyIssue {
+            key: "domian".to_string(),
+            problem: Problem::Unknown,
+            detail: "not in the Key Registry".to_string(),
+            apps: Vec::new(),
+        };
+        assert_eq!(validation_exit_code(Ok(report(vec![unknown]))), 1);
+        assert_eq!(validation_exit_code(Err(eyre::eyre!("no config"))), 2);
+    }
+
+    #[test]
+    fn test_rotation_apps_are_the_metas_requiring_the_key() {
+        let metas = [
+            meta("immich", "immich_db_password, domain"),
+            meta("paperless", "paperless_db_password"),
+            meta("apps", "domain"),
+        ];
+        let deployable = ["immich".to_string(), "paperless".to_string()];
+        assert_eq!(
+            rotation_apps("immich_db_password", &metas, &deployable).unwrap(),
+            vec!["immich"]
+        );
+        let err = rotation_apps("domain", &metas, &deployable).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("'domain' is required by the apps playbook(s)")
+        );
+        let err = rotation_apps("restic_password", &metas, &deployable).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("No Playbook Meta requires 'restic_password'")
+        );
+    }
+
+    #[test]
+    fn test_hosts_running_lists_other_hosts_with_a_successful_deploy() {
+        let record = |host: &str, app: &str, success: bool| -> DeployRecord {
+            serde_json::from_str(&format!(
+                r#"{{"id": "x_{host}", "source": "deploy", "host": "{host}",
+                    "apps": ["{app}"], "started_at": "2026-01-01T00:00:00Z",
+                    "finished_at": "2026-01-01T00:01:00Z", "success": {success}}}"#
+            ))
+            .unwrap()
+        };
+        let records = [
+            record("prod", "immich", true),
+            record("lab", "immich", true),
+            record("old", "immich", false),
+            record("media", "navidrome", true),
+        ];
+        assert_eq!(
+            hosts_running(&records, &["immich".to_string()], "prod")
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["lab"]
+        );
+    }
 }
@@ -1,28 +1,63 @@
 use crate::ansible_assets::AnsibleAssets;
-use crate::config::Config;
+use crate::commands::backup::{
+    create_pre_upgrade_backup, finish_staged_restore, restore_staged_app, staged_backup_ids,
+};
+use crate::config::{Config, Preflight};
 use crate::hosts::HOST_FLAG;
-use crate::output;
-use crate::playbook_meta::{app_memory_vars, app_version_vars};
+use crate::key_registry::KeyRegistry;
+use crate::output::{self, OutputFormat};
+use crate::playbook_meta::{
+    app_memory_vars, app_version_var, app_version_vars, declared_app_versions,
+};
 use crate::prompt::{confirm, select_multi};
 use crate::services::ansible_runner::{InventoryHost, run_playbook};
+use crate::services::backup::recipe::discover_backuppable_apps;
 use crate::services::dependency_resolver::{
     PlaybookRun, get_app_names, get_infrastructure_role_names, resolve_tags_to_playbook_runs,
 };
+use crate::services::deploy_journal::{
+    DeployEntry, DeployJournal, DeployRecord, DeploySource, HistoryFilter, PreUpgradeBackup,
+    RecordedRun, journaled, last_deployed_version, rollback_target,
+};
 use crate::services::dns_verify::{
     AppVerifyConfig, HickoryLookup, app_verify_config, format_dns_error, verify_a_record,
 };
-use crate::services::inventory::{Host, hosts_ignoreip_var, select_or_arg};
-use clap::Args;
-use eyre::Result;
+use crate::services::inventory::{Host, get_host, get_hosts, hosts_ignoreip_var, select_or_arg};
+use crate::services::progress::{Progress, SilentProgress, TerminalProgress};
+use crate::signal;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use clap::{Args, Subcommand};
+use eyre::{Result, WrapErr};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tabled::Tabled;
 
 const ALL_ENTRY: &str = "[all]";
 
 #[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct DeployCmd {
+    #[command(subcommand)]
+    pub command: Option<DeployCommands>,
     #[arg(help = "App(s) to deploy (e.g. paperless freshrss)")]
     pub apps: Vec<String>,
-    #[arg(short = 'H', long, help = "Target host")]
-    pub host: Option<String>,
+    #[arg(short = 'H', long, help = "Target host (repeat to deploy to several)")]
+    pub host: Vec<String>,
+    #[arg(
+        long,
+        conflicts_with = "host",
+        help = "Deploy to every host carrying this tag in hosts.toml"
+    )]
+    pub tag: Option<String>,
+    #[arg(
+        short = 'j',
+        long,
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u16).range(1..),
+        help = "Maximum number of hosts deployed at once"
+    )]
+    pub jobs: u16,
     #[arg(short = 'C', long, help = "Dry-run mode (ansible check mode)")]
     pub check: bool,
     #[arg(long, help = "Deploy all apps", conflicts_with = "apps")]
@@ -34,10 +69,112 @@ pub struct DeployCmd {
         help = "Verify public DNS after each app's playbook run (queries 1.1.1.1)"
     )]
     pub verify_public_dns: bool,
+    #[arg(
+        long,
+        help = "Skip the automatic backup of apps whose App Version changes"
+    )]
+    pub skip_backup: bool,
 }
 
-fn select_host(host_arg: Option<String>) -> Result<Host> {
-    select_or_arg(host_arg, HOST_FLAG)
+#[derive(Subcommand)]
+pub enum DeployCommands {
+    #[command(
+        visible_alias = "hi",
+        about = "List past deploys recorded in the deploy journal"
+    )]
+    History {
+        #[arg(short = 'H', long, help = "Only deploys to this host")]
+        host: Option<String>,
+        #[arg(short, long, help = "Only deploys that included this app")]
+        app: Option<String>,
+        #[arg(long, help = "Only failed deploys")]
+        failed: bool,
+        #[arg(
+            short = 'n',
+            long,
+            default_value_t = 20,
+            help = "Maximum number of deploys listed"
+        )]
+        limit: usize,
+        #[arg(
+            short = 'o',
+            long,
+            value_enum,
+            default_value = "human",
+            help = "Output format"
+        )]
+        output: OutputFormat,
+    },
+    #[command(
+        visible_alias = "rb",
+        about = "Redeploy an app with the App Version it ran before the current one"
+    )]
+    Rollback {
+        #[arg(help = "App to roll back")]
+        app: String,
+        #[arg(short = 'H', long, help = "Target host")]
+        host: Option<String>,
+        #[arg(
+            long,
+            help = "Also restore the app's data from the newest local backup taken before the upgrade"
+        )]
+        restore_backup: bool,
+        #[arg(
+            long,
+            requires = "restore_backup",
+            help = "Restore this backup ID instead of the newest pre-upgrade one"
+        )]
+        backup_id: Option<String>,
+        #[arg(short = 'C', long, help = "Dry-run mode (ansible check mode)")]
+        check: bool,
+        #[arg(short = 'f', long, help = "Skip confirmation prompt")]
+        force: bool,
+    },
+    #[command(visible_alias = "sh", about = "Show one recorded deploy in full")]
+    Show {
+        #[arg(
+            default_value = "latest",
+            help = "Deploy ID from `auberge deploy history`, or `latest`"
+        )]
+        id: String,
+        #[arg(
+            short = 'o',
+            long,
+            value_enum,
+            default_value = "human",
+            help = "Output format"
+        )]
+        output: OutputFormat,
+    },
+}
+
+/// Resolve the Hosts a deploy targets: every Host carrying `tag`, else each
+/// `-H` in the order given (duplicates dropped), else one picked interactively.
+fn select_hosts(host_args: Vec<String>, tag: Option<String>) -> Result<Vec<Host>> {
+    if let Some(tag) = tag {
+        let hosts = get_hosts(Some(&tag), None)?;
+        if hosts.is_empty() {
+            eyre::bail!("No hosts tagged '{tag}'. Tag one with `auberge host edit`.");
+        }
+        return Ok(hosts);
+    }
+
+    if host_args.is_empty() {
+        return Ok(vec![select_or_arg(None, HOST_FLAG)?]);
+    }
+
+    dedupe_preserving_order(host_args)
+        .iter()
+        .map(|name| get_host(name, None))
+        .collect()
+}
+
+fn dedupe_preserving_order(names: Vec<String>) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    names
+        .into_iter()
+        .filter(|name| seen.insert(name.clone()))
+        .collect()
 }
 
 fn select_apps(available: &[String]) -> Result<Vec<String>> {
@@ -97,16 +234,21 @@ fn validate_apps(requested: &[String], available: &[String], infra_roles: &[Stri
     eyre::bail!("{}", messages.join("\n"));
 }
 
-fn show_execution_plan(runs: &[PlaybookRun], host: &Host, check: bool) -> Result<()> {
+fn show_execution_plan(runs: &[PlaybookRun], hosts: &[Host], check: bool) -> Result<()> {
     eprintln!();
     if check {
         output::info("Execution plan (DRY RUN):");
     } else {
         output::info("Execution plan:");
     }
+    let label = if hosts.len() == 1 { "Host" } else { "Hosts" };
     output::info(&format!(
-        "  Host: {} ({})",
-        host.name, host.vars.ansible_host
+        "  {label}: {}",
+        hosts
+            .iter()
+            .map(|h| format!("{} ({})", h.name, h.vars.ansible_host))
+            .collect::<Vec<_>>()
+            .join(", ")
     ));
     for run in runs {
         let name = run
@@ -167,6 +309,7 @@ fn run_dns_checks_for_run(
     config: &Config,
     host: &Host,
     verify_public: bool,
+    prefix: &str,
 ) -> Result<()> {
     if !run.is_apps() || run.tags.is_empty() {
         return Ok(());
@@ -185,7 +328,7 @@ fn run_dns_checks_for_run(
 
         let kind = if vc.is_tailnet() { "tailnet" } else { "public" };
         output::info(&format!(
-            "DNS check ({kind}): {} → {} via {}",
+            "{prefix}DNS check ({kind}): {} → {} via {}",
             tag, vc.fqdn, vc.resolver_ip
         ));
 
@@ -201,7 +344,7 @@ fn run_dns_checks_for_run(
         };
         match verify_a_record(&lookup, &vc.fqdn, &vc.expected_ip) {
             Ok(None) => {
-                output::success(&format!("DNS OK: {} → {}", vc.fqdn, vc.expected_ip));
+                output::success(&format!("{prefix}DNS OK: {} → {}", vc.fqdn, vc.expected_ip));
             }
             Ok(Some(failure)) => {
                 errors.push(format_dns_error(
@@ -246,8 +389,74 @@ pub fn run_deploy(cmd: DeployCmd) -> Result<()> {
         cmd.apps.clone()
     };
 
-    let host = select_host(cmd.host)?;
+    let hosts = select_hosts(cmd.host, cmd.tag)?;
+    let mut plan = resolve_plan(
+        apps,
+        &hosts,
+        Config::load()?,
+        cmd.check,
+        cmd.verify_public_dns,
+    )?;
+    plan.back_up_upgrades = !cmd.check && !cmd.skip_backup;
+
+    show_execution_plan(&plan.runs, &hosts, cmd.check)?;
+    warn_apps_prerequisites(&plan.runs);
+    confirm_deploy(cmd.force)?;
+
+    if let [host] = hosts.as_slice() {
+        let mut progress = TerminalProgress::new("");
+        journaled(plan.journal_entry(host), |entry| {
+            entry.pre_upgrade_backup = back_up_upgrades(&plan, host, "")?;
+            deploy_host(&plan, host, "", &mut progress)
+        })?;
+        output::success("Deployment completed successfully");
+        return Ok(());
+    }
 
+    // A backup draws its own terminal progress, so backups run one Host at
+    // a time ahead of the fan-out instead of interleaving inside it.
+    let backed_up: Vec<(&Host, Result<Option<PreUpgradeBackup>, String>)> = hosts
+        .iter()
+        .map(|host| {
+            let backup = back_up_upgrades(&plan, host, &format!("{}: ", host.name));
+            (host, backup.map_err(|e| format!("{e:#}")))
+        })
+        .collect();
+
+    let outcomes = fan_out(&backed_up, usize::from(cmd.jobs), |(host, backup)| {
+        let started = Instant::now();
+        let prefix = format!("{}: ", host.name);
+        let result = journaled(plan.journal_entry(host), |entry| {
+            entry.pre_upgrade_backup = backup.clone().map_err(|e| eyre::eyre!(e))?;
+            deploy_host(&plan, host, &prefix, &mut SilentProgress)
+        });
+        HostOutcome {
+            host: host.name.clone(),
+            elapsed: started.elapsed(),
+            error: result.err().map(|e| e.to_string()),
+        }
+    });
+
+    render_host_outcomes(&outcomes);
+
+    let failed = outcomes.iter().filter(|o| o.error.is_some()).count();
+    if failed > 0 {
+        eyre::bail!("{failed} of {} host(s) failed to deploy", outcomes.len());
+    }
+    output::success(&format!("Deployed to {} hosts", outcomes.len()));
+    Ok(())
+}
+
+/// Resolve `apps` to their playbook runs (hardening first) and validate
+/// config for every run on every Host up front, each with its own
+/// overrides, so a deploy fails before any playbook runs.
+fn resolve_plan(
+    apps: Vec<String>,
+    hosts: &[Host],
+    config: Config,
+    check: bool,
+    verify_public_dns: bool,
+) -> Result<DeployPlan> {
     let (resolved_runs, unknown_tags) = resolve_tags_to_playbook_runs(&apps)?;
 
     if !unknown_tags.is_empty() {
@@ -260,26 +469,168 @@ pub fn run_deploy(cmd: DeployCmd) -> Result<()> {
 
     let runs = prepend_hardening(resolved_runs)?;
 
-    // Validate config and build preflights for all runs upfront so we fail
-    // fast before executing any playbook.
-    let config = Config::load()?;
-    let preflights: Vec<_> = runs
-        .iter()
-        .map(|run| {
-            let name = run.path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-            let tags = if run.tags.is_empty() {
-                None
-            } else {
-                Some(run.tags.as_slice())
-            };
-            config.preflight_for(name, tags)
+    let registry = KeyRegistry::bundled()?;
+    let mut preflights = HashMap::new();
+    for host in hosts {
+        let host_config = config.for_host(&host.name);
+        let host_preflights: Vec<_> = runs
+            .iter()
+            .map(|run| {
+                let name = run.path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                let tags = if run.tags.is_empty() {
+                    None
+                } else {
+                    Some(run.tags.as_slice())
+                };
+                host_config
+                    .preflight_for(&registry, name, tags)
+                    .wrap_err_with(|| format!("Config is not ready for {}", host.name))
+            })
+            .collect::<Result<_>>()?;
+        preflights.insert(host.name.clone(), host_preflights);
+    }
+
+    let playbooks_dir = AnsibleAssets::prepare()?.playbooks_dir();
+    let backuppable = discover_backuppable_apps(&playbooks_dir)?;
+    Ok(DeployPlan {
+        source: DeploySource::Deploy,
+        backuppable: apps
+            .iter()
+            .filter(|app| backuppable.contains(app))
+            .cloned()
+            .collect(),
+        back_up_upgrades: false,
+        apps,
+        runs,
+        preflights,
+        app_versions: app_version_vars(&playbooks_dir)?,
+        memory_budgets: app_memory_vars(&playbooks_dir)?,
+        hosts_ignoreip: hosts_ignoreip_var()?,
+        config,
+        check,
+        verify_public_dns,
+    })
+}
+
+/// Everything a deploy resolves once, before the first playbook runs, and
+/// then replays unchanged against every targeted Host.
+struct DeployPlan {
+    source: DeploySource,
+    apps: Vec<String>,
+    /// The subset of `apps` with a Backup Recipe.
+    backuppable: Vec<String>,
+    /// Back up `backuppable` apps whose App Version changes before deploying.
+    back_up_upgrades: bool,
+    runs: Vec<PlaybookRun>,
+    /// One Preflight per run, for each targeted Host by name.
+    preflights: HashMap<String, Vec<Preflight>>,
+    app_versions: Vec<(String, String)>,
+    memory_budgets: Vec<(String, String)>,
+    hosts_ignoreip: (String, String),
+    config: Config,
+    check: bool,
+    verify_public_dns: bool,
+}
+
+impl DeployPlan {
+    fn journal_entry(&self, host: &Host) -> DeployEntry {
+        DeployEntry {
+            source: self.source,
+            host: host.name.clone(),
+            apps: self.apps.clone(),
+            runs: self.runs.iter().map(RecordedRun::from).collect(),
+            app_versions: self.app_versions.clone(),
+            memory_budgets: self.memory_budgets.clone(),
+            check: self.check,
+            pre_upgrade_backup: None,
+        }
+    }
+}
+
+/// An App whose declared App Version differs from the one the deploy
+/// journal last saw deployed to a Host.
+#[derive(Debug, PartialEq)]
+struct VersionChange {
+    app: String,
+    from: String,
+    to: String,
+}
+
+/// The apps among `apps` that would change App Version on `host`. An app
+/// the journal never saw deployed there is not a change: there is nothing
+/// on the Host yet for a migration to rewrite.
+fn version_changes(
+    apps: &[String],
+    app_versions: &[(String, String)],
+    records: &[DeployRecord],
+    host: &str,
+) -> Vec<VersionChange> {
+    apps.iter()
+        .filter_map(|app| {
+            let var = app_version_var(app);
+            let to = app_versions
+                .iter()
+                .find(|(name, _)| name == &var)?
+                .1
+                .as_str();
+            let from = last_deployed_version(records, host, app, &var)?;
+            (from != to).then(|| VersionChange {
+                app: app.clone(),
+                from: from.to_string(),
+                to: to.to_string(),
+            })
         })
-        .collect::<Result<_>>()?;
+        .collect()
+}
 
-    show_execution_plan(&runs, &host, cmd.check)?;
-    warn_apps_prerequisites(&runs);
-    confirm_deploy(cmd.force)?;
+/// Before a deploy changes an App Version — and so may run migrations
+/// there is no undoing — take a staged backup of every app it changes.
+/// A failed backup stops the deploy to that Host.
+fn back_up_upgrades(
+    plan: &DeployPlan,
+    host: &Host,
+    prefix: &str,
+) -> Result<Option<PreUpgradeBackup>> {
+    if !plan.back_up_upgrades || plan.backuppable.is_empty() {
+        return Ok(None);
+    }
+    let records = DeployJournal::open()?.records()?;
+    let changes = version_changes(&plan.backuppable, &plan.app_versions, &records, &host.name);
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    output::info(&format!(
+        "{prefix}App Version change on {}: {}; backing up first",
+        host.name,
+        changes
+            .iter()
+            .map(|c| format!("{} {} → {}", c.app, c.from, c.to))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+    let apps: Vec<String> = changes.into_iter().map(|c| c.app).collect();
+    let id = create_pre_upgrade_backup(&host.name, &apps).map_err(|e| {
+        eyre::eyre!("{e:#}\nNot deploying without it; pass --skip-backup to deploy anyway")
+    })?;
+    output::success(&format!(
+        "{prefix}Pre-upgrade backup {id} taken; restore with `auberge backup restore {id} -H {} -a {}`",
+        host.name,
+        apps.join(",")
+    ));
 
+    Ok(Some(PreUpgradeBackup { id, apps }))
+}
+
+/// Run every playbook in `plan` against one Host, stopping at the first
+/// failure. `prefix` names the Host on each status line when several Hosts
+/// deploy at once and their lines interleave.
+fn deploy_host(
+    plan: &DeployPlan,
+    host: &Host,
+    prefix: &str,
+    progress: &mut dyn Progress,
+) -> Result<()> {
     let inventory_host = InventoryHost {
         name: host.name.clone(),
         address: host.vars.ansible_host.clone(),
@@ -287,19 +638,21 @@ pub fn run_deploy(cmd: DeployCmd) -> Result<()> {
         user: host.vars.bootstrap_user.clone(),
         groups: host.groups.clone(),
     };
-
-    let playbooks_dir = AnsibleAssets::prepare()?.playbooks_dir();
-    let app_versions = app_version_vars(&playbooks_dir)?;
-    let memory_budgets = app_memory_vars(&playbooks_dir)?;
-    let hosts_ignoreip = hosts_ignoreip_var()?;
-    let extra_vars: Vec<(&str, &str)> = app_versions
+    let extra_vars: Vec<(&str, &str)> = plan
+        .app_versions
         .iter()
-        .chain(memory_budgets.iter())
-        .chain(std::iter::once(&hosts_ignoreip))
+        .chain(plan.memory_budgets.iter())
+        .chain(std::iter::once(&plan.hosts_ignoreip))
         .map(|(name, value)| (name.as_str(), value.as_str()))
         .collect();
 
-    for (run, preflight) in runs.iter().zip(preflights.iter()) {
+    let preflights = plan
+        .preflights
+        .get(&host.name)
+        .ok_or_else(|| eyre::eyre!("No preflight resolved for {}", host.name))?;
+    let config = plan.config.for_host(&host.name);
+
+    for (run, preflight) in plan.runs.iter().zip(preflights) {
         let playbook_name = run
             .path
             .file_stem()
@@ -312,24 +665,23 @@ pub fn run_deploy(cmd: DeployCmd) -> Result<()> {
         };
 
         output::info(&format!(
-            "Running {} on {}{}",
+            "{prefix}Running {} on {}{}",
             playbook_name,
             host.name,
             run_tags.map_or(String::new(), |t| format!(" (tags: {})", t.join(", ")))
         ));
 
-        let mut progress = crate::services::progress::TerminalProgress::new("");
         let result = run_playbook(
             preflight,
             &run.path,
             &inventory_host,
-            cmd.check,
+            plan.check,
             run_tags,
             None,
             Some(&extra_vars),
             false,
             false,
-            &mut progress,
+            progress,
         )?;
 
         if !result.success {
@@ -349,14 +701,400 @@ pub fn run_deploy(cmd: DeployCmd) -> Result<()> {
             }
         }
 
-        output::success(&format!("{} completed successfully", playbook_name));
+        output::success(&format!("{prefix}{} completed successfully", playbook_name));
 
-        if !cmd.check {
-            run_dns_checks_for_run(run, &config, &host, cmd.verify_public_dns)?;
+        if !plan.check {
+            run_dns_checks_for_run(run, &config, host, plan.verify_public_dns, prefix)?;
         }
     }
 
-    output::success("Deployment completed successfully");
+    Ok(())
+}
+
+/// Map `f` over `items` on at most `jobs` threads at once, returning the
+/// results in input order. A slow Host holds one slot, never the queue.
+fn fan_out<T: Sync, R: Send>(items: &[T], jobs: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let next = Mutex::new(items.iter().enumerate());
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+
+    std::thread::scope(|s| {
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            s.spawn(|| {
+                loop {
+                    let Some((index, item)) = next.lock().unwrap().next() else {
+                        break;
+                    };
+                    let result = f(item);
+                    results.lock().unwrap()[index] = Some(result);
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.expect("every item is claimed by exactly one worker"))
+        .collect()
+}
+
+struct HostOutcome {
+    host: String,
+    elapsed: Duration,
+    error: Option<String>,
+}
+
+#[derive(Tabled)]
+struct HostOutcomeRow {
+    #[tabled(rename = "Host")]
+    host: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Duration")]
+    duration: String,
+}
+
+fn host_outcome_rows(outcomes: &[HostOutcome]) -> Vec<HostOutcomeRow> {
+    outcomes
+        .iter()
+        .map(|o| HostOutcomeRow {
+            host: o.host.clone(),
+            status: match &o.error {
+                None => "✓".to_string(),
+                Some(err) => format!("✗ {}", err.lines().next().unwrap_or_default()),
+            },
+            duration: output::format_duration(o.elapsed.as_secs()),
+        })
+        .collect()
+}
+
+fn render_host_outcomes(outcomes: &[HostOutcome]) {
+    eprintln!();
+    output::print_table(&host_outcome_rows(outcomes));
+    eprintln!();
+    for outcome in outcomes {
+        if let Some(err) = &outcome.error {
+            eprintln!("{}: {}", outcome.host, err);
+        }
+    }
+}
+
+pub fn run_deploy_command(command: DeployCommands) -> Result<()> {
+    match command {
+        DeployCommands::History {
+            host,
+            app,
+            failed,
+            limit,
+            output,
+        } => {
+            let filter = HistoryFilter {
+                host,
+                app,
+                failed_only: failed,
+            };
+            render_history(&DeployJournal::open()?.history(&filter, limit)?, output)
+        }
+        DeployCommands::Rollback {
+            app,
+            host,
+            restore_backup,
+            backup_id,
+            check,
+            force,
+        } => signal::with_ctrlc(|| {
+            run_rollback(RollbackOptions {
+                app,
+                host,
+                restore_backup,
+                backup_id,
+                check,
+                force,
+            })
+        }),
+        DeployCommands::Show { id, output } => {
+            render_record(&DeployJournal::open()?.find(&id)?, output)
+        }
+    }
+}
+
+struct RollbackOptions {
+    app: String,
+    host: Option<String>,
+    restore_backup: bool,
+    backup_id: Option<String>,
+    check: bool,
+    force: bool,
+}
+
+/// Redeploy one App at the App Version the deploy journal last saw it run
+/// before the version declared now. The version reaches ansible as an
+/// extra-vars override, so the Playbook Meta in the repo is left untouched.
+fn run_rollback(opts: RollbackOptions) -> Result<()> {
+    let app = opts.app;
+    let host = select_or_arg(opts.host, HOST_FLAG)?;
+
+    let playbooks_dir = AnsibleAssets::prepare()?.playbooks_dir();
+    let current = declared_app_versions(&playbooks_dir)?
+        .into_iter()
+        .find(|(name, _)| name == &app)
+        .map(|(_, pin)| pin.value)
+        .ok_or_else(|| {
+            eyre::eyre!("{app} declares no App Version in its Playbook Meta; nothing to roll back")
+        })?;
+    let version_var = app_version_var(&app);
+
+    let records = DeployJournal::open()?.records()?;
+    let target = rollback_target(&records, &host.name, &app, &version_var, &current)
+        .ok_or_else(|| {
+            eyre::eyre!(
+                "The deploy journal has no successful deploy of {app} to {} at a version other than {current}",
+                host.name
+            )
+        })?;
+
+    let backup_id = if opts.restore_backup {
+        Some(match opts.backup_id {
+            Some(id) => id,
+            None => match target.pre_upgrade_backup(&app) {
+                Some(id) => Some(id.to_string()),
+                None => pre_upgrade_backup(
+                    &staged_backup_ids(&host.name, &app)?,
+                    target.upgrade.map(|r| r.started_at),
+                ),
+            }
+            .ok_or_else(|| {
+                eyre::eyre!(
+                    "No local backup of {app} from {} predates the upgrade. Pick one with --backup-id.",
+                    host.name
+                )
+            })?,
+        })
+    } else {
+        None
+    };
+
+    let mut plan = resolve_plan(
+        vec![app.clone()],
+        std::slice::from_ref(&host),
+        Config::load()?,
+        opts.check,
+        false,
+    )?;
+    plan.source = DeploySource::Rollback;
+    pin_var(&mut plan.app_versions, &version_var, target.version);
+
+    show_execution_plan(&plan.runs, std::slice::from_ref(&host), opts.check)?;
+    output::info(&format!(
+        "Rolling back {app}: {current} → {} (last deployed {}, {})",
+        target.version,
+        format_started(target.deployed),
+        target.deployed.id
+    ));
+    if let Some(id) = &backup_id {
+        output::info(&format!(
+            "First stopping {app} and restoring its data from backup {id}"
+        ));
+    }
+    eprintln!();
+    confirm_deploy(opts.force)?;
+
+    let restore = match backup_id {
+        Some(id) if opts.check => {
+            output::info(&format!("Check mode: skipping restore of backup {id}"));
+            None
+        }
+        backup_id => backup_id,
+    };
+    if let Some(id) = &restore {
+        restore_staged_app(&host.name, id, &app).wrap_err_with(|| {
+            format!(
+                "Restoring {app} from backup {id} failed; {app} is left stopped on {} and was not redeployed",
+                host.name
+            )
+        })?;
+    }
+
+    let mut progress = TerminalProgress::new("");
+    journaled(plan.journal_entry(&host), |_| {
+        deploy_host(&plan, &host, "", &mut progress)
+    })?;
+
+    if restore.is_some() {
+        finish_staged_restore(&host.name, &app)?;
+    }
+
+    output::success(&format!(
+        "Rolled back {app} on {} to {}",
+        host.name, target.version
+    ));
+    output::info(&format!(
+        "The repo still declares {current}; the next `auberge deploy {app}` reinstalls it"
+    ));
+    Ok(())
+}
+
+/// Redeploy `apps` to one Host with `config` in place of `config.toml`, so
+/// a caller can prove a value works before it writes it. Recorded in the
+/// deploy journal under `source`.
+pub fn redeploy_with_config(
+    apps: Vec<String>,
+    host: &Host,
+    config: Config,
+    source: DeploySource,
+    force: bool,
+) -> Result<()> {
+    let mut plan = resolve_plan(apps, std::slice::from_ref(host), config, false, false)?;
+    plan.source = source;
+
+    show_execution_plan(&plan.runs, std::slice::from_ref(host), false)?;
+    confirm_deploy(force)?;
+
+    let mut progress = TerminalProgress::new("");
+    journaled(plan.journal_entry(host), |_| {
+        deploy_host(&plan, host, "", &mut progress)
+    })
+}
+
+/// Override one extra var in place, appending it if absent.
+fn pin_var(vars: &mut Vec<(String, String)>, name: &str, value: &str) {
+    match vars.iter_mut().find(|(n, _)| n == name) {
+        Some((_, v)) => *v = value.to_string(),
+        None => vars.push((name.to_string(), value.to_string())),
+    }
+}
+
+/// The newest staged backup taken before `upgraded_at`, or the newest of all
+/// when the journal never saw the upgrade. Backup IDs are UTC timestamps, as
+/// `backup create` names them; `ids` is oldest first.
+fn pre_upgrade_backup(ids: &[String], upgraded_at: Option<DateTime<Utc>>) -> Option<String> {
+    ids.iter()
+        .rev()
+        .find(|id| match upgraded_at {
+            None => true,
+            Some(upgraded_at) => NaiveDateTime::parse_from_str(id, "%Y-%m-%d_%H-%M-%S")
+                .is_ok_and(|taken| taken.and_utc() < upgraded_at),
+        })
+        .cloned()
+}
+
+#[derive(Tabled)]
+struct HistoryRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Host")]
+    host: String,
+    #[tabled(rename = "Apps")]
+    apps: String,
+    #[tabled(rename = "Started")]
+    started: String,
+    #[tabled(rename = "Duration")]
+    duration: String,
+    #[tabled(rename = "Result")]
+    result: String,
+}
+
+fn format_started(record: &DeployRecord) -> String {
+    record
+        .started_at
+        .with_timezone(&chrono::Local)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+fn record_result(record: &DeployRecord) -> &'static str {
+    match (record.success, record.check) {
+        (true, false) => "✓",
+        (true, true) => "✓ (check)",
+        (false, _) => "✗",
+    }
+}
+
+fn history_rows(records: &[DeployRecord]) -> Vec<HistoryRow> {
+    records
+        .iter()
+        .map(|r| HistoryRow {
+            id: r.id.clone(),
+            host: r.host.clone(),
+            apps: if r.apps.is_empty() {
+                format!("({})", r.source.as_str())
+            } else {
+                r.apps.join(", ")
+            },
+            started: format_started(r),
+            duration: output::format_duration(r.duration_secs()),
+            result: record_result(r).to_string(),
+        })
+        .collect()
+}
+
+fn render_history(records: &[DeployRecord], format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(records)?),
+        OutputFormat::Human if records.is_empty() => {
+            output::info("No deploys recorded yet");
+        }
+        OutputFormat::Human => output::print_table(&history_rows(records)),
+    }
+    Ok(())
+}
+
+fn render_record(record: &DeployRecord, format: OutputFormat) -> Result<()> {
+    if let OutputFormat::Json = format {
+        println!("{}", serde_json::to_string_pretty(record)?);
+        return Ok(());
+    }
+
+    println!("ID:       {}", record.id);
+    println!("Source:   auberge {}", record.source.as_str());
+    println!("Host:     {}", record.host);
+    if !record.apps.is_empty() {
+        println!("Apps:     {}", record.apps.join(", "));
+    }
+    println!("Started:  {}", format_started(record));
+    println!(
+        "Duration: {}",
+        output::format_duration(record.duration_secs())
+    );
+    println!("Result:   {}", record_result(record));
+
+    println!();
+    println!("Playbook runs:");
+    for run in &record.runs {
+        if run.tags.is_empty() {
+            println!("  {}", run.playbook);
+        } else {
+            println!("  {} (tags: {})", run.playbook, run.tags.join(", "));
+        }
+    }
+    for (title, vars) in [
+        ("App Versions:", &record.app_versions),
+        ("Memory budgets:", &record.memory_budgets),
+    ] {
+        if !vars.is_empty() {
+            println!();
+            println!("{title}");
+            for (name, value) in vars {
+                println!("  {name}={value}");
+            }
+        }
+    }
+    if let Some(backup) = &record.pre_upgrade_backup {
+        println!();
+        println!(
+            "Pre-upgrade backup: {} ({})",
+            backup.id,
+            backup.apps.join(", ")
+        );
+    }
+    if let Some(failure) = &record.failure {
+        println!();
+        println!("Failure:");
+        for line in failure.lines() {
+            println!("  {line}");
+        }
+    }
     Ok(())
 }
 
@@ -463,6 +1201,180 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_dedupe_preserving_order_drops_repeats() {
+        let names = ["b", "a", "b", "c", "a"].map(String::from).to_vec();
+        assert_eq!(dedupe_preserving_order(names), vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_fan_out_returns_results_in_input_order() {
+        let items: Vec<u64> = (0..12).collect();
+        let results = fan_out(&items, 3, |n| {
+            std::thread::sleep(Duration::from_millis(12 - n));
+            n * 10
+        });
+        assert_eq!(results, (0..12).map(|n| n * 10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_fan_out_never_exceeds_the_job_bound() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let items: Vec<usize> = (0..10).collect();
+        fan_out(&items, 2, |_| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(5));
+            running.fetch_sub(1, Ordering::SeqCst);
+        });
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[test]
+    fn test_fan_out_with_no_items_returns_empty() {
+        let items: Vec<u8> = vec![];
+        assert!(fan_out(&items, 4, |n| *n).is_empty());
+    }
+
+    #[test]
+    fn test_host_outcome_rows_report_every_host_and_first_error_line() {
+        let outcomes = vec![
+            HostOutcome {
+                host: "alpha".to_string(),
+                elapsed: Duration::from_secs(75),
+                error: None,
+            },
+            HostOutcome {
+                host: "beta".to_string(),
+                elapsed: Duration::from_secs(3),
+                error: Some("apps failed with exit code 2:\nfatal: [beta]: FAILED!".to_string()),
+            },
+        ];
+        let rows = host_outcome_rows(&outcomes);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].status, "✓");
+        assert_eq!(rows[0].duration, "1m 15s");
+        assert_eq!(rows[1].host, "beta");
+        assert_eq!(rows[1].status, "✗ apps failed with exit code 2:");
+    }
+
+    #[test]
+    fn test_history_rows_label_apps_and_results() {
+        let at = |t: &str| t.parse::<chrono::DateTime<chrono::Utc>>().unwrap();
+        let record = |apps: &[&str], success: bool, check: bool| DeployRecord {
+            id: "2026-09-01_08-00-00_prod".to_string(),
+            source: if apps.is_empty() {
+                DeploySource::AnsibleRun
+            } else {
+                DeploySource::Deploy
+            },
+            host: "prod".to_string(),
+            apps: apps.iter().map(|a| a.to_string()).collect(),
+            runs: vec![],
+            app_versions: Default::default(),
+            memory_budgets: Default::default(),
+            check,
+            pre_upgrade_backup: None,
+            started_at: at("2026-09-01T08:00:00Z"),
+            finished_at: at("2026-09-01T08:02:05Z"),
+            success,
+            failure: None,
+        };
+        let rows = history_rows(&[
+            record(&["paperless", "freshrss"], true, false),
+            record(&["paperless"], true, true),
+            record(&[], false, false),
+        ]);
+        assert_eq!(rows[0].apps, "paperless, freshrss");
+        assert_eq!(rows[0].result, "✓");
+        assert_eq!(rows[0].duration, "2m 5s");
+        assert_eq!(rows[1].result, "✓ (check)");
+        assert_eq!(rows[2].apps, "(ansible run)");
+        assert_eq!(rows[2].result, "✗");
+    }
+
+    #[test]
+    fn test_version_changes_compare_declared_with_last_deployed() {
+        let at = |t: &str| t.parse::<chrono::DateTime<chrono::Utc>>().unwrap();
+        let deployed = DeployRecord {
+            id: "2026-09-01_08-00-00_prod".to_string(),
+            source: DeploySource::Deploy,
+            host: "prod".to_string(),
+            apps: vec!["paperless".to_string(), "freshrss".to_string()],
+            runs: vec![],
+            app_versions: [
+                ("paperless_version", "2.18.4"),
+                ("freshrss_version", "1.26.0"),
+            ]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+            memory_budgets: Default::default(),
+            check: false,
+            pre_upgrade_backup: None,
+            started_at: at("2026-09-01T08:00:00Z"),
+            finished_at: at("2026-09-01T08:05:00Z"),
+            success: true,
+            failure: None,
+        };
+        let declared = [
+            ("paperless_version", "3.0.0"),
+            ("freshrss_version", "1.26.0"),
+            ("immich_version", "1.140.0"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let apps = ["paperless", "freshrss", "immich"].map(String::from);
+
+        assert_eq!(
+            version_changes(&apps, &declared, std::slice::from_ref(&deployed), "prod"),
+            vec![VersionChange {
+                app: "paperless".to_string(),
+                from: "2.18.4".to_string(),
+                to: "3.0.0".to_string(),
+            }]
+        );
+        assert!(version_changes(&apps, &declared, &[deployed], "staging").is_empty());
+    }
+
+    #[test]
+    fn test_pin_var_overrides_or_appends() {
+        let mut vars = vec![
+            ("paperless_version".to_string(), "3.0.0".to_string()),
+            ("freshrss_version".to_string(), "1.26.0".to_string()),
+        ];
+        pin_var(&mut vars, "paperless_version", "2.18.4");
+        pin_var(&mut vars, "immich_version", "1.140.0");
+        assert_eq!(vars[0].1, "2.18.4");
+        assert_eq!(vars[1].1, "1.26.0");
+        assert_eq!(
+            vars[2],
+            ("immich_version".to_string(), "1.140.0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_pre_upgrade_backup_picks_newest_before_the_upgrade() {
+        let ids = [
+            "2026-09-01_03-00-00",
+            "2026-09-02_03-00-00",
+            "2026-09-03_03-00-00",
+        ]
+        .map(String::from);
+        let upgraded_at = "2026-09-02T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(
+            pre_upgrade_backup(&ids, Some(upgraded_at)).as_deref(),
+            Some("2026-09-02_03-00-00")
+        );
+        assert_eq!(
+            pre_upgrade_backup(&ids, None).as_deref(),
+            Some("2026-09-03_03-00-00")
+        );
+        let before_all = "2026-08-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(pre_upgrade_backup(&ids, Some(before_all)), None);
+    }
+
     #[test]
     fn test_get_app_names_returns_roles() {
         let apps = get_app_names().unwrap();
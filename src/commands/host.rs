@@ -1,6 +1,7 @@
 use crate::hosts::{Host, HostManager};
 use crate::output;
 use crate::output::OutputFormat;
+use crate::profile::Profile;
 use crate::prompt::{Choice, confirm, select_item};
 use crate::services::ssh::{LiveSshSession, SshSession};
 use clap::Subcommand;
@@ -365,21 +366,29 @@ pub fn run_host_detect_tailscale_ip(name_arg: Option<String>) -> Result<()> {
     Ok(())
 }
 
-/// Regenerates the CLI-owned ~/.ssh/config.d/auberge.conf from the hosts.toml
+/// Regenerates the CLI-owned ~/.ssh/config.d/auberge.conf (one file per
+/// Profile) from the hosts.toml
 /// on disk after every host mutation (#534). The user's ~/.ssh/config is never
 /// written; when it lacks the Include line, only a hint is printed.
 fn sync_ssh_include() -> Result<()> {
     let hosts = HostManager::load_hosts()?;
     let home = dirs::home_dir().ok_or_else(|| eyre::eyre!("Could not determine home directory"))?;
     let ssh_dir = home.join(".ssh");
-    crate::services::ssh_include::write_include_file(&ssh_dir, &hosts).wrap_err(
-        "hosts.toml was updated but ~/.ssh/config.d/auberge.conf could not be regenerated; rerun any host subcommand after fixing",
+    let profile = Profile::active();
+    crate::services::ssh_include::write_include_file(&ssh_dir, &profile, &hosts).wrap_err_with(
+        || format!(
+            "hosts.toml was updated but ~/.ssh/config.d/{} could not be regenerated; rerun any host subcommand after fixing",
+            profile.ssh_include_name()
+        ),
     )?;
-    if !crate::services::ssh_include::main_config_has_include(&ssh_dir)? {
+    if !crate::services::ssh_include::main_config_has_include(&ssh_dir, &profile)? {
         output::info(
             "ssh aliases inactive: add this line at the top of ~/.ssh/config (first-obtained value wins):",
         );
-        output::info(&format!("  {}", crate::services::ssh_include::INCLUDE_LINE));
+        output::info(&format!(
+            "  {}",
+            crate::services::ssh_include::include_line(&profile)
+        ));
     }
     Ok(())
 }
@@ -0,0 +1,82 @@
+use crate::output;
+use crate::profile;
+use clap::Subcommand;
+use eyre::Result;
+use tabled::Tabled;
+
+#[derive(Subcommand)]
+pub enum ProfileCommands {
+    #[command(visible_alias = "l", about = "List profiles, marking the active one")]
+    List,
+    #[command(
+        visible_alias = "c",
+        about = "Create a profile with its own config.toml and hosts.toml"
+    )]
+    Create {
+        #[arg(help = "Profile name (letters, digits, '-' and '_')")]
+        name: String,
+    },
+    #[command(
+        visible_alias = "u",
+        about = "Use a profile whenever --profile and AUBERGE_PROFILE are unset"
+    )]
+    Use {
+        #[arg(help = "Profile name, or 'default'")]
+        name: String,
+    },
+}
+
+#[derive(Tabled)]
+struct ProfileRow {
+    #[tabled(rename = "")]
+    marker: &'static str,
+    #[tabled(rename = "NAME")]
+    name: String,
+    #[tabled(rename = "CONFIG")]
+    config_dir: String,
+}
+
+pub fn run_profile_list() -> Result<()> {
+    let (active, source) = profile::resolve();
+    let rows = profile::list()?
+        .into_iter()
+        .map(|p| {
+            Ok(ProfileRow {
+                marker: if p.name == active { "*" } else { "" },
+                config_dir: p.config_dir()?.display().to_string(),
+                name: p.name,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    output::print_table(&rows);
+    output::info(&format!("Active: {active} (from {})", source.as_str()));
+    Ok(())
+}
+
+pub fn run_profile_create(name: String) -> Result<()> {
+    let created = profile::create(&name)?;
+    output::success(&format!(
+        "Created profile '{name}' at {}",
+        created.config_dir()?.display()
+    ));
+    output::info(&format!(
+        "Scaffold its config with `auberge --profile {name} config init --output \"$(auberge --profile {name} config path)\"`"
+    ));
+    Ok(())
+}
+
+pub fn run_profile_use(name: String) -> Result<()> {
+    profile::use_profile(&name)?;
+    output::success(&format!("Using profile '{name}'"));
+    if let Ok(env) = std::env::var(profile::PROFILE_ENV)
+        && !env.trim().is_empty()
+        && env.trim() != name
+    {
+        output::warn(&format!(
+            "{} is set to '{}' and still wins in this shell",
+            profile::PROFILE_ENV,
+            env.trim()
+        ));
+    }
+    Ok(())
+}
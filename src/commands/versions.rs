@@ -1,27 +1,76 @@
 use crate::ansible_assets::AnsibleAssets;
+use crate::duration;
+use crate::hosts::HostManager;
 use crate::output::{self, OutputFormat};
-use crate::playbook_meta::{VersionPin, declared_app_versions};
-use crate::tool_versions::{ToolVersion, declared_tool_versions};
-use clap::Args;
+use crate::playbook_meta::{
+    VersionPin, VersionProbe, declared_app_versions, load_all_metas, rewrite_app_version,
+};
+use crate::services::http_cache::{CachedResponse, ResponseCache};
+use crate::services::ssh::{LiveSshSession, SshSession, resolve_ssh_key_path};
+use crate::tool_versions::{ToolVersion, declared_tool_versions, rewrite_tool_version};
+use chrono::{TimeDelta, Utc};
+use clap::{Args, Subcommand};
 use eyre::{Result, WrapErr};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tabled::Tabled;
 
 const NPM_REGISTRY: &str = "https://registry.npmjs.org";
 const GITHUB_API: &str = "https://api.github.com";
 const GO_PROXY: &str = "https://proxy.golang.org";
+const PYPI: &str = "https://pypi.org";
+const GITLAB: &str = "https://gitlab.com";
+const DOCKER_HUB: &str = "https://registry-1.docker.io";
+const OSV_API: &str = "https://api.osv.dev";
 // Abbreviated packument: dist-tags without per-version metadata (~1% the size).
 const NPM_ABBREVIATED: &str = "application/vnd.npm.install-v1+json";
+// ssh exits 255 when it fails itself, as opposed to relaying the remote exit.
+const SSH_CONNECTION_FAILURE: i32 = 255;
 
 #[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct VersionsCmd {
+    #[command(subcommand)]
+    pub command: Option<VersionsCommands>,
     #[arg(
         long,
         help = "Query each App's datasource for its latest release and report drift"
     )]
     pub check_upstream: bool,
+    #[arg(
+        long,
+        help = "Query OSV for known vulnerabilities affecting each declared version"
+    )]
+    pub advisories: bool,
+    #[arg(
+        long,
+        default_value = OSV_API,
+        help = "Base URL of the OSV-compatible API --advisories queries"
+    )]
+    pub osv_url: String,
+    #[arg(
+        long,
+        value_name = "DURATION",
+        default_value = "0s",
+        value_parser = parse_cache_age,
+        help = "Serve cached upstream responses younger than this (e.g. 1h) without revalidating"
+    )]
+    pub max_cache_age: TimeDelta,
+    #[arg(
+        long,
+        conflicts_with = "max_cache_age",
+        help = "Answer upstream and advisory checks from the response cache only"
+    )]
+    pub offline: bool,
+    #[arg(
+        short = 'H',
+        long,
+        help = "Probe this host for the App Version each app is running"
+    )]
+    pub host: Option<String>,
     #[arg(
         short = 'o',
         long,
@@ -32,14 +81,66 @@ pub struct VersionsCmd {
     pub output: OutputFormat,
 }
 
+fn parse_cache_age(input: &str) -> std::result::Result<TimeDelta, String> {
+    duration::parse(input).ok_or_else(|| "expected <number><s|m|h|d>, for example 1h".to_string())
+}
+
+#[derive(Subcommand)]
+pub enum VersionsCommands {
+    #[command(
+        visible_alias = "b",
+        about = "Rewrite version pins in this checkout to their latest upstream release"
+    )]
+    Bump {
+        #[arg(help = "App (e.g. paperless) or role/tool (e.g. blocky/lego); every pin if omitted")]
+        target: Option<String>,
+        #[arg(
+            long,
+            requires = "target",
+            help = "Pin this version instead of querying upstream for the latest"
+        )]
+        to: Option<String>,
+        #[arg(
+            short = 'o',
+            long,
+            value_enum,
+            default_value = "human",
+            help = "Output format"
+        )]
+        output: OutputFormat,
+    },
+    #[command(
+        visible_alias = "cl",
+        about = "Show the upstream release notes between a pin's declared and latest version"
+    )]
+    Changelog {
+        #[arg(help = "App (e.g. paperless) or role/tool (e.g. blocky/lego)")]
+        target: String,
+        #[arg(
+            short = 'o',
+            long,
+            value_enum,
+            default_value = "human",
+            help = "Output format"
+        )]
+        output: OutputFormat,
+    },
+}
+
 #[derive(Debug, Serialize)]
 struct AppReport {
     app: String,
     declared: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    running: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    running_status: Option<RunningStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     latest: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     status: Option<DriftStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    advisories: Option<Vec<Advisory>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -51,6 +152,18 @@ struct ToolReport {
     latest: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     status: Option<DriftStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    advisories: Option<Vec<Advisory>>,
+}
+
+/// A known vulnerability affecting a declared version, as OSV reports it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Advisory {
+    id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
 }
 
 /// App and Tool Versions stay distinct sections end to end — the ADR-0017
@@ -79,13 +192,60 @@ impl DriftStatus {
     }
 }
 
+/// What a Host runs against what the repo declares. `Unknown` when the App
+/// declares no probe, or its probe finds nothing — typically an App never
+/// deployed to that Host.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum RunningStatus {
+    Current,
+    Drifted,
+    Unknown,
+}
+
+impl RunningStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Current => "current",
+            Self::Drifted => "drifted",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
 /// Returns the process exit code — the Backup Verdict convention (0 every
 /// pin current, 1 at least one behind, 2 operational error) so a cron can
-/// branch on drift. `unknown` drift does not fail the gate.
+/// branch on drift. A Host running another version than declared fails the
+/// gate like upstream drift, and so does a declared version with a known
+/// advisory; `unknown` on either side does not.
 pub async fn run_versions(cmd: VersionsCmd) -> i32 {
     versions_exit_code(versions_and_report(cmd).await)
 }
 
+pub async fn run_versions_command(command: VersionsCommands) -> Result<()> {
+    match command {
+        VersionsCommands::Bump { target, to, output } => {
+            let outcome = bump(target.as_deref(), to.as_deref()).await?;
+            render_bumps(&outcome.bumps, output)?;
+            if outcome.failed.is_empty() {
+                return Ok(());
+            }
+            for failure in &outcome.failed {
+                output::warn(&format!("{}: {}", failure.target, failure.error));
+            }
+            eyre::bail!(
+                "{} pin(s) could not be checked upstream and were left alone",
+                outcome.failed.len()
+            )
+        }
+        VersionsCommands::Changelog { target, output } => {
+            let changelog =
+                changelog(&target, &UpstreamClient::new(CachePolicy::default())?).await?;
+            render_changelog(&changelog, output)
+        }
+    }
+}
+
 fn versions_exit_code(result: Result<VersionsReport>) -> i32 {
     match result {
         Ok(report) => {
@@ -95,7 +255,17 @@ fn versions_exit_code(result: Result<VersionsReport>) -> i32 {
                 .map(|app| app.status)
                 .chain(report.tools.iter().map(|tool| tool.status))
                 .any(|status| status == Some(DriftStatus::Behind));
-            i32::from(behind)
+            let drifted = report
+                .apps
+                .iter()
+                .any(|app| app.running_status == Some(RunningStatus::Drifted));
+            let vulnerable = report
+                .apps
+                .iter()
+                .map(|app| &app.advisories)
+                .chain(report.tools.iter().map(|tool| &tool.advisories))
+                .any(|advisories| advisories.as_ref().is_some_and(|a| !a.is_empty()));
+            i32::from(behind || drifted || vulnerable)
         }
         Err(e) => {
             eprintln!("✗ {e:#}");
@@ -106,16 +276,25 @@ fn versions_exit_code(result: Result<VersionsReport>) -> i32 {
 
 /// Report the App Version each Playbook Meta declares and the Tool Versions
 /// annotated in role defaults (ADR-0017, amended for #451). Reads only the
-/// embedded asset tree — what the repo declares, not what any Host runs.
-/// Upstream is queried only behind `--check-upstream`; the default path stays
-/// offline.
+/// embedded asset tree — what the repo declares — unless `--host` asks a Host
+/// what it runs. Upstream is queried only behind `--check-upstream`, OSV only
+/// behind `--advisories`; the default path stays offline.
 async fn versions_and_report(cmd: VersionsCmd) -> Result<VersionsReport> {
     let assets = AnsibleAssets::prepare()?;
     let apps = declared_app_versions(&assets.playbooks_dir())?;
     let tools = declared_tool_versions(&assets.roles_dir())?;
 
-    let report = if cmd.check_upstream {
-        let client = UpstreamClient::new()?;
+    let policy = if cmd.offline {
+        CachePolicy::Offline
+    } else {
+        CachePolicy::Revalidate(cmd.max_cache_age)
+    };
+    let client = UpstreamClient::with_bases(UpstreamBases {
+        osv: cmd.osv_url.clone(),
+        ..UpstreamBases::public()
+    })?
+    .cached(ResponseCache::open()?, policy);
+    let mut report = if cmd.check_upstream {
         VersionsReport {
             apps: app_drift_reports(&apps, &client).await?,
             tools: tool_drift_reports(&tools, &client).await?,
@@ -123,35 +302,139 @@ async fn versions_and_report(cmd: VersionsCmd) -> Result<VersionsReport> {
     } else {
         VersionsReport {
             apps: apps
-                .into_iter()
+                .iter()
                 .map(|(app, pin)| AppReport {
-                    app,
-                    declared: pin.value,
+                    app: app.clone(),
+                    declared: pin.value.clone(),
+                    running: None,
+                    running_status: None,
                     latest: None,
                     status: None,
+                    advisories: None,
                 })
                 .collect(),
             tools: tools
-                .into_iter()
+                .iter()
                 .map(|tool| ToolReport {
-                    role: tool.role,
-                    tool: tool.tool,
-                    declared: tool.pin.value,
+                    role: tool.role.clone(),
+                    tool: tool.tool.clone(),
+                    declared: tool.pin.value.clone(),
                     latest: None,
                     status: None,
+                    advisories: None,
                 })
                 .collect(),
         }
     };
 
+    if cmd.advisories {
+        for (report, (app, pin)) in report.apps.iter_mut().zip(&apps) {
+            report.advisories = client
+                .advisories(pin)
+                .await
+                .wrap_err_with(|| format!("Failed to query advisories for {app}"))?;
+        }
+        for (report, tool) in report.tools.iter_mut().zip(&tools) {
+            report.advisories = client.advisories(&tool.pin).await.wrap_err_with(|| {
+                format!("Failed to query advisories for {}/{}", tool.role, tool.tool)
+            })?;
+        }
+    }
+
+    if let Some(host_name) = &cmd.host {
+        let host = HostManager::get_host(host_name)?;
+        let ssh_key_path = resolve_ssh_key_path(&host, None)?;
+        let session = LiveSshSession::new(&host, &ssh_key_path);
+        let probes: HashMap<String, VersionProbe> = load_all_metas(&assets.playbooks_dir())?
+            .into_iter()
+            .filter_map(|(app, meta)| meta.version_probe.map(|probe| (app, probe)))
+            .collect();
+        probe_running_versions(&mut report.apps, &probes, &session)
+            .wrap_err_with(|| format!("Failed to probe {host_name}"))?;
+    }
+
+    let host = cmd.host.as_deref();
     match cmd.output {
-        OutputFormat::Human => print_report_tables(&report, cmd.check_upstream),
-        OutputFormat::Json => println!("{}", render_json(&report, cmd.check_upstream)?),
+        OutputFormat::Human => {
+            print_report_tables(&report, cmd.check_upstream, host);
+            if cmd.advisories {
+                print_advisories(&report);
+            }
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            render_json(&report, cmd.check_upstream, cmd.advisories, host)?
+        ),
     }
 
     Ok(report)
 }
 
+/// Fill in what the Host runs for every App that declares a probe.
+fn probe_running_versions<S: SshSession + ?Sized>(
+    apps: &mut [AppReport],
+    probes: &HashMap<String, VersionProbe>,
+    session: &S,
+) -> Result<()> {
+    for app in apps {
+        let running = match probes.get(&app.app) {
+            Some(probe) => probe_version(session, probe)
+                .wrap_err_with(|| format!("Failed to probe the running {} version", app.app))?,
+            None => None,
+        };
+        app.running_status = Some(running_status(&app.declared, running.as_deref()));
+        app.running = running;
+    }
+    Ok(())
+}
+
+/// Run one probe. A probe that exits non-zero or prints no version reports
+/// nothing running; only SSH itself failing (exit 255) is an error, since a
+/// Host that cannot be reached says nothing about any App on it.
+fn probe_version<S: SshSession + ?Sized>(
+    session: &S,
+    probe: &VersionProbe,
+) -> Result<Option<String>> {
+    let result = session.run(&probe.command)?;
+    if result.exit_code == Some(SSH_CONNECTION_FAILURE) {
+        eyre::bail!("ssh failed: {}", result.stderr_str().trim());
+    }
+    if !result.success {
+        return Ok(None);
+    }
+    let stdout = result.stdout_str();
+    let version = match &probe.extract {
+        None => stdout.trim().to_string(),
+        Some(extract) => {
+            let regex = Regex::new(extract).wrap_err("Invalid version_probe extract")?;
+            match regex.captures(&stdout).and_then(|c| c.name("version")) {
+                Some(capture) => capture.as_str().to_string(),
+                None => return Ok(None),
+            }
+        }
+    };
+    Ok((!version.is_empty()).then_some(version))
+}
+
+/// Pure comparison over (declared, running). Version-shaped values compare
+/// by segments, so a Host reporting `v0.34.0` for a declared `0.34.0` runs
+/// the declared version.
+fn running_status(declared: &str, running: Option<&str>) -> RunningStatus {
+    let Some(running) = running else {
+        return RunningStatus::Unknown;
+    };
+    let same = if is_version_like(declared) && is_version_like(running) {
+        compare_versions(declared, running) == Ordering::Equal
+    } else {
+        declared == running
+    };
+    if same {
+        RunningStatus::Current
+    } else {
+        RunningStatus::Drifted
+    }
+}
+
 async fn resolve_drift(
     client: &UpstreamClient,
     name: &str,
@@ -175,8 +458,11 @@ async fn app_drift_reports(
         reports.push(AppReport {
             app: app.clone(),
             declared: pin.value.clone(),
+            running: None,
+            running_status: None,
             latest: Some(latest),
             status: Some(status),
+            advisories: None,
         });
     }
     Ok(reports)
@@ -196,11 +482,396 @@ async fn tool_drift_reports(
             declared: tool.pin.value.clone(),
             latest: Some(latest),
             status: Some(status),
+            advisories: None,
         });
     }
     Ok(reports)
 }
 
+/// Where a pin is declared: an App Version in its Playbook Meta, or a Tool
+/// Version in its role's defaults.
+#[derive(Debug, Clone, PartialEq)]
+enum PinSite {
+    App(String),
+    Tool { role: String, tool: String },
+}
+
+#[derive(Debug, Clone)]
+struct DeclaredPin {
+    site: PinSite,
+    pin: VersionPin,
+    file: PathBuf,
+}
+
+impl DeclaredPin {
+    /// `paperless` for an App Version, `blocky/lego` for a Tool Version —
+    /// the spelling `versions bump` and `versions changelog` take as target.
+    fn name(&self) -> String {
+        match &self.site {
+            PinSite::App(app) => app.clone(),
+            PinSite::Tool { role, tool } => format!("{role}/{tool}"),
+        }
+    }
+
+    fn rewrite(&self, content: &str, to: &str) -> Result<String> {
+        match &self.site {
+            PinSite::App(_) => rewrite_app_version(content, to),
+            PinSite::Tool { role, tool } => rewrite_tool_version(content, role, tool, to),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Bump {
+    target: String,
+    from: String,
+    to: String,
+    file: String,
+}
+
+/// A pin whose latest release could not be resolved, so `bump` left it.
+#[derive(Debug)]
+struct BumpFailure {
+    target: String,
+    error: String,
+}
+
+struct BumpOutcome {
+    bumps: Vec<Bump>,
+    failed: Vec<BumpFailure>,
+}
+
+/// Rewrite pins in the checkout's `ansible/` tree — the committed files,
+/// so the result is a diff to review and commit like a Renovate PR. Without
+/// `--to`, each pin moves to the latest release `--check-upstream` would
+/// report, and only pins that are behind move; `--to` stays offline. A pin
+/// whose upstream lookup fails is reported and left, not allowed to hold
+/// back the others.
+async fn bump(target: Option<&str>, to: Option<&str>) -> Result<BumpOutcome> {
+    let assets = AnsibleAssets::checkout()?;
+    let candidates = declared_pins(&assets.playbooks_dir(), &assets.roles_dir(), target)?;
+
+    let (planned, failed) = match to {
+        Some(to) => (
+            candidates
+                .into_iter()
+                .filter(|candidate| candidate.pin.value != to)
+                .map(|candidate| (candidate, to.to_string()))
+                .collect(),
+            Vec::new(),
+        ),
+        None => upstream_bumps(candidates, &UpstreamClient::new(CachePolicy::default())?).await,
+    };
+
+    let bumps = apply_bumps(&planned)?;
+    Ok(BumpOutcome { bumps, failed })
+}
+
+/// Every declared pin, App Versions first, narrowed to `target` when given.
+fn declared_pins(
+    playbooks_dir: &Path,
+    roles_dir: &Path,
+    target: Option<&str>,
+) -> Result<Vec<DeclaredPin>> {
+    let apps = declared_app_versions(playbooks_dir)?
+        .into_iter()
+        .map(|(app, pin)| DeclaredPin {
+            file: playbooks_dir.join(format!("{app}.meta.yml")),
+            site: PinSite::App(app),
+            pin,
+        });
+    let tools = declared_tool_versions(roles_dir)?
+        .into_iter()
+        .map(|tool| DeclaredPin {
+            file: roles_dir.join(&tool.role).join("defaults/main.yml"),
+            site: PinSite::Tool {
+                role: tool.role,
+                tool: tool.tool,
+            },
+            pin: tool.pin,
+        });
+    let mut candidates: Vec<DeclaredPin> = apps.chain(tools).collect();
+
+    if let Some(target) = target {
+        candidates.retain(|candidate| candidate.name() == target);
+        if candidates.is_empty() {
+            eyre::bail!(
+                "`{target}` declares no version pin; expected an app (e.g. paperless) or role/tool (e.g. blocky/lego)"
+            );
+        }
+    }
+    Ok(candidates)
+}
+
+/// The pins that are behind with the release each moves to, and the pins
+/// whose latest release could not be resolved.
+async fn upstream_bumps(
+    candidates: Vec<DeclaredPin>,
+    client: &UpstreamClient,
+) -> (Vec<(DeclaredPin, String)>, Vec<BumpFailure>) {
+    let mut planned = Vec::new();
+    let mut failed = Vec::new();
+    for candidate in candidates {
+        match resolve_drift(client, &candidate.name(), &candidate.pin).await {
+            Ok((latest, DriftStatus::Behind)) => planned.push((candidate, latest)),
+            Ok(_) => {}
+            Err(e) => failed.push(BumpFailure {
+                target: candidate.name(),
+                error: format!("{e:#}"),
+            }),
+        }
+    }
+    (planned, failed)
+}
+
+/// Rewrite every planned pin, all or nothing. Each file is rewritten in
+/// memory first — a role's defaults can declare several pins — then written
+/// to a temp file beside it, and only once every file is written are they
+/// renamed into place, so a failure leaves no pin half bumped.
+fn apply_bumps(planned: &[(DeclaredPin, String)]) -> Result<Vec<Bump>> {
+    let mut rewritten: Vec<(&Path, String)> = Vec::new();
+    let mut bumps = Vec::with_capacity(planned.len());
+    for (candidate, to) in planned {
+        let file = candidate.file.as_path();
+        let index = match rewritten.iter().position(|(path, _)| *path == file) {
+            Some(index) => index,
+            None => {
+                let content = std::fs::read_to_string(file)
+                    .wrap_err_with(|| format!("Failed to read {}", file.display()))?;
+                rewritten.push((file, content));
+                rewritten.len() - 1
+            }
+        };
+        let content = &mut rewritten[index].1;
+        *content = candidate
+            .rewrite(content, to)
+            .wrap_err_with(|| format!("Failed to rewrite {}", file.display()))?;
+        bumps.push(Bump {
+            target: candidate.name(),
+            from: candidate.pin.value.clone(),
+            to: to.clone(),
+            file: file.display().to_string(),
+        });
+    }
+
+    let staged = rewritten
+        .iter()
+        .map(|(file, content)| {
+            stage_rewrite(file, content)
+                .wrap_err_with(|| format!("Failed to write {}", file.display()))
+                .map(|temp| (temp, *file))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    for (temp, file) in staged {
+        temp.persist(file)
+            .wrap_err_with(|| format!("Failed to write {}", file.display()))?;
+    }
+    Ok(bumps)
+}
+
+/// `content` in a temp file in `file`'s directory, with `file`'s mode, ready
+/// to rename over it.
+fn stage_rewrite(file: &Path, content: &str) -> Result<tempfile::NamedTempFile> {
+    use std::io::Write;
+
+    let dir = file.parent().unwrap_or(Path::new("."));
+    let mut temp = tempfile::NamedTempFile::new_in(dir)?;
+    temp.write_all(content.as_bytes())?;
+    std::fs::set_permissions(temp.path(), std::fs::metadata(file)?.permissions())?;
+    Ok(temp)
+}
+
+fn render_bumps(bumps: &[Bump], output: OutputFormat) -> Result<()> {
+    match output {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(bumps)?),
+        OutputFormat::Human if bumps.is_empty() => output::info("Every pin is already current"),
+        OutputFormat::Human => {
+            let rows: Vec<BumpRow> = bumps
+                .iter()
+                .map(|bump| BumpRow {
+                    target: &bump.target,
+                    from: &bump.from,
+                    to: &bump.to,
+                    file: &bump.file,
+                })
+                .collect();
+            output::print_table(&rows);
+            output::success(&format!(
+                "Bumped {} pin(s); review with `git diff` and commit",
+                bumps.len()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Why a release deserves a read before bumping past it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ReleaseFlag {
+    Major,
+    Breaking,
+    Migration,
+}
+
+impl ReleaseFlag {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Major => "major",
+            Self::Breaking => "breaking",
+            Self::Migration => "migration",
+        }
+    }
+}
+
+/// One upstream release as its datasource publishes it.
+#[derive(Debug, Clone, PartialEq)]
+struct ReleaseNote {
+    version: String,
+    tag: String,
+    published_at: Option<String>,
+    url: Option<String>,
+    body: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ChangelogEntry {
+    version: String,
+    tag: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    published_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    flags: Vec<ReleaseFlag>,
+    body: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Changelog {
+    target: String,
+    declared: String,
+    releases: Vec<ChangelogEntry>,
+}
+
+/// Every release a bump of `target` would cross, oldest first. Reads the
+/// declared pin like the default `versions` run, then the release notes
+/// from the pin's own datasource.
+async fn changelog(target: &str, client: &UpstreamClient) -> Result<Changelog> {
+    let assets = AnsibleAssets::prepare()?;
+    let declared = declared_pins(&assets.playbooks_dir(), &assets.roles_dir(), Some(target))?
+        .into_iter()
+        .next()
+        .expect("declared_pins fails on an unknown target");
+    let notes = client
+        .release_notes(&declared.pin)
+        .await
+        .wrap_err_with(|| format!("Failed to fetch the {target} release notes"))?;
+    Ok(Changelog {
+        target: declared.name(),
+        releases: releases_since(&declared.pin.value, notes),
+        declared: declared.pin.value,
+    })
+}
+
+/// Pure selection over the published notes: stable releases newer than
+/// `declared`, in version order, each flagged against the one before it —
+/// the declared version for the first.
+fn releases_since(declared: &str, notes: Vec<ReleaseNote>) -> Vec<ChangelogEntry> {
+    let mut notes: Vec<ReleaseNote> = notes
+        .into_iter()
+        .filter(|note| is_version_like(&note.version) && is_stable(&note.version))
+        .filter(|note| compare_versions(&note.version, declared) == Ordering::Greater)
+        .collect();
+    notes.sort_by(|a, b| compare_versions(&a.version, &b.version));
+
+    let mut previous = declared.to_string();
+    notes
+        .into_iter()
+        .map(|note| {
+            let flags = release_flags(&previous, &note.version, &note.body);
+            previous.clone_from(&note.version);
+            ChangelogEntry {
+                version: note.version,
+                tag: note.tag,
+                published_at: note.published_at,
+                url: note.url,
+                flags,
+                body: note.body,
+            }
+        })
+        .collect()
+}
+
+fn release_flags(previous: &str, version: &str, body: &str) -> Vec<ReleaseFlag> {
+    let body = body.to_lowercase();
+    let mut flags = Vec::new();
+    if crosses_major(previous, version) {
+        flags.push(ReleaseFlag::Major);
+    }
+    if body.contains("breaking") {
+        flags.push(ReleaseFlag::Breaking);
+    }
+    if body.contains("migration") {
+        flags.push(ReleaseFlag::Migration);
+    }
+    flags
+}
+
+/// A semver major crossing: the first segment changes, or — below 1.0,
+/// where semver promises nothing across minors — the second one does.
+/// lego v4 → v5 (#447) is the first kind; headscale's 0.x minors, which
+/// drop old DB migrations, are the second.
+fn crosses_major(previous: &str, version: &str) -> bool {
+    let previous = segments(previous);
+    let version = segments(version);
+    let significant = if previous.first() == Some(&"0") { 2 } else { 1 };
+    (0..significant)
+        .any(|i| previous.get(i).copied().unwrap_or("0") != version.get(i).copied().unwrap_or("0"))
+}
+
+fn render_changelog(changelog: &Changelog, output: OutputFormat) -> Result<()> {
+    if matches!(output, OutputFormat::Json) {
+        println!("{}", serde_json::to_string_pretty(changelog)?);
+        return Ok(());
+    }
+    let Some(latest) = changelog.releases.last() else {
+        output::info(&format!(
+            "{} is current at {}; no newer release",
+            changelog.target, changelog.declared
+        ));
+        return Ok(());
+    };
+    println!(
+        "{} {} → {} ({} release(s))",
+        changelog.target,
+        changelog.declared,
+        latest.version,
+        changelog.releases.len()
+    );
+    for release in &changelog.releases {
+        let mut heading = format!("\n{} {}", marker(&release.flags), release.tag);
+        if let Some(published_at) = &release.published_at {
+            heading.push_str(&format!(" — {published_at}"));
+        }
+        if !release.flags.is_empty() {
+            let flags: Vec<&str> = release.flags.iter().map(|flag| flag.as_str()).collect();
+            heading.push_str(&format!(" [{}]", flags.join(", ")));
+        }
+        println!("{heading}");
+        if let Some(url) = &release.url {
+            println!("  {url}");
+        }
+        for line in release.body.trim().lines() {
+            println!("  {line}");
+        }
+    }
+    Ok(())
+}
+
+fn marker(flags: &[ReleaseFlag]) -> &'static str {
+    if flags.is_empty() { "•" } else { "⚠" }
+}
+
 /// Pure drift comparison over (declared, latest). `Unknown` when either side
 /// is not version-shaped — mirroring Renovate, which skips values its
 /// versioning cannot parse.
@@ -249,11 +920,53 @@ fn compare_versions(a: &str, b: &str) -> Ordering {
     Ordering::Equal
 }
 
+/// Where each datasource is queried. Production uses the public registries;
+/// tests point every one at a wiremock server.
+struct UpstreamBases {
+    npm: String,
+    github: String,
+    go: String,
+    pypi: String,
+    gitlab: String,
+    docker_hub: String,
+    osv: String,
+}
+
+impl UpstreamBases {
+    fn public() -> Self {
+        Self {
+            npm: NPM_REGISTRY.to_string(),
+            github: GITHUB_API.to_string(),
+            go: GO_PROXY.to_string(),
+            pypi: PYPI.to_string(),
+            gitlab: GITLAB.to_string(),
+            docker_hub: DOCKER_HUB.to_string(),
+            osv: OSV_API.to_string(),
+        }
+    }
+}
+
+/// How [`UpstreamClient::fetch`] uses the response cache.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CachePolicy {
+    /// Serve entries no older than the max age; revalidate the rest.
+    Revalidate(TimeDelta),
+    /// Serve every entry regardless of age and never touch the network.
+    Offline,
+}
+
+impl Default for CachePolicy {
+    /// Revalidate every entry: always current, and still cheap when the
+    /// upstream honours ETags.
+    fn default() -> Self {
+        Self::Revalidate(TimeDelta::zero())
+    }
+}
+
 struct UpstreamClient {
     http: reqwest::Client,
-    npm_base: String,
-    github_base: String,
-    go_base: String,
+    bases: UpstreamBases,
+    cache: Option<(ResponseCache, CachePolicy)>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -263,35 +976,185 @@ struct Release {
     draft: bool,
     #[serde(default)]
     prerelease: bool,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    html_url: Option<String>,
+    #[serde(default)]
+    published_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitlabRelease {
+    tag_name: String,
+    #[serde(default)]
+    upcoming_release: bool,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    released_at: Option<String>,
+    #[serde(default, rename = "_links")]
+    links: Option<GitlabReleaseLinks>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitlabReleaseLinks {
+    #[serde(rename = "self")]
+    html: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tag {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PypiProject {
+    releases: HashMap<String, Vec<PypiFile>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PypiFile {
+    #[serde(default)]
+    yanked: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct DockerTags {
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvResponse {
+    #[serde(default)]
+    vulns: Vec<Advisory>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DockerToken {
+    #[serde(alias = "access_token")]
+    token: String,
 }
 
 impl UpstreamClient {
-    fn new() -> Result<Self> {
-        Self::with_bases(
-            NPM_REGISTRY.to_string(),
-            GITHUB_API.to_string(),
-            GO_PROXY.to_string(),
-        )
+    fn new(policy: CachePolicy) -> Result<Self> {
+        Ok(Self::with_bases(UpstreamBases::public())?.cached(ResponseCache::open()?, policy))
     }
 
-    fn with_bases(npm_base: String, github_base: String, go_base: String) -> Result<Self> {
+    fn with_bases(bases: UpstreamBases) -> Result<Self> {
         let http = reqwest::Client::builder()
             .user_agent(concat!("auberge/", env!("CARGO_PKG_VERSION")))
             .build()
             .wrap_err("Failed to build HTTP client")?;
         Ok(Self {
             http,
-            npm_base,
-            github_base,
-            go_base,
+            bases,
+            cache: None,
         })
     }
 
+    fn cached(self, cache: ResponseCache, policy: CachePolicy) -> Self {
+        Self {
+            cache: Some((cache, policy)),
+            ..self
+        }
+    }
+
+    /// Send `request` through the response cache. An entry younger than the
+    /// max age is served as is; an older one is revalidated with its ETag, so
+    /// an unchanged upstream answers 304 — which GitHub does not count
+    /// against its rate limit. Offline, every entry is served regardless of
+    /// age and a miss is an error. A registry's anonymous `Bearer` challenge
+    /// is answered once, transparently.
+    async fn fetch(&self, request: reqwest::RequestBuilder) -> Result<CachedResponse> {
+        let mut request = request.build()?;
+        let key = cache_key(&request);
+        let now = Utc::now();
+        let cached = self.cache.as_ref().and_then(|(cache, _)| cache.get(&key));
+        match (self.cache.as_ref().map(|(_, policy)| *policy), &cached) {
+            (Some(CachePolicy::Offline), _) => {
+                return cached.ok_or_else(|| {
+                    eyre::eyre!(
+                        "no cached response for {}; run once without --offline",
+                        request.url()
+                    )
+                });
+            }
+            (Some(CachePolicy::Revalidate(max_age)), Some(hit))
+                if now - hit.fetched_at <= max_age =>
+            {
+                return Ok(hit.clone());
+            }
+            _ => {}
+        }
+
+        if let Some(etag) = cached.as_ref().and_then(|hit| hit.etag.as_deref()) {
+            request
+                .headers_mut()
+                .insert(reqwest::header::IF_NONE_MATCH, etag.parse()?);
+        }
+        let retry = request.try_clone();
+        let mut response = self.http.execute(request).await?;
+        if let (reqwest::StatusCode::UNAUTHORIZED, Some(mut retry)) = (response.status(), retry)
+            && let Some(params) = auth_challenge(&response)
+        {
+            let token = self.bearer_token(&params).await?;
+            retry.headers_mut().insert(
+                reqwest::header::AUTHORIZATION,
+                format!("Bearer {token}").parse()?,
+            );
+            response = self.http.execute(retry).await?;
+        }
+
+        let entry = match cached {
+            Some(hit) if response.status() == reqwest::StatusCode::NOT_MODIFIED => CachedResponse {
+                fetched_at: now,
+                ..hit
+            },
+            _ => {
+                let response = response.error_for_status()?;
+                let header = |name| {
+                    response
+                        .headers()
+                        .get(name)
+                        .and_then(|value: &reqwest::header::HeaderValue| value.to_str().ok())
+                        .map(str::to_string)
+                };
+                CachedResponse {
+                    key,
+                    etag: header(reqwest::header::ETAG),
+                    link: header(reqwest::header::LINK),
+                    fetched_at: now,
+                    body: response.text().await?,
+                }
+            }
+        };
+        if let Some((cache, _)) = &self.cache
+            && let Err(e) = cache.put(&entry)
+        {
+            output::warn(&format!("Failed to cache upstream response: {e:#}"));
+        }
+        Ok(entry)
+    }
+
+    async fn fetch_json<T: serde::de::DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T> {
+        let response = self.fetch(request).await?;
+        serde_json::from_str(&response.body).wrap_err("Failed to parse upstream response")
+    }
+
     async fn latest(&self, version: &VersionPin) -> Result<String> {
         match version.datasource.as_str() {
             "npm" => self.npm_latest(&version.dep_name).await,
             "github-releases" => self.github_latest(version).await,
+            "github-tags" => self.github_tags_latest(version).await,
+            "gitlab-releases" => self.gitlab_latest(version).await,
             "go" => self.go_latest(&version.dep_name).await,
+            "pypi" => self.pypi_latest(&version.dep_name).await,
+            "docker" => self.docker_latest(version).await,
             other => eyre::bail!("Unsupported datasource `{other}`"),
         }
     }
@@ -301,15 +1164,8 @@ impl UpstreamClient {
     /// and ordering reuse the shared rules, so a `go` pin drifts exactly like
     /// a release tag.
     async fn go_latest(&self, dep_name: &str) -> Result<String> {
-        let url = format!("{}/{}/@v/list", self.go_base, escape_go_module(dep_name));
-        let listing = self
-            .http
-            .get(&url)
-            .send()
-            .await?
-            .error_for_status()?
-            .text()
-            .await?;
+        let url = format!("{}/{}/@v/list", self.bases.go, escape_go_module(dep_name));
+        let listing = self.fetch(self.http.get(&url)).await?.body;
         listing
             .lines()
             .map(str::trim)
@@ -320,15 +1176,13 @@ impl UpstreamClient {
     }
 
     async fn npm_latest(&self, dep_name: &str) -> Result<String> {
-        let url = format!("{}/{}", self.npm_base, dep_name);
+        let url = format!("{}/{}", self.bases.npm, dep_name);
         let packument: serde_json::Value = self
-            .http
-            .get(&url)
-            .header(reqwest::header::ACCEPT, NPM_ABBREVIATED)
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
+            .fetch_json(
+                self.http
+                    .get(&url)
+                    .header(reqwest::header::ACCEPT, NPM_ABBREVIATED),
+            )
             .await?;
         packument["dist-tags"]["latest"]
             .as_str()
@@ -336,32 +1190,295 @@ impl UpstreamClient {
             .ok_or_else(|| eyre::eyre!("npm packument for {dep_name} has no dist-tags.latest"))
     }
 
-    async fn github_latest(&self, version: &VersionPin) -> Result<String> {
-        let url = format!(
-            "{}/repos/{}/releases?per_page=100",
-            self.github_base, version.dep_name
-        );
-        let mut request = self
+    /// PyPI's JSON API lists every release with its files. Like Renovate, a
+    /// release whose every file is yanked does not count, and only final
+    /// releases (no `a`/`b`/`rc`/`dev` segment) are candidates.
+    async fn pypi_latest(&self, dep_name: &str) -> Result<String> {
+        let url = format!("{}/pypi/{}/json", self.bases.pypi, normalize_pypi(dep_name));
+        let project: PypiProject = self.fetch_json(self.http.get(&url)).await?;
+        latest_pypi_version(&project)
+            .ok_or_else(|| eyre::eyre!("PyPI lists no final release of {dep_name}"))
+    }
+
+    fn github_get(&self, url: &str) -> reqwest::RequestBuilder {
+        let request = self
             .http
-            .get(&url)
+            .get(url)
             .header(reqwest::header::ACCEPT, "application/vnd.github+json");
-        if let Ok(token) = std::env::var("GITHUB_TOKEN") {
-            request = request.bearer_auth(token);
-        }
-        let releases: Vec<Release> = request.send().await?.error_for_status()?.json().await?;
-        let extract = version
-            .extract_version
-            .as_deref()
-            .map(Regex::new)
-            .transpose()
-            .wrap_err_with(|| format!("Invalid extractVersion for {}", version.dep_name))?;
-        latest_release_version(&releases, extract.as_ref()).ok_or_else(|| {
-            eyre::eyre!(
-                "no release of {} yields a version through its coordinates",
-                version.dep_name
+        match std::env::var("GITHUB_TOKEN") {
+            Ok(token) => request.bearer_auth(token),
+            Err(_) => request,
+        }
+    }
+
+    /// The newest 100 releases are one request and always hold the latest
+    /// one, so `latest` never pages.
+    async fn github_latest(&self, version: &VersionPin) -> Result<String> {
+        let releases: Vec<Release> = self
+            .fetch_json(
+                self.github_get(&github_releases_url(&self.bases.github, &version.dep_name)),
             )
+            .await?;
+        let extract = extract_regex(version)?;
+        latest_release_version(&releases, extract.as_ref()).ok_or_else(|| no_release(version))
+    }
+
+    /// The releases of `dep_name`, newest first, paged until a page reaches
+    /// the `declared` version: nothing past it is newer.
+    async fn github_releases_since(
+        &self,
+        dep_name: &str,
+        declared: &str,
+        extract: Option<&Regex>,
+    ) -> Result<Vec<Release>> {
+        let url = github_releases_url(&self.bases.github, dep_name);
+        self.github_pages_until(url, |page: &[Release]| {
+            page.iter().any(|release| {
+                tag_version(&release.tag_name, extract).is_some_and(|version| {
+                    compare_versions(&version, declared) != Ordering::Greater
+                })
+            })
         })
+        .await
+    }
+
+    /// Every item of a GitHub listing, following its `Link` header from page
+    /// to page: a listing stops at 100 items a page.
+    async fn github_pages<T: serde::de::DeserializeOwned>(&self, url: String) -> Result<Vec<T>> {
+        self.github_pages_until(url, |_: &[T]| false).await
+    }
+
+    /// [`Self::github_pages`], stopping after the first page `last` accepts.
+    async fn github_pages_until<T: serde::de::DeserializeOwned>(
+        &self,
+        url: String,
+        last: impl Fn(&[T]) -> bool,
+    ) -> Result<Vec<T>> {
+        let mut url = url;
+        let mut items = Vec::new();
+        loop {
+            let page = self.fetch(self.github_get(&url)).await?;
+            let page_items: Vec<T> =
+                serde_json::from_str(&page.body).wrap_err("Failed to parse upstream response")?;
+            let done = last(&page_items);
+            items.extend(page_items);
+            match page.link.as_deref().and_then(next_link) {
+                Some(next) if !done => url = next,
+                _ => break,
+            }
+        }
+        Ok(items)
+    }
+
+    /// Renovate's `github-tags`: every tag of the repository, whether or not
+    /// a release was cut from it — for upstreams that only push tags. GitHub
+    /// lists tags by name, not date, so the highest version wins wherever it
+    /// sits in the listing.
+    async fn github_tags_latest(&self, version: &VersionPin) -> Result<String> {
+        let url = format!(
+            "{}/repos/{}/tags?per_page=100",
+            self.bases.github, version.dep_name
+        );
+        let tags: Vec<Tag> = self.github_pages(url).await?;
+        let extract = extract_regex(version)?;
+        latest_tag_version(tags.iter().map(|tag| tag.name.as_str()), extract.as_ref())
+            .ok_or_else(|| no_release(version))
+    }
+
+    /// Renovate's `gitlab-releases` against gitlab.com: the project is
+    /// addressed by its URL-encoded path, and an upcoming release (one whose
+    /// `released_at` lies in the future) is not a release yet.
+    async fn gitlab_latest(&self, version: &VersionPin) -> Result<String> {
+        let releases = self.gitlab_releases(&version.dep_name).await?;
+        let extract = extract_regex(version)?;
+        latest_tag_version(
+            releases
+                .iter()
+                .filter(|release| !release.upcoming_release)
+                .map(|release| release.tag_name.as_str()),
+            extract.as_ref(),
+        )
+        .ok_or_else(|| no_release(version))
+    }
+
+    async fn gitlab_releases(&self, dep_name: &str) -> Result<Vec<GitlabRelease>> {
+        let url = format!(
+            "{}/api/v4/projects/{}/releases?per_page=100",
+            self.bases.gitlab,
+            dep_name.replace('/', "%2F")
+        );
+        let mut request = self.http.get(&url);
+        if let Ok(token) = std::env::var("GITLAB_TOKEN") {
+            request = request.header("PRIVATE-TOKEN", token);
+        }
+        self.fetch_json(request).await
     }
+
+    /// Known vulnerabilities affecting the pinned version, from OSV's
+    /// `/v1/query`. `None` when the pin names no package OSV can match — a
+    /// Docker tag, or a release whose tag cannot be recovered.
+    async fn advisories(&self, version: &VersionPin) -> Result<Option<Vec<Advisory>>> {
+        let Some((package, pinned)) = osv_package(version) else {
+            return Ok(None);
+        };
+        let query = serde_json::json!({
+            "version": pinned,
+            "package": package,
+        });
+        let response: OsvResponse = self
+            .fetch_json(
+                self.http
+                    .post(format!("{}/v1/query", self.bases.osv))
+                    .json(&query),
+            )
+            .await?;
+        Ok(Some(response.vulns))
+    }
+
+    /// Every published release of a pin with its notes, read through the
+    /// pin's `extractVersion` like `latest`. Only the release datasources
+    /// publish notes; the rest only know version numbers.
+    async fn release_notes(&self, version: &VersionPin) -> Result<Vec<ReleaseNote>> {
+        let extract = extract_regex(version)?;
+        let note = |tag: String, published_at, url, body: Option<String>| {
+            tag_version(&tag, extract.as_ref()).map(|version| ReleaseNote {
+                version,
+                tag,
+                published_at,
+                url,
+                body: body.unwrap_or_default(),
+            })
+        };
+        match version.datasource.as_str() {
+            "github-releases" => Ok(self
+                .github_releases_since(&version.dep_name, &version.value, extract.as_ref())
+                .await?
+                .into_iter()
+                .filter(|release| !release.draft && !release.prerelease)
+                .filter_map(|release| {
+                    note(
+                        release.tag_name,
+                        release.published_at,
+                        release.html_url,
+                        release.body,
+                    )
+                })
+                .collect()),
+            "gitlab-releases" => Ok(self
+                .gitlab_releases(&version.dep_name)
+                .await?
+                .into_iter()
+                .filter(|release| !release.upcoming_release)
+                .filter_map(|release| {
+                    note(
+                        release.tag_name,
+                        release.released_at,
+                        release.links.and_then(|links| links.html),
+                        release.description,
+                    )
+                })
+                .collect()),
+            other => eyre::bail!(
+                "datasource `{other}` publishes no release notes; only github-releases and gitlab-releases do"
+            ),
+        }
+    }
+
+    /// Renovate's `docker` datasource over the Registry v2 API. Tags are
+    /// compared under docker versioning: only tags of the same shape as the
+    /// pinned one — same segment count, same `-suffix` — are candidates, so
+    /// `16.4-alpine` moves to `16.6-alpine`, never to `17` or `16.6`.
+    async fn docker_latest(&self, version: &VersionPin) -> Result<String> {
+        let (registry, repository) = docker_registry(&version.dep_name, &self.bases.docker_hub);
+        let mut url = format!("{registry}/v2/{repository}/tags/list?n=1000");
+        let mut tags = Vec::new();
+        loop {
+            let page = self.fetch(self.http.get(&url)).await?;
+            tags.extend(serde_json::from_str::<DockerTags>(&page.body)?.tags);
+            match page.link.as_deref().and_then(next_link) {
+                Some(next) if next.starts_with('/') => url = format!("{registry}{next}"),
+                Some(next) => url = next,
+                None => break,
+            }
+        }
+        let extract = extract_regex(version)?;
+        tags.iter()
+            .filter_map(|tag| tag_version(tag, extract.as_ref()))
+            .filter(|tag| docker_compatible(&version.value, tag))
+            .max_by(|a, b| compare_versions(a, b))
+            .ok_or_else(|| {
+                eyre::eyre!(
+                    "{} has no tag shaped like {}",
+                    version.dep_name,
+                    version.value
+                )
+            })
+    }
+
+    /// Registries answer an anonymous pull with a 401 whose `Bearer` challenge
+    /// names where to fetch an anonymous token for that repository.
+    async fn bearer_token(&self, challenge: &[(String, String)]) -> Result<String> {
+        let realm = challenge
+            .iter()
+            .find(|(key, _)| key == "realm")
+            .map(|(_, value)| value.clone())
+            .ok_or_else(|| eyre::eyre!("registry auth challenge names no realm"))?;
+        let query: Vec<&(String, String)> =
+            challenge.iter().filter(|(key, _)| key != "realm").collect();
+        let token: DockerToken = self
+            .http
+            .get(&realm)
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(token.token)
+    }
+}
+
+/// What identifies a request in the cache: method, URL, the `Accept` header
+/// (npm serves a different document per type) and the body (OSV queries
+/// are POSTs).
+fn cache_key(request: &reqwest::Request) -> String {
+    let accept = request
+        .headers()
+        .get(reqwest::header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("*/*");
+    let body = request
+        .body()
+        .and_then(|body| body.as_bytes())
+        .map(String::from_utf8_lossy)
+        .unwrap_or_default();
+    format!("{} {} {accept} {body}", request.method(), request.url())
+}
+
+/// The parameters of a 401's `Bearer` challenge, when it is one.
+fn auth_challenge(unauthorized: &reqwest::Response) -> Option<Vec<(String, String)>> {
+    let challenge = unauthorized
+        .headers()
+        .get(reqwest::header::WWW_AUTHENTICATE)?
+        .to_str()
+        .ok()?;
+    bearer_challenge(challenge)
+}
+
+fn extract_regex(version: &VersionPin) -> Result<Option<Regex>> {
+    version
+        .extract_version
+        .as_deref()
+        .map(Regex::new)
+        .transpose()
+        .wrap_err_with(|| format!("Invalid extractVersion for {}", version.dep_name))
+}
+
+fn no_release(version: &VersionPin) -> eyre::Report {
+    eyre::eyre!(
+        "no release of {} yields a version through its coordinates",
+        version.dep_name
+    )
 }
 
 /// Renovate's github-releases semantics: the newest stable release whose tag
@@ -369,10 +1486,22 @@ impl UpstreamClient {
 /// which also filters monorepo tags (grimmory's `grimmory/vX.Y.Z` among
 /// auberge's own releases).
 fn latest_release_version(releases: &[Release], extract: Option<&Regex>) -> Option<String> {
-    releases
-        .iter()
-        .filter(|release| !release.draft && !release.prerelease)
-        .filter_map(|release| tag_version(&release.tag_name, extract))
+    latest_tag_version(
+        releases
+            .iter()
+            .filter(|release| !release.draft && !release.prerelease)
+            .map(|release| release.tag_name.as_str()),
+        extract,
+    )
+}
+
+/// The newest stable version among `tags`, each read through `extract` —
+/// shared by every tag-shaped datasource.
+fn latest_tag_version<'a>(
+    tags: impl Iterator<Item = &'a str>,
+    extract: Option<&Regex>,
+) -> Option<String> {
+    tags.filter_map(|tag| tag_version(tag, extract))
         .filter(|version| is_version_like(version) && is_stable(version))
         .max_by(|a, b| compare_versions(a, b))
 }
@@ -399,6 +1528,156 @@ fn escape_go_module(module: &str) -> String {
     escaped
 }
 
+/// The OSV package a pin names, and its version as OSV spells it. Registry
+/// datasources map to their ecosystem; OSV versions Go modules without the
+/// `v` prefix the module proxy uses. Release datasources map to the `GIT`
+/// ecosystem by repository URL, versioned by the tag the release was cut
+/// from.
+fn osv_package(version: &VersionPin) -> Option<(serde_json::Value, String)> {
+    let dep_name = &version.dep_name;
+    let (ecosystem, name, pinned) = match version.datasource.as_str() {
+        "npm" => ("npm", dep_name.clone(), version.value.clone()),
+        "pypi" => ("PyPI", dep_name.clone(), version.value.clone()),
+        "go" => (
+            "Go",
+            dep_name.clone(),
+            version
+                .value
+                .strip_prefix('v')
+                .unwrap_or(&version.value)
+                .to_string(),
+        ),
+        "github-releases" | "github-tags" => (
+            "GIT",
+            format!("https://github.com/{dep_name}"),
+            pinned_tag(version)?,
+        ),
+        "gitlab-releases" => (
+            "GIT",
+            format!("https://gitlab.com/{dep_name}"),
+            pinned_tag(version)?,
+        ),
+        _ => return None,
+    };
+    Some((
+        serde_json::json!({ "name": name, "ecosystem": ecosystem }),
+        pinned,
+    ))
+}
+
+/// The tag a release pin was cut from: the declared version put back
+/// through `extractVersion`, which for a literal prefix is just that prefix
+/// — `^v(?<version>.+)$` turns `0.63.2` back into `v0.63.2`. `None` for any
+/// other pattern.
+fn pinned_tag(version: &VersionPin) -> Option<String> {
+    let Some(extract) = version.extract_version.as_deref() else {
+        return Some(version.value.clone());
+    };
+    let prefix = extract.strip_prefix('^')?.strip_suffix("(?<version>.+)$")?;
+    (regex::escape(prefix) == prefix).then(|| format!("{prefix}{}", version.value))
+}
+
+/// PEP 503 name normalization: case-insensitive, with runs of `-`, `_` and
+/// `.` equivalent — `Django_Q2` and `django-q2` are the same project.
+fn normalize_pypi(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+    normalized
+}
+
+fn latest_pypi_version(project: &PypiProject) -> Option<String> {
+    project
+        .releases
+        .iter()
+        .filter(|(_, files)| files.is_empty() || files.iter().any(|file| !file.yanked))
+        .map(|(version, _)| version.as_str())
+        .filter(|version| is_final_pep440(version))
+        .max_by(|a, b| compare_versions(a, b))
+        .map(str::to_string)
+}
+
+/// A PEP 440 final release: numeric segments, optionally a `postN` one.
+/// Pre-releases (`2.0.0rc1`, `1.4b2`) and dev releases are not.
+fn is_final_pep440(version: &str) -> bool {
+    is_version_like(version)
+        && segments(version).iter().all(|segment| {
+            segment.parse::<u64>().is_ok()
+                || segment
+                    .strip_prefix("post")
+                    .is_some_and(|n| n.parse::<u64>().is_ok())
+        })
+}
+
+/// Split a docker image name into its registry base URL and repository,
+/// the way the docker CLI does: a first segment holding a `.` or `:` (or
+/// `localhost`) is a registry host; anything else lives on Docker Hub,
+/// where official images sit under `library/`.
+fn docker_registry(dep_name: &str, docker_hub: &str) -> (String, String) {
+    match dep_name.split_once('/') {
+        Some(("docker.io", repository)) => docker_registry(repository, docker_hub),
+        Some((host, repository)) if host.contains(['.', ':']) || host == "localhost" => {
+            (format!("https://{host}"), repository.to_string())
+        }
+        Some(_) => (docker_hub.to_string(), dep_name.to_string()),
+        None => (docker_hub.to_string(), format!("library/{dep_name}")),
+    }
+}
+
+/// Renovate's docker versioning compatibility: a candidate tag must share
+/// the pinned tag's segment count and `-suffix`, and be numeric where the
+/// pin is.
+fn docker_compatible(pinned: &str, candidate: &str) -> bool {
+    let (pinned_release, pinned_suffix) = split_docker_suffix(pinned);
+    let (release, suffix) = split_docker_suffix(candidate);
+    suffix == pinned_suffix
+        && is_version_like(release)
+        && segments(release).len() == segments(pinned_release).len()
+        && segments(release)
+            .iter()
+            .all(|segment| segment.parse::<u64>().is_ok())
+}
+
+fn split_docker_suffix(tag: &str) -> (&str, &str) {
+    tag.split_once('-').unwrap_or((tag, ""))
+}
+
+/// Parse a `Bearer realm="…",service="…",scope="…"` challenge into its
+/// parameters, in order.
+fn bearer_challenge(challenge: &str) -> Option<Vec<(String, String)>> {
+    let params = challenge.strip_prefix("Bearer ")?;
+    let pattern = Regex::new(r#"(\w+)="([^"]*)""#).expect("valid challenge pattern");
+    Some(
+        pattern
+            .captures_iter(params)
+            .map(|c| (c[1].to_string(), c[2].to_string()))
+            .collect(),
+    )
+}
+
+fn github_releases_url(base: &str, dep_name: &str) -> String {
+    format!("{base}/repos/{dep_name}/releases?per_page=100")
+}
+
+/// The `rel="next"` target of an RFC 5988 `Link` header — how the Registry
+/// v2 API and GitHub paginate their listings.
+fn next_link(link: &str) -> Option<String> {
+    link.split(',')
+        .find(|part| part.contains(r#"rel="next""#))
+        .and_then(|part| {
+            let start = part.find('<')? + 1;
+            let end = part.find('>')?;
+            Some(part[start..end].to_string())
+        })
+}
+
 fn tag_version(tag: &str, extract: Option<&Regex>) -> Option<String> {
     match extract {
         None => Some(tag.to_string()),
@@ -429,6 +1708,34 @@ struct DriftAppRow<'a> {
     status: &'static str,
 }
 
+#[derive(Tabled)]
+struct RunningAppRow<'a> {
+    #[tabled(rename = "APP")]
+    app: &'a str,
+    #[tabled(rename = "DECLARED")]
+    declared: &'a str,
+    #[tabled(rename = "RUNNING")]
+    running: &'a str,
+    #[tabled(rename = "ON HOST")]
+    running_status: &'static str,
+}
+
+#[derive(Tabled)]
+struct RunningDriftAppRow<'a> {
+    #[tabled(rename = "APP")]
+    app: &'a str,
+    #[tabled(rename = "DECLARED")]
+    declared: &'a str,
+    #[tabled(rename = "RUNNING")]
+    running: &'a str,
+    #[tabled(rename = "LATEST")]
+    latest: &'a str,
+    #[tabled(rename = "ON HOST")]
+    running_status: &'static str,
+    #[tabled(rename = "STATUS")]
+    status: &'static str,
+}
+
 #[derive(Tabled)]
 struct DeclaredToolRow<'a> {
     #[tabled(rename = "ROLE")]
@@ -453,30 +1760,91 @@ struct DriftToolRow<'a> {
     status: &'static str,
 }
 
-fn print_report_tables(report: &VersionsReport, checked_upstream: bool) {
-    println!("App Versions");
-    if checked_upstream {
-        let rows: Vec<DriftAppRow> = report
-            .apps
-            .iter()
-            .map(|app| DriftAppRow {
-                app: &app.app,
-                declared: &app.declared,
-                latest: app.latest.as_deref().expect("drift reports carry latest"),
-                status: app.status.expect("drift reports carry status").as_str(),
-            })
-            .collect();
-        output::print_table(&rows);
-    } else {
-        let rows: Vec<DeclaredAppRow> = report
-            .apps
-            .iter()
-            .map(|app| DeclaredAppRow {
-                app: &app.app,
-                declared: &app.declared,
-            })
-            .collect();
-        output::print_table(&rows);
+#[derive(Tabled)]
+struct BumpRow<'a> {
+    #[tabled(rename = "PIN")]
+    target: &'a str,
+    #[tabled(rename = "FROM")]
+    from: &'a str,
+    #[tabled(rename = "TO")]
+    to: &'a str,
+    #[tabled(rename = "FILE")]
+    file: &'a str,
+}
+
+fn running(app: &AppReport) -> (&str, &'static str) {
+    (
+        app.running.as_deref().unwrap_or("-"),
+        app.running_status
+            .expect("probed reports carry running_status")
+            .as_str(),
+    )
+}
+
+fn print_report_tables(report: &VersionsReport, checked_upstream: bool, host: Option<&str>) {
+    match host {
+        Some(host) => println!("App Versions (running on {host})"),
+        None => println!("App Versions"),
+    }
+    match (checked_upstream, host.is_some()) {
+        (true, true) => {
+            let rows: Vec<RunningDriftAppRow> = report
+                .apps
+                .iter()
+                .map(|app| {
+                    let (running, running_status) = running(app);
+                    RunningDriftAppRow {
+                        app: &app.app,
+                        declared: &app.declared,
+                        running,
+                        latest: app.latest.as_deref().expect("drift reports carry latest"),
+                        running_status,
+                        status: app.status.expect("drift reports carry status").as_str(),
+                    }
+                })
+                .collect();
+            output::print_table(&rows);
+        }
+        (false, true) => {
+            let rows: Vec<RunningAppRow> = report
+                .apps
+                .iter()
+                .map(|app| {
+                    let (running, running_status) = running(app);
+                    RunningAppRow {
+                        app: &app.app,
+                        declared: &app.declared,
+                        running,
+                        running_status,
+                    }
+                })
+                .collect();
+            output::print_table(&rows);
+        }
+        (true, false) => {
+            let rows: Vec<DriftAppRow> = report
+                .apps
+                .iter()
+                .map(|app| DriftAppRow {
+                    app: &app.app,
+                    declared: &app.declared,
+                    latest: app.latest.as_deref().expect("drift reports carry latest"),
+                    status: app.status.expect("drift reports carry status").as_str(),
+                })
+                .collect();
+            output::print_table(&rows);
+        }
+        (false, false) => {
+            let rows: Vec<DeclaredAppRow> = report
+                .apps
+                .iter()
+                .map(|app| DeclaredAppRow {
+                    app: &app.app,
+                    declared: &app.declared,
+                })
+                .collect();
+            output::print_table(&rows);
+        }
     }
 
     println!("\nTool Versions");
@@ -507,9 +1875,71 @@ fn print_report_tables(report: &VersionsReport, checked_upstream: bool) {
     }
 }
 
-fn render_json(report: &VersionsReport, checked_upstream: bool) -> Result<String> {
+#[derive(Tabled)]
+struct AdvisoryRow<'a> {
+    #[tabled(rename = "PIN")]
+    pin: String,
+    #[tabled(rename = "VERSION")]
+    version: &'a str,
+    #[tabled(rename = "ADVISORY")]
+    id: &'a str,
+    #[tabled(rename = "SUMMARY")]
+    summary: &'a str,
+}
+
+/// Advisories get their own section rather than a column: one pin can carry
+/// several, and the drift tables stay as they are.
+fn print_advisories(report: &VersionsReport) {
+    let pins = report
+        .apps
+        .iter()
+        .map(|app| (app.app.clone(), &app.declared, &app.advisories))
+        .chain(report.tools.iter().map(|tool| {
+            (
+                format!("{}/{}", tool.role, tool.tool),
+                &tool.declared,
+                &tool.advisories,
+            )
+        }));
+
+    let mut rows = Vec::new();
+    let mut unchecked = Vec::new();
+    for (pin, version, advisories) in pins {
+        match advisories {
+            None => unchecked.push(pin),
+            Some(advisories) => rows.extend(advisories.iter().map(|advisory| AdvisoryRow {
+                pin: pin.clone(),
+                version,
+                id: &advisory.id,
+                summary: advisory.summary.as_deref().unwrap_or("-"),
+            })),
+        }
+    }
+
+    println!("\nAdvisories");
+    if rows.is_empty() {
+        println!("No known advisories affect a checked version");
+    } else {
+        output::print_table(&rows);
+    }
+    if !unchecked.is_empty() {
+        output::info(&format!(
+            "Not checked (no package OSV can match): {}",
+            unchecked.join(", ")
+        ));
+    }
+}
+
+fn render_json(
+    report: &VersionsReport,
+    checked_upstream: bool,
+    checked_advisories: bool,
+    host: Option<&str>,
+) -> Result<String> {
     Ok(serde_json::to_string_pretty(&serde_json::json!({
         "checked_upstream": checked_upstream,
+        "checked_advisories": checked_advisories,
+        "host": host,
         "apps": report.apps,
         "tools": report.tools,
     }))?)
@@ -518,6 +1948,7 @@ fn render_json(report: &VersionsReport, checked_upstream: bool) -> Result<String
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::ssh::{CommandResult, MockSshSession, SshOp};
     use wiremock::matchers::{method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -525,8 +1956,11 @@ mod tests {
         AppReport {
             app: app.to_string(),
             declared: declared.to_string(),
+            running: None,
+            running_status: None,
             status: latest.map(|latest| drift(declared, latest)),
             latest: latest.map(str::to_string),
+            advisories: None,
         }
     }
 
@@ -537,6 +1971,7 @@ mod tests {
             declared: declared.to_string(),
             status: latest.map(|latest| drift(declared, latest)),
             latest: latest.map(str::to_string),
+            advisories: None,
         }
     }
 
@@ -544,6 +1979,18 @@ mod tests {
         VersionsReport { apps, tools }
     }
 
+    fn mock_bases(server: &MockServer) -> UpstreamBases {
+        UpstreamBases {
+            npm: server.uri(),
+            github: server.uri(),
+            go: server.uri(),
+            pypi: server.uri(),
+            gitlab: server.uri(),
+            docker_hub: server.uri(),
+            osv: server.uri(),
+        }
+    }
+
     fn pin(datasource: &str, dep_name: &str, extract_version: Option<&str>) -> VersionPin {
         VersionPin {
             value: "1.0.0".to_string(),
@@ -559,6 +2006,9 @@ mod tests {
             tag_name: tag.to_string(),
             draft: false,
             prerelease: false,
+            body: None,
+            html_url: None,
+            published_at: None,
         }
     }
 
@@ -581,98 +2031,404 @@ mod tests {
         }
     }
 
-    #[test]
-    fn latest_release_version_picks_the_numeric_max() {
-        let releases = [release("v0.9.0"), release("v0.10.0"), release("v0.2.0")];
-        assert_eq!(latest_release_version(&releases, None).unwrap(), "v0.10.0");
+    fn probe(command: &str, extract: Option<&str>) -> VersionProbe {
+        VersionProbe {
+            command: command.to_string(),
+            extract: extract.map(str::to_string),
+        }
+    }
+
+    fn ran(exit_code: i32, stdout: &str) -> CommandResult {
+        CommandResult {
+            success: exit_code == 0,
+            exit_code: Some(exit_code),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: b"connection refused".to_vec(),
+        }
     }
 
     #[test]
-    fn latest_release_version_skips_drafts_and_prereleases() {
-        let releases = [
-            Release {
-                tag_name: "v2.0.0".to_string(),
-                draft: true,
-                prerelease: false,
-            },
-            Release {
-                tag_name: "v1.9.0".to_string(),
-                draft: false,
-                prerelease: true,
-            },
-            release("v1.8.0"),
+    fn running_status_compares_versions_by_segments() {
+        let cases = [
+            ("0.34.0", Some("0.34.0"), RunningStatus::Current),
+            ("0.34.0", Some("v0.34.0"), RunningStatus::Current),
+            ("1.2", Some("1.2.0"), RunningStatus::Current),
+            ("3.0.5", Some("2.18.4"), RunningStatus::Drifted),
+            ("3.0.5", Some("3.1.0"), RunningStatus::Drifted), // ahead is drift too
+            ("edge", Some("edge"), RunningStatus::Current),
+            ("3.0.5", None, RunningStatus::Unknown),
         ];
-        assert_eq!(latest_release_version(&releases, None).unwrap(), "v1.8.0");
+        for (declared, running, expected) in cases {
+            assert_eq!(
+                running_status(declared, running),
+                expected,
+                "({declared}, {running:?})"
+            );
+        }
     }
 
     #[test]
-    fn extract_version_filters_monorepo_tags_and_strips_the_prefix() {
-        let releases = [
-            release("v0.14.12"),
-            release("grimmory/v2.3.0"),
-            release("grimmory/v2.4.0"),
-        ];
-        let extract = Regex::new("^grimmory/v(?<version>.+)$").unwrap();
+    fn probe_version_trims_stdout_or_applies_extract() {
+        let session = MockSshSession::new();
+        session.stage_run_result(ran(0, "3.0.5\n"));
+        session.stage_run_result(ran(0, "Version: v0.34.0\nBuild time: 2026-08-01\n"));
+        session.stage_run_result(ran(0, "no version here"));
+
         assert_eq!(
-            latest_release_version(&releases, Some(&extract)).unwrap(),
-            "2.4.0"
+            probe_version(&session, &probe("cat /opt/paperless/version", None)).unwrap(),
+            Some("3.0.5".to_string())
+        );
+        let blocky = probe("blocky version", Some("Version: v(?<version>\\S+)"));
+        assert_eq!(
+            probe_version(&session, &blocky).unwrap(),
+            Some("0.34.0".to_string())
         );
+        assert_eq!(probe_version(&session, &blocky).unwrap(), None);
     }
 
     #[test]
-    fn latest_release_version_skips_unflagged_hyphenated_prereleases() {
-        let releases = [release("2.0.1-alpha.1"), release("2.0.0")];
-        assert_eq!(latest_release_version(&releases, None).unwrap(), "2.0.0");
+    fn probe_version_reports_nothing_when_the_probe_fails() {
+        let session = MockSshSession::new();
+        session.stage_run_result(ran(1, ""));
+        session.stage_run_result(ran(0, "  \n"));
+        let cat = probe("cat /opt/gokapi/version", None);
+
+        assert_eq!(probe_version(&session, &cat).unwrap(), None);
+        assert_eq!(probe_version(&session, &cat).unwrap(), None);
     }
 
     #[test]
-    fn latest_release_version_is_none_when_no_tag_is_version_shaped() {
-        let releases = [release("nightly")];
-        assert_eq!(latest_release_version(&releases, None), None);
+    fn probe_version_errors_when_ssh_itself_fails() {
+        let session = MockSshSession::new();
+        session.stage_run_result(ran(255, ""));
+
+        let err = probe_version(&session, &probe("true", None)).unwrap_err();
+        assert!(err.to_string().contains("connection refused"));
     }
 
     #[test]
-    fn render_json_offline_omits_latest_and_status() {
-        let report = versions_report(
-            vec![report("actual", "26.8.0", None)],
-            vec![tool_report("blocky", "lego", "5.3.1", None)],
-        );
+    fn probe_running_versions_marks_apps_without_a_probe_unknown() {
+        let session = MockSshSession::new();
+        session.stage_run_result(ran(0, "2.18.4"));
+        let mut apps = vec![
+            report("actual", "26.8.1", None),
+            report("paperless", "3.0.5", None),
+        ];
+        let probes = HashMap::from([(
+            "paperless".to_string(),
+            probe("cat /opt/paperless/version", None),
+        )]);
 
-        let json: serde_json::Value =
-            serde_json::from_str(&render_json(&report, false).unwrap()).unwrap();
+        probe_running_versions(&mut apps, &probes, &session).unwrap();
 
-        assert_eq!(json["checked_upstream"], false);
-        let app = &json["apps"][0];
-        assert_eq!(app["app"], "actual");
-        assert_eq!(app["declared"], "26.8.0");
-        assert!(app.get("latest").is_none());
-        assert!(app.get("status").is_none());
-        let tool = &json["tools"][0];
-        assert_eq!(tool["role"], "blocky");
-        assert_eq!(tool["tool"], "lego");
-        assert_eq!(tool["declared"], "5.3.1");
-        assert!(tool.get("latest").is_none());
-        assert!(tool.get("status").is_none());
+        assert_eq!(apps[0].running, None);
+        assert_eq!(apps[0].running_status, Some(RunningStatus::Unknown));
+        assert_eq!(apps[1].running.as_deref(), Some("2.18.4"));
+        assert_eq!(apps[1].running_status, Some(RunningStatus::Drifted));
+        assert_eq!(
+            session.calls(),
+            vec![SshOp::Run("cat /opt/paperless/version".to_string())]
+        );
     }
 
     #[test]
-    fn render_json_with_drift_reports_latest_and_status() {
-        let report = versions_report(
-            vec![
-                report("actual", "26.8.0", Some("26.8.1")),
-                report("headscale", "0.29.3", Some("0.29.3")),
-            ],
-            vec![tool_report("caddy", "l4", "v0.1.2", Some("v0.2.0"))],
+    fn versions_exit_code_fails_the_gate_on_running_drift() {
+        let mut drifted = report("paperless", "3.0.5", None);
+        drifted.running = Some("2.18.4".to_string());
+        drifted.running_status = Some(RunningStatus::Drifted);
+        let mut unknown = report("actual", "26.8.1", None);
+        unknown.running_status = Some(RunningStatus::Unknown);
+
+        assert_eq!(
+            versions_exit_code(Ok(versions_report(vec![unknown], vec![]))),
+            0
+        );
+        assert_eq!(
+            versions_exit_code(Ok(versions_report(vec![drifted], vec![]))),
+            1
         );
+    }
+
+    #[test]
+    fn render_json_with_a_host_reports_running_versions() {
+        let mut app = report("paperless", "3.0.5", None);
+        app.running = Some("2.18.4".to_string());
+        app.running_status = Some(RunningStatus::Drifted);
+        let report = versions_report(vec![app], vec![]);
 
         let json: serde_json::Value =
-            serde_json::from_str(&render_json(&report, true).unwrap()).unwrap();
+            serde_json::from_str(&render_json(&report, false, false, Some("prod")).unwrap())
+                .unwrap();
 
-        assert_eq!(json["checked_upstream"], true);
-        assert_eq!(json["apps"][0]["latest"], "26.8.1");
-        assert_eq!(json["apps"][0]["status"], "behind");
-        assert_eq!(json["apps"][1]["status"], "current");
-        assert_eq!(json["tools"][0]["latest"], "v0.2.0");
+        assert_eq!(json["host"], "prod");
+        assert_eq!(json["apps"][0]["running"], "2.18.4");
+        assert_eq!(json["apps"][0]["running_status"], "drifted");
+    }
+
+    fn advisory(id: &str) -> Advisory {
+        Advisory {
+            id: id.to_string(),
+            summary: Some("Path traversal".to_string()),
+            aliases: vec![],
+        }
+    }
+
+    #[test]
+    fn render_json_with_advisories_lists_them_per_pin() {
+        let mut app = report("paperless", "2.18.4", None);
+        app.advisories = Some(vec![advisory("GHSA-xxxx-yyyy-zzzz")]);
+        let report = versions_report(vec![app, report("blocky", "0.26", None)], vec![]);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_json(&report, false, true, None).unwrap()).unwrap();
+
+        assert_eq!(json["checked_advisories"], true);
+        assert_eq!(
+            json["apps"][0]["advisories"][0]["id"],
+            "GHSA-xxxx-yyyy-zzzz"
+        );
+        assert!(json["apps"][1].get("advisories").is_none());
+    }
+
+    #[test]
+    fn versions_exit_code_fails_the_gate_on_a_known_advisory() {
+        let mut vulnerable = tool_report("hermes", "uv", "0.5.0", None);
+        vulnerable.advisories = Some(vec![advisory("GHSA-xxxx-yyyy-zzzz")]);
+        let mut clean = report("actual", "26.8.0", None);
+        clean.advisories = Some(vec![]);
+
+        assert_eq!(
+            versions_exit_code(Ok(versions_report(vec![clean], vec![vulnerable]))),
+            1
+        );
+    }
+
+    #[test]
+    fn versions_exit_code_passes_clean_and_unchecked_pins() {
+        let mut clean = report("actual", "26.8.0", None);
+        clean.advisories = Some(vec![]);
+        let unchecked = report("headscale", "0.29.3", None);
+
+        assert_eq!(
+            versions_exit_code(Ok(versions_report(vec![clean, unchecked], vec![]))),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn advisories_query_osv_with_the_pins_ecosystem() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/query"))
+            .and(wiremock::matchers::body_json(serde_json::json!({
+                "version": "0.1.2",
+                "package": { "name": "github.com/mholt/caddy-l4", "ecosystem": "Go" },
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "vulns": [{
+                    "id": "GO-2026-0001",
+                    "summary": "Denial of service",
+                    "aliases": ["CVE-2026-0001"],
+                    "modified": "2026-01-01T00:00:00Z"
+                }]
+            })))
+            .mount(&server)
+            .await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+        let l4 = VersionPin {
+            value: "v0.1.2".to_string(),
+            ..pin("go", "github.com/mholt/caddy-l4", None)
+        };
+
+        let advisories = client.advisories(&l4).await?.unwrap();
+
+        assert_eq!(advisories.len(), 1);
+        assert_eq!(advisories[0].id, "GO-2026-0001");
+        assert_eq!(advisories[0].aliases, ["CVE-2026-0001"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn advisories_are_empty_when_osv_knows_none() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/query"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+
+        let advisories = client
+            .advisories(&pin("npm", "@actual-app/sync-server", None))
+            .await?;
+
+        assert_eq!(advisories, Some(vec![]));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn advisories_query_github_pins_as_git_tags() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/query"))
+            .and(wiremock::matchers::body_json(serde_json::json!({
+                "version": "v0.25.1",
+                "package": {
+                    "name": "https://github.com/juanfont/headscale",
+                    "ecosystem": "GIT"
+                },
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "vulns": [{ "id": "GHSA-0000-0000-0000" }]
+            })))
+            .mount(&server)
+            .await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+        let headscale = VersionPin {
+            value: "0.25.1".to_string(),
+            ..pin(
+                "github-releases",
+                "juanfont/headscale",
+                Some("^v(?<version>.+)$"),
+            )
+        };
+
+        let advisories = client.advisories(&headscale).await?.unwrap();
+
+        assert_eq!(advisories[0].id, "GHSA-0000-0000-0000");
+        Ok(())
+    }
+
+    #[test]
+    fn pinned_tag_reverses_a_literal_prefix_extract_version() {
+        let with = |extract: Option<&str>| VersionPin {
+            value: "2.4.0".to_string(),
+            ..pin("github-releases", "sripwoud/auberge", extract)
+        };
+        assert_eq!(pinned_tag(&with(None)).as_deref(), Some("2.4.0"));
+        assert_eq!(
+            pinned_tag(&with(Some("^grimmory/v(?<version>.+)$"))).as_deref(),
+            Some("grimmory/v2.4.0")
+        );
+        assert_eq!(pinned_tag(&with(Some("^v?(?<version>.+)$"))), None);
+    }
+
+    #[test]
+    fn every_bundled_pin_names_an_osv_package() -> Result<()> {
+        let apps = declared_app_versions(&ansible_dir().join("playbooks"))?;
+        let tools = declared_tool_versions(&ansible_dir().join("roles"))?;
+        let pins = apps
+            .iter()
+            .map(|(_, pin)| pin)
+            .chain(tools.iter().map(|tool| &tool.pin));
+        for pin in pins {
+            assert!(osv_package(pin).is_some(), "{} is unchecked", pin.dep_name);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn advisories_skip_datasources_without_an_osv_ecosystem() -> Result<()> {
+        let server = MockServer::start().await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+
+        let advisories = client.advisories(&pin("docker", "postgres", None)).await?;
+
+        assert_eq!(advisories, None);
+        assert!(server.received_requests().await.unwrap().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn latest_release_version_picks_the_numeric_max() {
+        let releases = [release("v0.9.0"), release("v0.10.0"), release("v0.2.0")];
+        assert_eq!(latest_release_version(&releases, None).unwrap(), "v0.10.0");
+    }
+
+    #[test]
+    fn latest_release_version_skips_drafts_and_prereleases() {
+        let releases = [
+            Release {
+                draft: true,
+                ..release("v2.0.0")
+            },
+            Release {
+                prerelease: true,
+                ..release("v1.9.0")
+            },
+            release("v1.8.0"),
+        ];
+        assert_eq!(latest_release_version(&releases, None).unwrap(), "v1.8.0");
+    }
+
+    #[test]
+    fn extract_version_filters_monorepo_tags_and_strips_the_prefix() {
+        let releases = [
+            release("v0.14.12"),
+            release("grimmory/v2.3.0"),
+            release("grimmory/v2.4.0"),
+        ];
+        let extract = Regex::new("^grimmory/v(?<version>.+)$").unwrap();
+        assert_eq!(
+            latest_release_version(&releases, Some(&extract)).unwrap(),
+            "2.4.0"
+        );
+    }
+
+    #[test]
+    fn latest_release_version_skips_unflagged_hyphenated_prereleases() {
+        let releases = [release("2.0.1-alpha.1"), release("2.0.0")];
+        assert_eq!(latest_release_version(&releases, None).unwrap(), "2.0.0");
+    }
+
+    #[test]
+    fn latest_release_version_is_none_when_no_tag_is_version_shaped() {
+        let releases = [release("nightly")];
+        assert_eq!(latest_release_version(&releases, None), None);
+    }
+
+    #[test]
+    fn render_json_offline_omits_latest_and_status() {
+        let report = versions_report(
+            vec![report("actual", "26.8.0", None)],
+            vec![tool_report("blocky", "lego", "5.3.1", None)],
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_json(&report, false, false, None).unwrap()).unwrap();
+
+        assert_eq!(json["checked_upstream"], false);
+        let app = &json["apps"][0];
+        assert_eq!(app["app"], "actual");
+        assert_eq!(app["declared"], "26.8.0");
+        assert!(app.get("latest").is_none());
+        assert!(app.get("status").is_none());
+        let tool = &json["tools"][0];
+        assert_eq!(tool["role"], "blocky");
+        assert_eq!(tool["tool"], "lego");
+        assert_eq!(tool["declared"], "5.3.1");
+        assert!(tool.get("latest").is_none());
+        assert!(tool.get("status").is_none());
+    }
+
+    #[test]
+    fn render_json_with_drift_reports_latest_and_status() {
+        let report = versions_report(
+            vec![
+                report("actual", "26.8.0", Some("26.8.1")),
+                report("headscale", "0.29.3", Some("0.29.3")),
+            ],
+            vec![tool_report("caddy", "l4", "v0.1.2", Some("v0.2.0"))],
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_json(&report, true, false, None).unwrap()).unwrap();
+
+        assert_eq!(json["checked_upstream"], true);
+        assert_eq!(json["apps"][0]["latest"], "26.8.1");
+        assert_eq!(json["apps"][0]["status"], "behind");
+        assert_eq!(json["apps"][1]["status"], "current");
+        assert_eq!(json["tools"][0]["latest"], "v0.2.0");
         assert_eq!(json["tools"][0]["status"], "behind");
     }
 
@@ -714,7 +2470,7 @@ mod tests {
             })))
             .mount(&server)
             .await;
-        let client = UpstreamClient::with_bases(server.uri(), server.uri(), server.uri())?;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
 
         let latest = client
             .latest(&pin("npm", "@actual-app/sync-server", None))
@@ -729,14 +2485,25 @@ mod tests {
         let server = MockServer::start().await;
         Mock::given(method("GET"))
             .and(path("/repos/sripwoud/auberge/releases"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
-                { "tag_name": "v0.14.12" },
-                { "tag_name": "grimmory/v2.4.0" },
-                { "tag_name": "grimmory/v2.3.0" },
-            ])))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(
+                        "link",
+                        format!(
+                            r#"<{}/repos/sripwoud/auberge/releases?per_page=100&page=2>; rel="next""#,
+                            server.uri()
+                        ),
+                    )
+                    .set_body_json(serde_json::json!([
+                        { "tag_name": "v0.14.12" },
+                        { "tag_name": "grimmory/v2.4.0" },
+                        { "tag_name": "grimmory/v2.3.0" },
+                    ])),
+            )
+            .expect(1)
             .mount(&server)
             .await;
-        let client = UpstreamClient::with_bases(server.uri(), server.uri(), server.uri())?;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
 
         let latest = client
             .latest(&pin(
@@ -760,7 +2527,7 @@ mod tests {
             )
             .mount(&server)
             .await;
-        let client = UpstreamClient::with_bases(server.uri(), server.uri(), server.uri())?;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
 
         let latest = client
             .latest(&pin("go", "github.com/mholt/caddy-l4", None))
@@ -777,7 +2544,7 @@ mod tests {
             .respond_with(ResponseTemplate::new(200).set_body_string("v0.2.0-beta.1\n"))
             .mount(&server)
             .await;
-        let client = UpstreamClient::with_bases(server.uri(), server.uri(), server.uri())?;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
 
         let result = client.latest(&pin("go", "example.com/mod", None)).await;
 
@@ -790,46 +2557,831 @@ mod tests {
         Ok(())
     }
 
+    fn ansible_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("ansible")
+    }
+
+    fn candidate(site: PinSite, value: &str, file: PathBuf) -> DeclaredPin {
+        DeclaredPin {
+            site,
+            pin: VersionPin {
+                value: value.to_string(),
+                ..pin("npm", "@actual-app/sync-server", None)
+            },
+            file,
+        }
+    }
+
+    #[test]
+    fn declared_pins_narrow_to_an_app_or_a_role_tool() -> Result<()> {
+        let playbooks = ansible_dir().join("playbooks");
+        let roles = ansible_dir().join("roles");
+
+        let app = declared_pins(&playbooks, &roles, Some("paperless"))?;
+        let tool = declared_pins(&playbooks, &roles, Some("blocky/lego"))?;
+
+        assert_eq!(app.len(), 1);
+        assert_eq!(app[0].site, PinSite::App("paperless".to_string()));
+        assert_eq!(app[0].file, playbooks.join("paperless.meta.yml"));
+        assert_eq!(tool.len(), 1);
+        assert_eq!(tool[0].file, roles.join("blocky/defaults/main.yml"));
+        Ok(())
+    }
+
     #[test]
-    fn escape_go_module_encodes_uppercase_as_bang_lowercase() {
+    fn declared_pins_cover_every_declared_pin_without_a_target() -> Result<()> {
+        let playbooks = ansible_dir().join("playbooks");
+        let roles = ansible_dir().join("roles");
+
+        let candidates = declared_pins(&playbooks, &roles, None)?;
+
         assert_eq!(
-            escape_go_module("github.com/Azure/azure-sdk-for-go"),
-            "github.com/!azure/azure-sdk-for-go"
+            candidates.len(),
+            declared_app_versions(&playbooks)?.len() + declared_tool_versions(&roles)?.len()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn declared_pins_reject_an_unknown_target() {
+        let result = declared_pins(
+            &ansible_dir().join("playbooks"),
+            &ansible_dir().join("roles"),
+            Some("caddy"),
         );
+
+        assert!(result.unwrap_err().to_string().contains("`caddy`"));
+    }
+
+    #[test]
+    fn apply_bump_rewrites_the_declaring_file_in_place() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let file = tmp.path().join("main.yml");
+        std::fs::write(
+            &file,
+            "# renovate: datasource=go depName=github.com/mholt/caddy-l4\n\
+             caddy_l4_version: \"v0.1.2\"\n",
+        )?;
+        let tool = PinSite::Tool {
+            role: "caddy".to_string(),
+            tool: "l4".to_string(),
+        };
+
+        let bumps = apply_bumps(&[(
+            candidate(tool, "v0.1.2", file.clone()),
+            "v0.2.0".to_string(),
+        )])?;
+
+        assert_eq!(bumps[0].target, "caddy/l4");
         assert_eq!(
-            escape_go_module("github.com/mholt/caddy-l4"),
-            "github.com/mholt/caddy-l4"
+            (bumps[0].from.as_str(), bumps[0].to.as_str()),
+            ("v0.1.2", "v0.2.0")
         );
+        assert!(std::fs::read_to_string(&file)?.contains("caddy_l4_version: \"v0.2.0\"\n"));
+        assert_eq!(std::fs::read_dir(tmp.path())?.count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn apply_bumps_rewrites_several_pins_of_one_file() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let file = tmp.path().join("main.yml");
+        std::fs::write(
+            &file,
+            "# renovate: datasource=go depName=github.com/mholt/caddy-l4\n\
+             caddy_l4_version: \"v0.1.2\"\n\
+             # renovate: datasource=go depName=github.com/caddy-dns/cloudflare\n\
+             caddy_cloudflare_version: \"v0.1.0\"\n",
+        )?;
+        let tool = |tool: &str| PinSite::Tool {
+            role: "caddy".to_string(),
+            tool: tool.to_string(),
+        };
+
+        apply_bumps(&[
+            (
+                candidate(tool("l4"), "v0.1.2", file.clone()),
+                "v0.2.0".to_string(),
+            ),
+            (
+                candidate(tool("cloudflare"), "v0.1.0", file.clone()),
+                "v0.2.1".to_string(),
+            ),
+        ])?;
+
+        let content = std::fs::read_to_string(&file)?;
+        assert!(content.contains("caddy_l4_version: \"v0.2.0\"\n"));
+        assert!(content.contains("caddy_cloudflare_version: \"v0.2.1\"\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn apply_bumps_leaves_every_file_untouched_when_one_rewrite_fails() -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let good = tmp.path().join("good.yml");
+        let bad = tmp.path().join("bad.yml");
+        let original = "# renovate: datasource=go depName=github.com/mholt/caddy-l4\n\
+                        caddy_l4_version: \"v0.1.2\"\n";
+        std::fs::write(&good, original)?;
+        std::fs::write(&bad, "caddy_l4_version: \"v0.1.2\"\n")?;
+        let tool = || PinSite::Tool {
+            role: "caddy".to_string(),
+            tool: "l4".to_string(),
+        };
+
+        let result = apply_bumps(&[
+            (
+                candidate(tool(), "v0.1.2", good.clone()),
+                "v0.2.0".to_string(),
+            ),
+            (
+                candidate(tool(), "v0.1.2", bad.clone()),
+                "v0.2.0".to_string(),
+            ),
+        ]);
+
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_to_string(&good)?, original);
+        assert_eq!(std::fs::read_dir(tmp.path())?.count(), 2);
+        Ok(())
     }
 
     #[tokio::test]
-    async fn latest_fails_fast_on_upstream_http_errors() -> Result<()> {
+    async fn upstream_bumps_skip_pins_that_are_current() -> Result<()> {
         let server = MockServer::start().await;
         Mock::given(method("GET"))
-            .respond_with(ResponseTemplate::new(500))
+            .and(path("/@actual-app/sync-server"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dist-tags": { "latest": "26.9.0" }
+            })))
             .mount(&server)
             .await;
-        let client = UpstreamClient::with_bases(server.uri(), server.uri(), server.uri())?;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+        let unused = PathBuf::from("unused");
 
-        let result = client
-            .latest(&pin("github-releases", "juanfont/headscale", None))
-            .await;
+        let (planned, failed) = upstream_bumps(
+            vec![
+                candidate(PinSite::App("behind".to_string()), "26.8.0", unused.clone()),
+                candidate(PinSite::App("current".to_string()), "26.9.0", unused),
+            ],
+            &client,
+        )
+        .await;
+        assert!(failed.is_empty());
 
-        assert!(result.is_err());
+        let planned: Vec<(String, &str)> = planned
+            .iter()
+            .map(|(candidate, to)| (candidate.name(), to.as_str()))
+            .collect();
+        assert_eq!(planned, [("behind".to_string(), "26.9.0")]);
         Ok(())
     }
 
     #[tokio::test]
-    async fn latest_rejects_an_unsupported_datasource() -> Result<()> {
-        let client = UpstreamClient::with_bases(
-            "http://unused".to_string(),
-            "http://unused".to_string(),
-            "http://unused".to_string(),
-        )?;
+    async fn upstream_bumps_report_a_failed_lookup_and_plan_the_rest() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/@actual-app/sync-server"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dist-tags": { "latest": "26.9.0" }
+            })))
+            .mount(&server)
+            .await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+        let unused = PathBuf::from("unused");
+        let gone = DeclaredPin {
+            site: PinSite::App("gone".to_string()),
+            pin: VersionPin {
+                value: "1.0.0".to_string(),
+                ..pin("npm", "@gone/server", None)
+            },
+            file: unused.clone(),
+        };
+
+        let (planned, failed) = upstream_bumps(
+            vec![
+                gone,
+                candidate(PinSite::App("behind".to_string()), "26.8.0", unused),
+            ],
+            &client,
+        )
+        .await;
 
-        let result = client.latest(&pin("docker", "some/image", None)).await;
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].0.name(), "behind");
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].target, "gone");
+        assert!(
+            failed[0]
+                .error
+                .contains("Failed to resolve the latest gone release")
+        );
+        Ok(())
+    }
 
-        assert!(result.unwrap_err().to_string().contains("docker"));
+    #[tokio::test]
+    async fn pypi_latest_skips_prereleases_and_fully_yanked_releases() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/pypi/django-q2/json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "releases": {
+                    "1.7.4": [{ "yanked": false }],
+                    "1.8.0": [{ "yanked": true }],
+                    "1.9.0rc1": [{ "yanked": false }],
+                    "1.7.10": [{ "yanked": false }, { "yanked": true }],
+                }
+            })))
+            .mount(&server)
+            .await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+
+        let latest = client.latest(&pin("pypi", "Django_Q2", None)).await?;
+
+        assert_eq!(latest, "1.7.10");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn github_tags_latest_reads_every_tag() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/savonet/liquidsoap/tags"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "name": "v2.3.0" },
+                { "name": "v2.4.0-rc1" },
+                { "name": "rolling-release-v2.3.x" },
+                { "name": "v2.3.3" },
+            ])))
+            .mount(&server)
+            .await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+
+        let latest = client
+            .latest(&pin(
+                "github-tags",
+                "savonet/liquidsoap",
+                Some("^v(?<version>.+)$"),
+            ))
+            .await?;
+
+        assert_eq!(latest, "2.3.3");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn github_tags_latest_follows_link_pagination() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/savonet/liquidsoap/tags"))
+            .and(wiremock::matchers::query_param("page", "2"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!([{ "name": "v2.10.1" }])),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/savonet/liquidsoap/tags"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(
+                        "link",
+                        format!(
+                            r#"<{}/repos/savonet/liquidsoap/tags?per_page=100&page=2>; rel="next""#,
+                            server.uri()
+                        ),
+                    )
+                    .set_body_json(serde_json::json!([
+                        { "name": "v2.9.0" },
+                        { "name": "v2.3.3" },
+                    ])),
+            )
+            .mount(&server)
+            .await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+
+        let latest = client
+            .latest(&pin(
+                "github-tags",
+                "savonet/liquidsoap",
+                Some("^v(?<version>.+)$"),
+            ))
+            .await?;
+
+        assert_eq!(latest, "2.10.1");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn gitlab_latest_addresses_the_encoded_project_and_skips_upcoming() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/fdroid%2Ffdroidserver/releases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "tag_name": "2.4.0", "upcoming_release": true },
+                { "tag_name": "2.3.5" },
+                { "tag_name": "2.3.4" },
+            ])))
+            .mount(&server)
+            .await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+
+        let latest = client
+            .latest(&pin("gitlab-releases", "fdroid/fdroidserver", None))
+            .await?;
+
+        assert_eq!(latest, "2.3.5");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn docker_latest_authenticates_and_keeps_the_pinned_tag_shape() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v2/library/postgres/tags/list"))
+            .and(wiremock::matchers::header("authorization", "Bearer anon"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "tags": ["16", "16.4-alpine", "16.6", "16.6-alpine", "17.2-alpine", "latest"]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v2/library/postgres/tags/list"))
+            .respond_with(ResponseTemplate::new(401).insert_header(
+                "www-authenticate",
+                format!(
+                    r#"Bearer realm="{}/token",service="registry.docker.io",scope="repository:library/postgres:pull""#,
+                    server.uri()
+                ),
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/token"))
+            .and(wiremock::matchers::query_param(
+                "scope",
+                "repository:library/postgres:pull",
+            ))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "token": "anon" })),
+            )
+            .mount(&server)
+            .await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+        let postgres = VersionPin {
+            value: "16.4-alpine".to_string(),
+            ..pin("docker", "postgres", None)
+        };
+
+        let latest = client.latest(&postgres).await?;
+
+        assert_eq!(latest, "17.2-alpine");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn docker_latest_follows_link_pagination() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v2/immich-app/immich-server/tags/list"))
+            .and(wiremock::matchers::query_param("last", "v1.120.0"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "tags": ["v1.131.3"] })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v2/immich-app/immich-server/tags/list"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(
+                        "link",
+                        r#"</v2/immich-app/immich-server/tags/list?n=1000&last=v1.120.0>; rel="next""#,
+                    )
+                    .set_body_json(serde_json::json!({ "tags": ["v1.119.0", "v1.120.0"] })),
+            )
+            .mount(&server)
+            .await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+        let immich = VersionPin {
+            value: "v1.119.0".to_string(),
+            ..pin("docker", "immich-app/immich-server", None)
+        };
+
+        let latest = client.latest(&immich).await?;
+
+        assert_eq!(latest, "v1.131.3");
+        Ok(())
+    }
+
+    #[test]
+    fn docker_registry_resolves_hub_names_and_explicit_hosts() {
+        let hub = "https://registry-1.docker.io";
+        assert_eq!(
+            docker_registry("postgres", hub),
+            (hub.to_string(), "library/postgres".to_string())
+        );
+        assert_eq!(
+            docker_registry("docker.io/valkey/valkey", hub),
+            (hub.to_string(), "valkey/valkey".to_string())
+        );
+        assert_eq!(
+            docker_registry("ghcr.io/immich-app/immich-server", hub),
+            (
+                "https://ghcr.io".to_string(),
+                "immich-app/immich-server".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn is_final_pep440_rejects_pre_and_dev_releases() {
+        assert!(is_final_pep440("2.18.4"));
+        assert!(is_final_pep440("1.0.post1"));
+        assert!(!is_final_pep440("2.0.0rc1"));
+        assert!(!is_final_pep440("1.4b2"));
+        assert!(!is_final_pep440("1.0.dev3"));
+    }
+
+    fn note(version: &str, body: &str) -> ReleaseNote {
+        ReleaseNote {
+            version: version.to_string(),
+            tag: format!("v{version}"),
+            published_at: None,
+            url: None,
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn releases_since_keeps_newer_stable_releases_in_version_order() {
+        let notes = vec![
+            note("5.0.0", ""),
+            note("4.9.0", ""),
+            note("4.10.0", ""),
+            note("4.8.1", ""),
+            note("5.1.0-rc.1", ""),
+        ];
+
+        let versions: Vec<String> = releases_since("4.8.1", notes)
+            .into_iter()
+            .map(|entry| entry.version)
+            .collect();
+
+        assert_eq!(versions, ["4.9.0", "4.10.0", "5.0.0"]);
+    }
+
+    #[test]
+    fn releases_since_flags_each_release_against_the_one_before_it() {
+        let notes = vec![
+            note("4.25.0", "Fixes.\n\nBREAKING CHANGE: drops --path"),
+            note("5.0.0", "Cleanup"),
+            note("5.1.0", "Run the DB migration first"),
+        ];
+
+        let flags: Vec<Vec<ReleaseFlag>> = releases_since("4.24.0", notes)
+            .into_iter()
+            .map(|entry| entry.flags)
+            .collect();
+
+        assert_eq!(
+            flags,
+            [
+                vec![ReleaseFlag::Breaking],
+                vec![ReleaseFlag::Major],
+                vec![ReleaseFlag::Migration],
+            ]
+        );
+    }
+
+    #[test]
+    fn crosses_major_treats_zero_x_minors_as_majors() {
+        assert!(crosses_major("v4.24.0", "v5.0.0"));
+        assert!(!crosses_major("4.24.0", "4.25.0"));
+        assert!(crosses_major("0.28.0", "0.29.0"));
+        assert!(!crosses_major("0.28.0", "0.28.1"));
+    }
+
+    #[tokio::test]
+    async fn release_notes_read_github_bodies_through_extract_version() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/go-acme/lego/releases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {
+                    "tag_name": "v5.0.0",
+                    "body": "Breaking: new CLI",
+                    "html_url": "https://github.com/go-acme/lego/releases/tag/v5.0.0",
+                    "published_at": "2026-07-01T00:00:00Z"
+                },
+                { "tag_name": "v5.1.0-beta.1", "prerelease": true, "body": "beta" },
+            ])))
+            .mount(&server)
+            .await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+
+        let notes = client
+            .release_notes(&pin(
+                "github-releases",
+                "go-acme/lego",
+                Some("^v(?<version>.+)$"),
+            ))
+            .await?;
+
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].version, "5.0.0");
+        assert_eq!(notes[0].tag, "v5.0.0");
+        assert_eq!(notes[0].body, "Breaking: new CLI");
+        assert_eq!(
+            notes[0].published_at.as_deref(),
+            Some("2026-07-01T00:00:00Z")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn release_notes_follow_link_pagination() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/go-acme/lego/releases"))
+            .and(wiremock::matchers::query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "tag_name": "v4.25.0", "body": "Older release" },
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/go-acme/lego/releases"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(
+                        "link",
+                        format!(
+                            r#"<{}/repos/go-acme/lego/releases?per_page=100&page=2>; rel="next", <{}/repos/go-acme/lego/releases?per_page=100&page=2>; rel="last""#,
+                            server.uri(),
+                            server.uri()
+                        ),
+                    )
+                    .set_body_json(serde_json::json!([
+                        { "tag_name": "v5.0.0", "body": "Breaking: new CLI" },
+                    ])),
+            )
+            .mount(&server)
+            .await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+
+        let notes = client
+            .release_notes(&pin(
+                "github-releases",
+                "go-acme/lego",
+                Some("^v(?<version>.+)$"),
+            ))
+            .await?;
+
+        let versions: Vec<&str> = notes.iter().map(|note| note.version.as_str()).collect();
+        assert_eq!(versions, ["5.0.0", "4.25.0"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn release_notes_stop_paging_past_the_declared_version() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/go-acme/lego/releases"))
+            .and(wiremock::matchers::query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/go-acme/lego/releases"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(
+                        "link",
+                        format!(
+                            r#"<{}/repos/go-acme/lego/releases?per_page=100&page=2>; rel="next""#,
+                            server.uri()
+                        ),
+                    )
+                    .set_body_json(serde_json::json!([
+                        { "tag_name": "v5.0.0", "body": "Breaking: new CLI" },
+                        { "tag_name": "v1.0.0", "body": "The declared release" },
+                    ])),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+
+        let notes = client
+            .release_notes(&pin(
+                "github-releases",
+                "go-acme/lego",
+                Some("^v(?<version>.+)$"),
+            ))
+            .await?;
+
+        assert_eq!(notes.len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn release_notes_read_gitlab_descriptions() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/fdroid%2Ffdroidserver/releases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {
+                    "tag_name": "2.3.5",
+                    "description": "Migration notes",
+                    "_links": { "self": "https://gitlab.com/fdroid/fdroidserver/-/releases/2.3.5" }
+                },
+            ])))
+            .mount(&server)
+            .await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+
+        let notes = client
+            .release_notes(&pin("gitlab-releases", "fdroid/fdroidserver", None))
+            .await?;
+
+        assert_eq!(notes[0].body, "Migration notes");
+        assert_eq!(
+            notes[0].url.as_deref(),
+            Some("https://gitlab.com/fdroid/fdroidserver/-/releases/2.3.5")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn release_notes_reject_datasources_without_notes() -> Result<()> {
+        let server = MockServer::start().await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+
+        let result = client
+            .release_notes(&pin("npm", "@actual-app/sync-server", None))
+            .await;
+
+        assert!(result.unwrap_err().to_string().contains("`npm`"));
+        Ok(())
+    }
+
+    fn cached_client(
+        server: &MockServer,
+        dir: &std::path::Path,
+        policy: CachePolicy,
+    ) -> Result<UpstreamClient> {
+        Ok(UpstreamClient::with_bases(mock_bases(server))?
+            .cached(ResponseCache::at(dir.to_path_buf()), policy))
+    }
+
+    fn npm_packument(server_latest: &str) -> ResponseTemplate {
+        ResponseTemplate::new(200)
+            .insert_header("etag", "\"v1\"")
+            .set_body_json(serde_json::json!({ "dist-tags": { "latest": server_latest } }))
+    }
+
+    #[tokio::test]
+    async fn fetch_serves_entries_younger_than_the_max_age_without_a_request() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(npm_packument("26.8.1"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let tmp = tempfile::tempdir()?;
+        let client = cached_client(
+            &server,
+            tmp.path(),
+            CachePolicy::Revalidate(TimeDelta::hours(1)),
+        )?;
+        let actual = pin("npm", "@actual-app/sync-server", None);
+
+        assert_eq!(client.latest(&actual).await?, "26.8.1");
+        assert_eq!(client.latest(&actual).await?, "26.8.1");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fetch_revalidates_stale_entries_with_their_etag() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(wiremock::matchers::header("if-none-match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(npm_packument("26.8.1"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let tmp = tempfile::tempdir()?;
+        let client = cached_client(&server, tmp.path(), CachePolicy::default())?;
+        let actual = pin("npm", "@actual-app/sync-server", None);
+
+        assert_eq!(client.latest(&actual).await?, "26.8.1");
+        assert_eq!(client.latest(&actual).await?, "26.8.1");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fetch_offline_answers_from_the_cache_only() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(npm_packument("26.8.1"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let tmp = tempfile::tempdir()?;
+        let actual = pin("npm", "@actual-app/sync-server", None);
+        cached_client(&server, tmp.path(), CachePolicy::default())?
+            .latest(&actual)
+            .await?;
+        let offline = cached_client(&server, tmp.path(), CachePolicy::Offline)?;
+
+        assert_eq!(offline.latest(&actual).await?, "26.8.1");
+        let miss = offline.latest(&pin("npm", "left-pad", None)).await;
+        assert!(miss.unwrap_err().to_string().contains("--offline"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fetch_keys_posts_by_their_body() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/query"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(2)
+            .mount(&server)
+            .await;
+        let tmp = tempfile::tempdir()?;
+        let client = cached_client(
+            &server,
+            tmp.path(),
+            CachePolicy::Revalidate(TimeDelta::hours(1)),
+        )?;
+
+        client.advisories(&pin("npm", "a", None)).await?;
+        client.advisories(&pin("npm", "b", None)).await?;
+        client.advisories(&pin("npm", "a", None)).await?;
+        Ok(())
+    }
+
+    #[test]
+    fn parse_cache_age_reads_unit_suffixed_durations() {
+        assert_eq!(parse_cache_age("90m"), Ok(TimeDelta::minutes(90)));
+        assert_eq!(parse_cache_age("0s"), Ok(TimeDelta::zero()));
+        assert!(parse_cache_age("soon").is_err());
+    }
+
+    #[test]
+    fn escape_go_module_encodes_uppercase_as_bang_lowercase() {
+        assert_eq!(
+            escape_go_module("github.com/Azure/azure-sdk-for-go"),
+            "github.com/!azure/azure-sdk-for-go"
+        );
+        assert_eq!(
+            escape_go_module("github.com/mholt/caddy-l4"),
+            "github.com/mholt/caddy-l4"
+        );
+    }
+
+    #[tokio::test]
+    async fn latest_fails_fast_on_upstream_http_errors() -> Result<()> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+        let client = UpstreamClient::with_bases(mock_bases(&server))?;
+
+        let result = client
+            .latest(&pin("github-releases", "juanfont/headscale", None))
+            .await;
+
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn latest_rejects_an_unsupported_datasource() -> Result<()> {
+        let client = UpstreamClient::with_bases(UpstreamBases {
+            npm: "http://unused".to_string(),
+            github: "http://unused".to_string(),
+            go: "http://unused".to_string(),
+            pypi: "http://unused".to_string(),
+            gitlab: "http://unused".to_string(),
+            docker_hub: "http://unused".to_string(),
+            osv: "http://unused".to_string(),
+        })?;
+
+        let result = client.latest(&pin("helm", "some/chart", None)).await;
+
+        assert!(result.unwrap_err().to_string().contains("helm"));
         Ok(())
     }
 }
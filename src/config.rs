@@ -1,13 +1,25 @@
+use crate::key_registry::KeyRegistry;
+use crate::profile::Profile;
+use crate::secrets::{SecretRef, resolve_value};
 use eyre::{Context, Result};
 use std::collections::HashMap;
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use std::path::PathBuf;
+use std::sync::Once;
 
 const SENSITIVE_SUFFIXES: &[&str] = &["password", "key", "token", "secret", "cookie", "signature"];
 
 const DEFAULT_TTL: u32 = 300;
 
+/// The table holding per-host overrides: `[hosts.<name>]`.
+const HOSTS_SECTION: &str = "hosts";
+/// `[backup]` holds auberge's own backup settings, never sent to Ansible.
+const BACKUP_SECTION: &str = "backup";
+
+/// Commands load `Config` more than once; legacy keys are worth one warning.
+static LEGACY_WARNING: Once = Once::new();
+
 /// Metadata describing a playbook's config requirements.
 /// Acts as the Key Registry entry for a given playbook.
 #[derive(Debug)]
@@ -104,6 +116,28 @@ impl Preflight {
     }
 }
 
+/// A config key the Key Registry has moved on from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LegacyKey {
+    /// `from` is a former name of `to`.
+    Renamed { from: String, to: String },
+    /// `key` still works but is on its way out; `note` says what to do.
+    Deprecated { key: String, note: String },
+}
+
+/// What `Config::migrate` did with one legacy key.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyMigration {
+    /// The value moved from `from` to `to`.
+    Renamed { from: String, to: String },
+    /// `to` already held the same value, so `from` was dropped.
+    Dropped { from: String, to: String },
+    /// `to` already holds a different value; both are left for the user.
+    Conflict { from: String, to: String },
+    /// Deprecated keys are reported, never removed.
+    Deprecated { key: String, note: String },
+}
+
 /// Merged configuration — the single source of truth for user settings.
 /// Replaces both the old `UserConfig` and the old typed `Config`.
 pub struct Config {
@@ -114,13 +148,26 @@ pub struct Config {
 impl Config {
     // ── Constructors ──────────────────────────────────────────────────────────
 
+    /// `config.toml` of the active Profile.
     pub fn path() -> Result<PathBuf> {
-        dirs::config_dir()
-            .map(|p| p.join("auberge/config.toml"))
-            .ok_or_else(|| eyre::eyre!("Could not determine XDG config directory"))
+        Ok(Self::config_dir()?.join("config.toml"))
     }
 
+    /// Load `config.toml`, warning once per process about keys the Key
+    /// Registry has renamed or deprecated.
     pub fn load() -> Result<Self> {
+        let config = Self::load_quiet()?;
+        LEGACY_WARNING.call_once(|| {
+            if let Ok(registry) = KeyRegistry::embedded() {
+                config.warn_legacy_keys(&registry);
+            }
+        });
+        Ok(config)
+    }
+
+    /// Load `config.toml` without the legacy-key warning, for commands that
+    /// report legacy keys themselves.
+    pub fn load_quiet() -> Result<Self> {
         let path = Self::path()?;
         if !path.exists() {
             eyre::bail!(
@@ -137,10 +184,10 @@ impl Config {
 
     // ── Directory helpers ─────────────────────────────────────────────────────
 
+    /// The active Profile's config directory, holding `config.toml` and
+    /// `hosts.toml`.
     pub fn config_dir() -> Result<PathBuf> {
-        dirs::config_dir()
-            .map(|p| p.join("auberge"))
-            .ok_or_else(|| eyre::eyre!("Could not determine XDG config directory"))
+        Profile::active().config_dir()
     }
 
     pub fn data_dir() -> Result<PathBuf> {
@@ -149,6 +196,110 @@ impl Config {
             .ok_or_else(|| eyre::eyre!("Could not determine XDG data directory"))
     }
 
+    // ── Per-host overrides ────────────────────────────────────────────────────
+
+    /// The config as `host` sees it: the base table with `[hosts.<host>]`
+    /// laid over it key by key, and no `[hosts]` section left. A Host
+    /// without overrides sees the base table.
+    pub fn for_host(&self, host: &str) -> Config {
+        let mut values = self.values.clone();
+        let overrides = values
+            .remove(HOSTS_SECTION)
+            .and_then(|hosts| hosts.get(host).and_then(toml::Value::as_table).cloned());
+        values.extend(overrides.unwrap_or_default());
+        Config {
+            path: self.path.clone(),
+            values,
+        }
+    }
+
+    /// The keys `[hosts.<host>]` overrides, sorted.
+    pub fn host_overrides(&self, host: &str) -> Vec<String> {
+        self.host_table(host)
+            .map(|table| table.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The Hosts with a `[hosts.<host>]` table, sorted.
+    pub fn override_hosts(&self) -> Vec<String> {
+        self.values
+            .get(HOSTS_SECTION)
+            .and_then(toml::Value::as_table)
+            .map(|hosts| {
+                hosts
+                    .iter()
+                    .filter(|(_, table)| table.is_table())
+                    .map(|(host, _)| host.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn set_for_host(&mut self, host: &str, key: &str, value: &str) -> Result<()> {
+        self.insert_for_host(host, key, value)?;
+        self.save()
+    }
+
+    /// A copy of this config with `key` overridden for `host`, not saved:
+    /// what a deploy to `host` would see if the value were written.
+    pub fn with_host_value(&self, host: &str, key: &str, value: &str) -> Result<Config> {
+        let mut config = Config {
+            path: self.path.clone(),
+            values: self.values.clone(),
+        };
+        config.insert_for_host(host, key, value)?;
+        Ok(config)
+    }
+
+    fn insert_for_host(&mut self, host: &str, key: &str, value: &str) -> Result<()> {
+        let hosts = self
+            .values
+            .entry(HOSTS_SECTION)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        let Some(hosts) = hosts.as_table_mut() else {
+            eyre::bail!("`{HOSTS_SECTION}` in config.toml is not a table");
+        };
+        let Some(table) = hosts
+            .entry(host)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+        else {
+            eyre::bail!("`{HOSTS_SECTION}.{host}` in config.toml is not a table");
+        };
+        table.insert(key.to_string(), toml::Value::String(value.to_string()));
+        Ok(())
+    }
+
+    /// Remove `key` from `[hosts.<host>]`, dropping the host's table once
+    /// it is empty.
+    pub fn remove_for_host(&mut self, host: &str, key: &str) -> Result<bool> {
+        let Some(hosts) = self
+            .values
+            .get_mut(HOSTS_SECTION)
+            .and_then(toml::Value::as_table_mut)
+        else {
+            return Ok(false);
+        };
+        let Some(table) = hosts.get_mut(host).and_then(toml::Value::as_table_mut) else {
+            return Ok(false);
+        };
+        if table.remove(key).is_none() {
+            return Ok(false);
+        }
+        if table.is_empty() {
+            hosts.remove(host);
+        }
+        if hosts.is_empty() {
+            self.values.remove(HOSTS_SECTION);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    fn host_table(&self, host: &str) -> Option<&toml::Table> {
+        self.values.get(HOSTS_SECTION)?.get(host)?.as_table()
+    }
+
     // ── Ergonomic accessors for ubiquitous keys ───────────────────────────────
 
     pub fn domain(&self) -> String {
@@ -226,6 +377,28 @@ impl Config {
             .map(|v| v.trim_end_matches('/').to_string())
     }
 
+    /// The `[backup.retention]` table, if config sets one.
+    pub fn backup_retention(&self) -> Option<&toml::Table> {
+        self.values
+            .get(BACKUP_SECTION)?
+            .get("retention")?
+            .as_table()
+    }
+
+    /// The `[backup.repositories]` table, if config sets one.
+    pub fn backup_repositories(&self) -> Option<&toml::Table> {
+        self.values
+            .get(BACKUP_SECTION)?
+            .get("repositories")?
+            .as_table()
+    }
+
+    /// Whether `key` holds a table of structured settings, such as
+    /// `[bichon]`, rather than a single value.
+    pub fn is_section(&self, key: &str) -> bool {
+        self.values.get(key).is_some_and(toml::Value::is_table)
+    }
+
     // ── Mutation ──────────────────────────────────────────────────────────────
 
     pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
@@ -234,6 +407,21 @@ impl Config {
         self.save()
     }
 
+    /// Set every `(key, value)` at the base level, or in `[hosts.<host>]`,
+    /// saving once.
+    pub fn set_all(&mut self, host: Option<&str>, entries: &[(String, String)]) -> Result<()> {
+        for (key, value) in entries {
+            match host {
+                Some(host) => self.insert_for_host(host, key, value)?,
+                None => {
+                    self.values
+                        .insert(key.clone(), toml::Value::String(value.clone()));
+                }
+            }
+        }
+        self.save()
+    }
+
     pub fn remove(&mut self, key: &str) -> Result<bool> {
         if self.values.remove(key).is_none() {
             return Ok(false);
@@ -242,8 +430,81 @@ impl Config {
         Ok(true)
     }
 
+    // ── Key Registry migration ────────────────────────────────────────────────
+
+    /// Every key that is a former name of a registry key, or deprecated.
+    pub fn legacy_keys(&self, registry: &KeyRegistry) -> Vec<LegacyKey> {
+        self.values
+            .keys()
+            .filter_map(|key| {
+                if let Some(to) = registry.renamed_to(key) {
+                    return Some(LegacyKey::Renamed {
+                        from: key.clone(),
+                        to: to.to_string(),
+                    });
+                }
+                registry.deprecation(key).map(|note| LegacyKey::Deprecated {
+                    key: key.clone(),
+                    note: note.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    fn warn_legacy_keys(&self, registry: &KeyRegistry) {
+        for legacy in self.legacy_keys(registry) {
+            match legacy {
+                LegacyKey::Renamed { from, to } => crate::output::warn(&format!(
+                    "Config key '{from}' was renamed to '{to}'; run `auberge config migrate`"
+                )),
+                LegacyKey::Deprecated { key, note } => {
+                    crate::output::warn(&format!("Config key '{key}' is deprecated: {note}"));
+                }
+            }
+        }
+    }
+
+    /// Rename every former key to its registry successor, saving (and
+    /// re-enforcing 0600) only if something changed.
+    pub fn migrate(&mut self, registry: &KeyRegistry) -> Result<Vec<KeyMigration>> {
+        let mut migrations = Vec::new();
+        for legacy in self.legacy_keys(registry) {
+            let (from, to) = match legacy {
+                LegacyKey::Renamed { from, to } => (from, to),
+                LegacyKey::Deprecated { key, note } => {
+                    migrations.push(KeyMigration::Deprecated { key, note });
+                    continue;
+                }
+            };
+            let migration = if !self.values.contains_key(&to) {
+                if let Some(value) = self.values.remove(&from) {
+                    self.values.insert(to.clone(), value);
+                }
+                KeyMigration::Renamed { from, to }
+            } else if self.values.get(&to) == self.values.get(&from) {
+                self.values.remove(&from);
+                KeyMigration::Dropped { from, to }
+            } else {
+                KeyMigration::Conflict { from, to }
+            };
+            migrations.push(migration);
+        }
+        let changed = migrations.iter().any(|m| {
+            matches!(
+                m,
+                KeyMigration::Renamed { .. } | KeyMigration::Dropped { .. }
+            )
+        });
+        if changed {
+            self.save()?;
+        }
+        Ok(migrations)
+    }
+
     // ── Display helpers ───────────────────────────────────────────────────────
 
+    /// Every key with its display value. Sensitive values are masked, but
+    /// name their secret backend (`**** (pass)`) when they have one.
     pub fn keys_redacted(&self) -> Vec<(String, String)> {
         let mut result = Vec::new();
         for (key, val) in &self.values {
@@ -251,7 +512,10 @@ impl Config {
             let display = if is_sensitive {
                 match val {
                     toml::Value::String(s) if s.is_empty() => "(empty)".to_string(),
-                    toml::Value::String(_) => "****".to_string(),
+                    toml::Value::String(s) => match SecretRef::parse(s).backend() {
+                        Some(backend) => format!("**** ({backend})"),
+                        None => "****".to_string(),
+                    },
                     other => value_to_string(other).unwrap_or_default(),
                 }
             } else {
@@ -287,28 +551,51 @@ impl Config {
         Ok(())
     }
 
-    /// Validate that all required keys in `meta` are present and resolved,
-    /// then return a flat map suitable for passing to Ansible.
-    pub fn validate_for(&self, meta: &PlaybookMeta) -> Result<()> {
-        let keys: Vec<&str> = meta.required_keys.iter().map(String::as_str).collect();
+    /// Validate that all required keys in `meta` are present and resolved.
+    /// A key the Key Registry gives a default may be left unset, but once
+    /// set it must resolve like any other.
+    pub fn validate_for(&self, registry: &KeyRegistry, meta: &PlaybookMeta) -> Result<()> {
+        let keys: Vec<&str> = meta
+            .required_keys
+            .iter()
+            .map(String::as_str)
+            .filter(|key| {
+                registry.default_for(key).is_none()
+                    || self.get(key).is_some_and(|v| !v.trim().is_empty())
+            })
+            .collect();
         self.validate_required_resolved(&keys)
     }
 
     // ── Ansible integration ───────────────────────────────────────────────────
 
-    pub fn flatten_for_ansible(&self) -> HashMap<String, String> {
-        flatten_toml(&self.values)
+    /// Every value, resolved, as Ansible extra vars. `[backup]` and per-host
+    /// overrides are not included; flatten [`Config::for_host`] to apply them.
+    pub fn flatten_for_ansible(&self) -> Result<HashMap<String, String>> {
+        let mut values = self.values.clone();
+        values.remove(HOSTS_SECTION);
+        values.remove(BACKUP_SECTION);
+        flatten_toml(&values)
     }
 
     /// Build a [`Preflight`] for `playbook`, validating all required keys.
+    /// Call it on [`Config::for_host`] to deploy with that Host's overrides.
     ///
     /// This is the **only** constructor for `Preflight`.  It looks up the
     /// playbook's requirements in the Key Registry (`PlaybookMeta`), validates
-    /// the config, and returns a capability value that unlocks `AnsibleRunner`.
-    pub fn preflight_for(&self, playbook: &str, tags: Option<&[String]>) -> Result<Preflight> {
+    /// the config, fills in registry defaults, checks every value against its
+    /// declared type, and returns a capability value that unlocks `AnsibleRunner`.
+    pub fn preflight_for(
+        &self,
+        registry: &KeyRegistry,
+        playbook: &str,
+        tags: Option<&[String]>,
+    ) -> Result<Preflight> {
         let meta = PlaybookMeta::for_playbook(playbook, tags);
-        self.validate_for(&meta)?;
-        let flat_vars = self.flatten_for_ansible();
+        self.validate_for(registry, &meta)?;
+        let mut flat_vars = self.flatten_for_ansible()?;
+        registry.apply_defaults(&mut flat_vars);
+        registry.check_all(&flat_vars)?;
         Ok(Preflight { meta, flat_vars })
     }
 
@@ -352,53 +639,23 @@ fn value_to_string(v: &toml::Value) -> Option<String> {
     }
 }
 
-fn resolve_value(v: &str) -> Result<String> {
-    if let Some(rest) = v.strip_prefix("!!") {
-        return Ok(format!("!{rest}"));
-    }
-    if let Some(cmd) = v.strip_prefix('!') {
-        use std::process::Stdio;
-        let output = std::process::Command::new("sh")
-            .arg("-c")
-            .arg(cmd)
-            .stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::null())
-            .output()
-            .wrap_err("Failed to execute shell command")?;
-        if !output.status.success() {
-            let code = output
-                .status
-                .code()
-                .map_or("signal".to_string(), |c| c.to_string());
-            eyre::bail!("Shell command failed (exit {code})");
-        }
-        let stdout =
-            String::from_utf8(output.stdout).wrap_err("Shell command output is not valid UTF-8")?;
-        let resolved = stdout.trim().to_string();
-        if resolved.is_empty() {
-            eyre::bail!("Shell command produced empty output");
-        }
-        return Ok(resolved);
-    }
-    Ok(v.to_string())
-}
-
-fn flatten_toml(table: &toml::Table) -> HashMap<String, String> {
+/// Every scalar of `table` and its subtables, resolved. A value that does
+/// not resolve is an error, never left out for a registry default to fill.
+fn flatten_toml(table: &toml::Table) -> Result<HashMap<String, String>> {
     let mut result = HashMap::new();
     for (key, value) in table {
         match value {
-            toml::Value::Table(inner) => result.extend(flatten_toml(inner)),
+            toml::Value::Table(inner) => result.extend(flatten_toml(inner)?),
             other => {
-                if let Some(s) = value_to_string(other)
-                    && let Ok(resolved) = resolve_value(&s)
-                {
+                if let Some(s) = value_to_string(other) {
+                    let resolved = resolve_value(&s)
+                        .wrap_err_with(|| format!("Failed to resolve config key '{key}'"))?;
                     result.insert(key.clone(), resolved);
                 }
             }
         }
     }
-    result
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -406,6 +663,11 @@ mod tests {
     use super::*;
     use std::os::unix::fs::PermissionsExt;
 
+    fn registry() -> KeyRegistry {
+        KeyRegistry::load(&PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("ansible/keys.yml"))
+            .unwrap()
+    }
+
     fn make_config(toml_str: &str) -> Config {
         let values: toml::Table = toml::from_str(toml_str).unwrap();
         Config {
@@ -506,6 +768,232 @@ mod tests {
         assert!(config.bichon_host_base_url("missing").is_none());
     }
 
+    #[test]
+    fn test_backup_retention_stays_out_of_ansible_vars() {
+        let config = make_config(
+            r#"
+            domain = "example.com"
+            [backup.retention]
+            keep_daily = 14
+            [backup.retention.apps.paperless]
+            keep_yearly = 5
+        "#,
+        );
+        let retention = config.backup_retention().unwrap();
+        assert_eq!(retention["keep_daily"].as_integer(), Some(14));
+        let flat = config.flatten_for_ansible().unwrap();
+        assert!(
+            flat.keys().all(|key| !key.starts_with("backup")),
+            "{flat:?}"
+        );
+        assert_eq!(flat.get("domain").unwrap(), "example.com");
+        assert!(make_config("domain = \"x\"").backup_retention().is_none());
+    }
+
+    // ── per-host overrides ────────────────────────────────────────────────────
+
+    const HOSTS_TOML: &str = r#"
+        domain = "example.com"
+        paperless_subdomain = "docs"
+        paperless_tailscale_ip = "100.64.0.1"
+
+        [hosts.lab]
+        paperless_subdomain = "papers"
+        paperless_tailscale_ip = "100.64.0.2"
+
+        [hosts.prod]
+    "#;
+
+    #[test]
+    fn test_for_host_overlays_the_hosts_table() {
+        let config = make_config(HOSTS_TOML);
+        let lab = config.for_host("lab");
+        assert_eq!(lab.get("paperless_subdomain").unwrap(), "papers");
+        assert_eq!(lab.get("paperless_tailscale_ip").unwrap(), "100.64.0.2");
+        assert_eq!(lab.get("domain").unwrap(), "example.com");
+        assert!(!lab.is_section("hosts"));
+
+        let prod = config.for_host("prod");
+        assert_eq!(prod.get("paperless_subdomain").unwrap(), "docs");
+        let unknown = config.for_host("nowhere");
+        assert_eq!(unknown.get("paperless_subdomain").unwrap(), "docs");
+    }
+
+    #[test]
+    fn test_flatten_for_ansible_never_leaks_host_overrides() {
+        let config = make_config(HOSTS_TOML);
+        let base = config.flatten_for_ansible().unwrap();
+        assert_eq!(base.get("paperless_subdomain").unwrap(), "docs");
+        let lab = config.for_host("lab").flatten_for_ansible().unwrap();
+        assert_eq!(lab.get("paperless_subdomain").unwrap(), "papers");
+        assert_eq!(lab.get("domain").unwrap(), "example.com");
+    }
+
+    #[test]
+    fn test_preflight_for_a_host_uses_its_overrides() {
+        let config = make_config(
+            r#"
+            admin_user_name = "alice"
+            domain = "example.com"
+            tailscale_authkey = "tskey-base"
+            [hosts.lab]
+            tailscale_authkey = "tskey-lab"
+            paperless_tailscale_ip = "not-an-ip"
+        "#,
+        );
+        let preflight = config
+            .preflight_for(&registry(), "infrastructure.yml", None)
+            .unwrap();
+        assert_eq!(
+            preflight.flat_vars().get("tailscale_authkey").unwrap(),
+            "tskey-base"
+        );
+        let err = config
+            .for_host("lab")
+            .preflight_for(&registry(), "infrastructure.yml", None)
+            .unwrap_err();
+        assert!(err.to_string().contains("paperless_tailscale_ip"), "{err}");
+    }
+
+    #[test]
+    fn test_set_and_remove_for_host_edit_the_hosts_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        let mut config = Config {
+            path: config_path.clone(),
+            values: toml::from_str(r#"domain = "example.com""#).unwrap(),
+        };
+
+        config
+            .set_for_host("lab", "paperless_subdomain", "papers")
+            .unwrap();
+        assert_eq!(config.host_overrides("lab"), vec!["paperless_subdomain"]);
+        let saved: toml::Table =
+            toml::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        assert_eq!(
+            saved["hosts"]["lab"]["paperless_subdomain"].as_str(),
+            Some("papers")
+        );
+        assert_eq!(saved["domain"].as_str(), Some("example.com"));
+
+        assert!(!config.remove_for_host("lab", "domain").unwrap());
+        assert!(
+            config
+                .remove_for_host("lab", "paperless_subdomain")
+                .unwrap()
+        );
+        assert!(!config.is_section("hosts"));
+        assert!(config.host_overrides("lab").is_empty());
+    }
+
+    // ── legacy keys / migrate ─────────────────────────────────────────────────
+
+    fn legacy_registry(dir: &std::path::Path) -> KeyRegistry {
+        let path = dir.join("keys.yml");
+        fs::write(
+            &path,
+            r#"
+keys:
+  cloudflare_dns_api_token:
+    secret: true
+    doc: "Cloudflare token"
+    replaces: [cloudflare_api_token]
+  domain:
+    secret: false
+    doc: "Primary domain"
+    replaces: [base_domain]
+  ssh_port:
+    secret: false
+    doc: "SSH port"
+    replaces: [port]
+  bichon_subdomain:
+    secret: false
+    doc: "Bichon subdomain"
+    deprecated: "remove it"
+"#,
+        )
+        .unwrap();
+        KeyRegistry::load(&path).unwrap()
+    }
+
+    #[test]
+    fn test_legacy_keys_lists_renamed_and_deprecated_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = make_config(
+            r#"
+            cloudflare_api_token = "cf"
+            bichon_subdomain = "mail"
+            ssh_port = 22022
+        "#,
+        );
+        assert_eq!(
+            config.legacy_keys(&legacy_registry(dir.path())),
+            vec![
+                LegacyKey::Deprecated {
+                    key: "bichon_subdomain".to_string(),
+                    note: "remove it".to_string(),
+                },
+                LegacyKey::Renamed {
+                    from: "cloudflare_api_token".to_string(),
+                    to: "cloudflare_dns_api_token".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_migrate_renames_drops_and_leaves_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        let toml = r#"
+cloudflare_api_token = "cf"
+base_domain = "example.com"
+domain = "example.com"
+port = 22
+ssh_port = 22022
+bichon_subdomain = "mail"
+"#;
+        fs::write(&config_path, toml).unwrap();
+        fs::set_permissions(&config_path, fs::Permissions::from_mode(0o644)).unwrap();
+        let mut config = Config {
+            path: config_path.clone(),
+            values: toml::from_str(toml).unwrap(),
+        };
+
+        let migrations = config.migrate(&legacy_registry(dir.path())).unwrap();
+
+        let pair = |from: &str, to: &str| (from.to_string(), to.to_string());
+        let (from, to) = pair("base_domain", "domain");
+        assert!(migrations.contains(&KeyMigration::Dropped { from, to }));
+        let (from, to) = pair("cloudflare_api_token", "cloudflare_dns_api_token");
+        assert!(migrations.contains(&KeyMigration::Renamed { from, to }));
+        let (from, to) = pair("port", "ssh_port");
+        assert!(migrations.contains(&KeyMigration::Conflict { from, to }));
+        assert_eq!(migrations.len(), 4);
+
+        let saved: toml::Table =
+            toml::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        assert_eq!(saved["cloudflare_dns_api_token"].as_str(), Some("cf"));
+        assert!(!saved.contains_key("cloudflare_api_token"));
+        assert!(!saved.contains_key("base_domain"));
+        assert_eq!(saved["port"].as_integer(), Some(22));
+        assert_eq!(saved["bichon_subdomain"].as_str(), Some("mail"));
+        let mode = fs::metadata(&config_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_migrate_leaves_an_up_to_date_config_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config {
+            path: dir.path().join("never-written.toml"),
+            values: toml::from_str(r#"domain = "example.com""#).unwrap(),
+        };
+        let migrations = config.migrate(&legacy_registry(dir.path())).unwrap();
+        assert!(migrations.is_empty());
+        assert!(!dir.path().join("never-written.toml").exists());
+    }
+
     // ── keys_redacted ─────────────────────────────────────────────────────────
 
     #[test]
@@ -515,6 +1003,9 @@ mod tests {
             admin_user_name = "alice"
             cloudflare_dns_api_token = "secret123"
             baikal_admin_password = ""
+            tailscale_authkey = "pass:auberge/tailscale"
+            restic_password = "!op read op://vault/restic"
+            restic_repository = "rclone:filen:auberge-backup"
         "#,
         );
         use std::collections::BTreeMap;
@@ -523,6 +1014,12 @@ mod tests {
         assert_eq!(map.get("admin_user_name").unwrap(), "alice");
         assert_eq!(map.get("cloudflare_dns_api_token").unwrap(), "****");
         assert_eq!(map.get("baikal_admin_password").unwrap(), "(empty)");
+        assert_eq!(map.get("tailscale_authkey").unwrap(), "**** (pass)");
+        assert_eq!(map.get("restic_password").unwrap(), "**** (shell)");
+        assert_eq!(
+            map.get("restic_repository").unwrap(),
+            "rclone:filen:auberge-backup"
+        );
     }
 
     // ── validate_required ─────────────────────────────────────────────────────
@@ -622,7 +1119,7 @@ mod tests {
             admin_user_name = "alice"
         "#,
         );
-        let flat = flatten_toml(&config.values);
+        let flat = flatten_toml(&config.values).unwrap();
         assert_eq!(flat.get("domain").unwrap(), "example.com");
         assert_eq!(flat.get("ssh_port").unwrap(), "22022");
         assert_eq!(flat.get("admin_user_name").unwrap(), "alice");
@@ -637,7 +1134,7 @@ mod tests {
             baikal_admin_password = "secret"
         "#,
         );
-        let flat = config.flatten_for_ansible();
+        let flat = config.flatten_for_ansible().unwrap();
         assert_eq!(flat.get("domain").unwrap(), "example.com");
         assert_eq!(flat.get("ssh_port").unwrap(), "22022");
         assert_eq!(flat.get("baikal_admin_password").unwrap(), "secret");
@@ -652,7 +1149,7 @@ mod tests {
             baikal_admin_password = "!echo cmdpassword"
         "#,
         );
-        let flat = config.flatten_for_ansible();
+        let flat = config.flatten_for_ansible().unwrap();
         assert_eq!(flat.get("domain").unwrap(), "example.com");
         assert_eq!(flat.get("baikal_admin_password").unwrap(), "cmdpassword");
     }
@@ -665,22 +1162,25 @@ mod tests {
             baikal_admin_password = "!!literal"
         "#,
         );
-        let flat = config.flatten_for_ansible();
+        let flat = config.flatten_for_ansible().unwrap();
         assert_eq!(flat.get("baikal_admin_password").unwrap(), "!literal");
     }
 
     #[cfg(unix)]
     #[test]
-    fn test_flatten_for_ansible_skips_failed_shell_commands() {
+    fn test_flatten_for_ansible_fails_on_failed_shell_commands() {
         let config = make_config(
             r#"
             domain = "example.com"
             broken_key = "!false"
         "#,
         );
-        let flat = config.flatten_for_ansible();
-        assert_eq!(flat.get("domain").unwrap(), "example.com");
-        assert!(flat.get("broken_key").is_none());
+        let err = config.flatten_for_ansible().unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Failed to resolve config key 'broken_key'"),
+            "{err}"
+        );
     }
 
     // ── get_resolved ──────────────────────────────────────────────────────────
@@ -993,7 +1493,7 @@ ssh_port = 22022
             tailscale_authkey = "tskey-abc123"
         "#,
         );
-        let result = config.preflight_for("infrastructure.yml", None);
+        let result = config.preflight_for(&registry(), "infrastructure.yml", None);
         assert!(result.is_ok());
         let preflight = result.unwrap();
         assert_eq!(preflight.meta().name, "infrastructure.yml");
@@ -1009,7 +1509,7 @@ ssh_port = 22022
         "#,
         );
         let err = config
-            .preflight_for("infrastructure.yml", None)
+            .preflight_for(&registry(), "infrastructure.yml", None)
             .unwrap_err();
         assert!(
             err.to_string().contains("tailscale_authkey"),
@@ -1028,7 +1528,7 @@ ssh_port = 22022
         "#,
         );
         let err = config
-            .preflight_for("infrastructure.yml", None)
+            .preflight_for(&registry(), "infrastructure.yml", None)
             .unwrap_err();
         assert!(
             err.to_string().contains("tailscale_authkey"),
@@ -1041,7 +1541,7 @@ ssh_port = 22022
     fn test_preflight_for_hardening_requires_no_keys() {
         // hardening.yml has no required keys — should succeed with empty config
         let config = make_config("");
-        let result = config.preflight_for("hardening.yml", None);
+        let result = config.preflight_for(&registry(), "hardening.yml", None);
         assert!(result.is_ok());
     }
 
@@ -1055,7 +1555,9 @@ ssh_port = 22022
             ssh_port = 22022
         "#,
         );
-        let preflight = config.preflight_for("infrastructure.yml", None).unwrap();
+        let preflight = config
+            .preflight_for(&registry(), "infrastructure.yml", None)
+            .unwrap();
         let flat = preflight.flat_vars();
         assert_eq!(flat.get("domain").unwrap(), "example.com");
         assert_eq!(flat.get("ssh_port").unwrap(), "22022");
@@ -1072,7 +1574,9 @@ ssh_port = 22022
         "#,
         );
         // Missing colporteur_subdomain
-        let err = config.preflight_for("apps.yml", Some(&tags)).unwrap_err();
+        let err = config
+            .preflight_for(&registry(), "apps.yml", Some(&tags))
+            .unwrap_err();
         assert!(
             err.to_string().contains("colporteur_subdomain"),
             "error should mention missing tag key: {}",
@@ -1089,11 +1593,76 @@ ssh_port = 22022
             tailscale_authkey = "tskey-supersecret"
         "#,
         );
-        let preflight = config.preflight_for("infrastructure.yml", None).unwrap();
+        let preflight = config
+            .preflight_for(&registry(), "infrastructure.yml", None)
+            .unwrap();
         // flat_vars contains the actual (unredacted) secret value for ansible
         assert_eq!(
             preflight.flat_vars().get("tailscale_authkey").unwrap(),
             "tskey-supersecret"
         );
     }
+
+    #[test]
+    fn test_preflight_for_rejects_a_mistyped_value() {
+        let config = make_config(
+            r#"
+            admin_user_name = "alice"
+            ssh_port = "22o22"
+            hostname = "vps"
+        "#,
+        );
+        let err = config
+            .preflight_for(&registry(), "bootstrap.yml", None)
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("ssh_port: expected an integer, got `22o22`"),
+            "error should point at the offending key: {err}"
+        );
+    }
+
+    #[test]
+    fn test_preflight_for_fills_registry_defaults() {
+        let config = make_config(
+            r#"
+            admin_user_name = "alice"
+            domain = "example.com"
+            tailscale_authkey = "tskey-abc"
+            gokapi_subdomain = ""
+        "#,
+        );
+        let preflight = config
+            .preflight_for(&registry(), "infrastructure.yml", None)
+            .unwrap();
+        assert_eq!(
+            preflight.flat_vars().get("gokapi_subdomain").unwrap(),
+            "share"
+        );
+        assert_eq!(
+            preflight.flat_vars().get("actual_subdomain").unwrap(),
+            "actual"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_preflight_for_never_defaults_a_value_that_fails_to_resolve() {
+        let config = make_config(
+            r#"
+            admin_user_name = "alice"
+            domain = "example.com"
+            tailscale_authkey = "tskey-abc"
+            gokapi_subdomain = "!false"
+        "#,
+        );
+        let err = config
+            .preflight_for(&registry(), "infrastructure.yml", None)
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Failed to resolve config key 'gokapi_subdomain'"),
+            "{err}"
+        );
+    }
 }
@@ -0,0 +1,603 @@
+use crate::key_registry::KeyRegistry;
+use crate::secrets::{SecretRef, escape_literal, expand_home};
+use clap::ValueEnum;
+use eyre::{Result, WrapErr};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+/// The shape of a file `auberge config import` reads.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ImportFormat {
+    /// `KEY=value` lines, as in a `.env` file.
+    Env,
+    /// A YAML mapping, as in Ansible `group_vars` and `host_vars`.
+    Yaml,
+    /// A TOML table.
+    Toml,
+}
+
+impl ImportFormat {
+    /// The format `path`'s name implies: `.env`, `*.env` and `.env.*` are
+    /// env files, `.yml`/`.yaml` YAML, `.toml` TOML.
+    pub fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        if name == ".env" || name.starts_with(".env.") {
+            return Some(Self::Env);
+        }
+        match path.extension()?.to_str()? {
+            "env" => Some(Self::Env),
+            "yml" | "yaml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// The values read from an import file, in file order, and the keys that
+/// cannot become a config value with the reason why.
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportedValues {
+    pub values: Vec<(String, String)>,
+    pub skipped: Vec<(String, String)>,
+}
+
+pub fn read(path: &Path, format: ImportFormat) -> Result<ImportedValues> {
+    let content =
+        fs::read_to_string(path).wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+    parse(&content, format).wrap_err_with(|| format!("Failed to parse {}", path.display()))
+}
+
+pub fn parse(content: &str, format: ImportFormat) -> Result<ImportedValues> {
+    match format {
+        ImportFormat::Env => parse_env(content),
+        ImportFormat::Yaml => parse_yaml(content),
+        ImportFormat::Toml => parse_toml(content),
+    }
+}
+
+/// `KEY=value` lines, optionally `export`ed. Keys are lowercased to match
+/// the Key Registry; values may be single-quoted (taken as is),
+/// double-quoted (with `\"`, `\\` and `\n` escapes) or bare, where a ` #`
+/// starts a comment.
+fn parse_env(content: &str) -> Result<ImportedValues> {
+    let mut imported = ImportedValues::default();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, raw)) = line.split_once('=') else {
+            eyre::bail!("line {}: expected KEY=value", index + 1);
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = env_value(raw.trim()).wrap_err_with(|| format!("line {}", index + 1))?;
+        imported.push(key, value);
+    }
+    Ok(imported)
+}
+
+fn env_value(raw: &str) -> Result<String> {
+    if let Some(rest) = raw.strip_prefix('\'') {
+        let Some((value, _)) = rest.split_once('\'') else {
+            eyre::bail!("unterminated single quote");
+        };
+        return Ok(value.to_string());
+    }
+    let Some(rest) = raw.strip_prefix('"') else {
+        let value = raw.split_once(" #").map_or(raw, |(value, _)| value);
+        return Ok(value.trim_end().to_string());
+    };
+    let mut value = String::new();
+    let mut chars = rest.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Ok(value),
+            '\\' => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some(escaped) => value.push(escaped),
+                None => break,
+            },
+            c => value.push(c),
+        }
+    }
+    eyre::bail!("unterminated double quote")
+}
+
+/// A top-level YAML mapping. Lists, maps and tagged values such as
+/// `!vault` have no config equivalent and are skipped.
+fn parse_yaml(content: &str) -> Result<ImportedValues> {
+    let document: serde_yaml::Value = serde_yaml::from_str(content)?;
+    let mut imported = ImportedValues::default();
+    let mapping = match document {
+        serde_yaml::Value::Mapping(mapping) => mapping,
+        serde_yaml::Value::Null => return Ok(imported),
+        _ => eyre::bail!("expected a mapping of variables"),
+    };
+    for (key, value) in mapping {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+        match value {
+            serde_yaml::Value::String(s) => imported.push(key.to_string(), s),
+            serde_yaml::Value::Number(n) => imported.push(key.to_string(), n.to_string()),
+            serde_yaml::Value::Bool(b) => imported.push(key.to_string(), b.to_string()),
+            serde_yaml::Value::Null => imported.skip(key, "empty"),
+            serde_yaml::Value::Tagged(tagged) if tagged.tag == "vault" => {
+                imported.skip(key, "vault-encrypted; decrypt it or set it by hand")
+            }
+            serde_yaml::Value::Tagged(_) => imported.skip(key, "tagged value"),
+            serde_yaml::Value::Sequence(_) | serde_yaml::Value::Mapping(_) => {
+                imported.skip(key, "not a single value")
+            }
+        }
+    }
+    Ok(imported)
+}
+
+/// The top-level keys of a TOML table. Tables and arrays are skipped.
+fn parse_toml(content: &str) -> Result<ImportedValues> {
+    let table: toml::Table = toml::from_str(content)?;
+    let mut imported = ImportedValues::default();
+    for (key, value) in table {
+        match value {
+            toml::Value::String(s) => imported.push(key, s),
+            toml::Value::Integer(i) => imported.push(key, i.to_string()),
+            toml::Value::Float(f) => imported.push(key, f.to_string()),
+            toml::Value::Boolean(b) => imported.push(key, b.to_string()),
+            toml::Value::Datetime(d) => imported.push(key, d.to_string()),
+            toml::Value::Array(_) | toml::Value::Table(_) => {
+                imported.skip(&key, "not a single value")
+            }
+        }
+    }
+    Ok(imported)
+}
+
+impl ImportedValues {
+    fn push(&mut self, key: String, value: String) {
+        if value.is_empty() {
+            self.skip(&key, "empty");
+        } else {
+            self.values.push((key, value));
+        }
+    }
+
+    fn skip(&mut self, key: &str, reason: &str) {
+        self.skipped.push((key.to_string(), reason.to_string()));
+    }
+}
+
+/// Where `config import` puts the values of secret keys.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SecretStore {
+    /// In `config.toml`, as read.
+    Literal,
+    /// In `pass` at `<prefix>/<key>`, referenced as `pass:<prefix>/<key>`.
+    Pass(String),
+    /// In the file `<dir>/<key>`, referenced as `file:<dir>/<key>`.
+    File(String),
+}
+
+impl FromStr for SecretStore {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "literal" => Ok(Self::Literal),
+            Some(("pass", prefix)) if !prefix.trim_matches('/').is_empty() => {
+                Ok(Self::Pass(prefix.trim_matches('/').to_string()))
+            }
+            Some(("file", dir)) if !dir.is_empty() => {
+                Ok(Self::File(dir.trim_end_matches('/').to_string()))
+            }
+            _ => Err(format!(
+                "'{s}' is not a secret store; use literal, pass:PREFIX or file:DIR"
+            )),
+        }
+    }
+}
+
+impl SecretStore {
+    /// The config value that will refer to `key`'s secret.
+    pub fn reference(&self, key: &str, value: &str) -> String {
+        match self {
+            Self::Literal => escape_literal(value),
+            Self::Pass(prefix) => format!("pass:{prefix}/{key}"),
+            Self::File(dir) => format!("file:{dir}/{key}"),
+        }
+    }
+
+    /// Whether storing `key` would replace a secret the store already holds.
+    pub fn holds(&self, key: &str) -> bool {
+        match self {
+            Self::Literal => false,
+            Self::Pass(prefix) => {
+                pass_store_dir().is_some_and(|dir| dir.join(format!("{prefix}/{key}.gpg")).exists())
+            }
+            Self::File(dir) => expand_home(dir).join(key).exists(),
+        }
+    }
+
+    /// Put `value` in the store and return the config value referring to it.
+    pub fn store(&self, key: &str, value: &str) -> Result<String> {
+        match self {
+            Self::Literal => {}
+            Self::Pass(prefix) => {
+                let name = format!("{prefix}/{key}");
+                let mut child = Command::new("pass")
+                    .args(["insert", "--multiline", "--force", &name])
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::piped())
+                    .spawn()
+                    .wrap_err("Failed to run `pass`; is it installed?")?;
+                if let Some(mut stdin) = child.stdin.take() {
+                    writeln!(stdin, "{value}")?;
+                }
+                let output = child.wait_with_output()?;
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    eyre::bail!(
+                        "`pass insert {name}` failed: {}",
+                        stderr.lines().next().unwrap_or_default()
+                    );
+                }
+            }
+            Self::File(dir) => {
+                let dir = expand_home(dir);
+                let mut builder = fs::DirBuilder::new();
+                builder.recursive(true);
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::DirBuilderExt;
+                    builder.mode(0o700);
+                }
+                builder
+                    .create(&dir)
+                    .wrap_err_with(|| format!("Failed to create {}", dir.display()))?;
+                let path = dir.join(key);
+                fs::write(&path, format!("{value}\n"))
+                    .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).wrap_err_with(
+                        || format!("Failed to set permissions on {}", path.display()),
+                    )?;
+                }
+            }
+        }
+        Ok(self.reference(key, value))
+    }
+
+    pub fn backend(&self) -> Option<&'static str> {
+        match self {
+            Self::Literal => None,
+            Self::Pass(_) => Some("pass"),
+            Self::File(_) => Some("file"),
+        }
+    }
+}
+
+fn pass_store_dir() -> Option<PathBuf> {
+    match std::env::var("PASSWORD_STORE_DIR") {
+        Ok(dir) if !dir.is_empty() => Some(expand_home(&dir)),
+        _ => dirs::home_dir().map(|home| home.join(".password-store")),
+    }
+}
+
+/// One value an import will write.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportEntry {
+    pub key: String,
+    /// The value `config.toml` will hold unless it goes to the store.
+    pub value: String,
+    /// A secret key with a literal value: it goes to the chosen
+    /// [`SecretStore`]. Backend references are written as they are.
+    pub store_secret: bool,
+}
+
+/// What importing a file would do, key by key, before anything is written.
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportPlan {
+    pub entries: Vec<ImportEntry>,
+    /// Keys already holding the imported value.
+    pub unchanged: Vec<String>,
+    /// Keys already holding a different value.
+    pub conflicts: Vec<String>,
+    /// Former key names, with the current name they were imported as.
+    pub renamed: Vec<(String, String)>,
+    /// Keys the Key Registry does not know.
+    pub unknown: Vec<String>,
+    /// Keys whose value breaks their Key Registry entry, with why.
+    pub invalid: Vec<(String, String)>,
+}
+
+impl ImportPlan {
+    /// Map `imported` through `registry` against the values `current`
+    /// returns. A key named twice, or by its former and current name,
+    /// takes the value read last. Values are literals, escaped so that
+    /// `!cmd` or `pass:x` reads back as itself, unless `keep_references`
+    /// keeps them as secret backend references.
+    pub fn new(
+        registry: &KeyRegistry,
+        imported: &ImportedValues,
+        current: impl Fn(&str) -> Option<String>,
+        keep_references: bool,
+    ) -> Self {
+        let mut plan = Self::default();
+        let mut latest: HashMap<String, String> = HashMap::new();
+        let mut order: Vec<String> = Vec::new();
+        for (name, value) in &imported.values {
+            let key = match registry.renamed_to(name) {
+                Some(to) => {
+                    plan.renamed.push((name.clone(), to.to_string()));
+                    to.to_string()
+                }
+                None if registry.get(name).is_some() => name.clone(),
+                None => {
+                    plan.unknown.push(name.clone());
+                    continue;
+                }
+            };
+            if latest.insert(key.clone(), value.clone()).is_none() {
+                order.push(key);
+            }
+        }
+        for key in order {
+            let value = latest.remove(&key).unwrap_or_default();
+            let (literal, written) = if keep_references {
+                let literal = matches!(SecretRef::parse(&value), SecretRef::Literal(_));
+                (literal, value.clone())
+            } else {
+                (true, escape_literal(&value))
+            };
+            if literal && let Err(e) = registry.check(&key, &value) {
+                plan.invalid.push((key, format!("{e:#}")));
+                continue;
+            }
+            match current(&key).filter(|v| !v.trim().is_empty()) {
+                Some(existing) if existing == written => plan.unchanged.push(key),
+                Some(_) => {
+                    plan.conflicts.push(key.clone());
+                    plan.push(registry, key, written, literal);
+                }
+                None => plan.push(registry, key, written, literal),
+            }
+        }
+        plan
+    }
+
+    fn push(&mut self, registry: &KeyRegistry, key: String, value: String, literal: bool) {
+        let secret = registry.get(&key).is_some_and(|entry| entry.secret);
+        self.entries.push(ImportEntry {
+            key,
+            value,
+            store_secret: secret && literal,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGISTRY: &str = r#"
+keys:
+  domain:
+    secret: false
+    doc: "Primary domain"
+  ssh_port:
+    secret: false
+    doc: "SSH port"
+    type: int
+  restic_password:
+    secret: true
+    doc: "Restic passphrase"
+  tailscale_authkey:
+    secret: true
+    doc: "Tailscale pre-auth key"
+    replaces: [ts_authkey]
+"#;
+
+    fn registry() -> KeyRegistry {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.yml");
+        fs::write(&path, REGISTRY).unwrap();
+        KeyRegistry::load(&path).unwrap()
+    }
+
+    fn values(imported: &ImportedValues) -> Vec<(&str, &str)> {
+        imported
+            .values
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_from_file_name() {
+        let detect = |name: &str| ImportFormat::detect(Path::new(name));
+        assert_eq!(detect(".env"), Some(ImportFormat::Env));
+        assert_eq!(detect("prod/.env.local"), Some(ImportFormat::Env));
+        assert_eq!(detect("vps.env"), Some(ImportFormat::Env));
+        assert_eq!(detect("group_vars/all.yml"), Some(ImportFormat::Yaml));
+        assert_eq!(detect("vars.toml"), Some(ImportFormat::Toml));
+        assert_eq!(detect("group_vars/all"), None);
+    }
+
+    #[test]
+    fn test_parse_env_handles_quotes_comments_and_export() {
+        let imported = parse(
+            "# comment\n\
+             DOMAIN=example.com # trailing\n\
+             export SSH_PORT=2222\n\
+             RESTIC_PASSWORD='p#ss \"x\"'\n\
+             TAILSCALE_AUTHKEY=\"a\\\"b\"\n\
+             EMPTY=\n",
+            ImportFormat::Env,
+        )
+        .unwrap();
+        assert_eq!(
+            values(&imported),
+            vec![
+                ("domain", "example.com"),
+                ("ssh_port", "2222"),
+                ("restic_password", "p#ss \"x\""),
+                ("tailscale_authkey", "a\"b"),
+            ]
+        );
+        assert_eq!(imported.skipped, vec![("empty".into(), "empty".into())]);
+        assert!(parse("NOT A LINE\n", ImportFormat::Env).is_err());
+        assert!(parse("A=\"open\n", ImportFormat::Env).is_err());
+    }
+
+    #[test]
+    fn test_parse_yaml_skips_structures_and_vault() {
+        let imported = parse(
+            "domain: example.com\n\
+             ssh_port: 2222\n\
+             users: [a, b]\n\
+             restic_password: !vault |\n  $ANSIBLE_VAULT;1.1;AES256\n  3836\n",
+            ImportFormat::Yaml,
+        )
+        .unwrap();
+        assert_eq!(
+            values(&imported),
+            vec![("domain", "example.com"), ("ssh_port", "2222")]
+        );
+        let skipped: Vec<&str> = imported.skipped.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(skipped, vec!["users", "restic_password"]);
+        assert!(imported.skipped[1].1.starts_with("vault-encrypted"));
+        assert_eq!(
+            parse("", ImportFormat::Yaml).unwrap(),
+            ImportedValues::default()
+        );
+    }
+
+    #[test]
+    fn test_parse_toml_skips_tables() {
+        let imported = parse(
+            "domain = \"example.com\"\nssh_port = 2222\n[hosts.vps]\ndomain = \"x\"\n",
+            ImportFormat::Toml,
+        )
+        .unwrap();
+        assert_eq!(
+            values(&imported),
+            vec![("domain", "example.com"), ("ssh_port", "2222")]
+        );
+        assert_eq!(imported.skipped[0].0, "hosts");
+    }
+
+    #[test]
+    fn test_plan_maps_through_the_registry() {
+        let imported = ImportedValues {
+            values: vec![
+                ("domain".into(), "new.example.com".into()),
+                ("ssh_port".into(), "twenty-two".into()),
+                ("ts_authkey".into(), "tskey-1".into()),
+                ("restic_password".into(), "pass:restic".into()),
+                ("mystery".into(), "x".into()),
+            ],
+            skipped: Vec::new(),
+        };
+        let current = |key: &str| (key == "domain").then(|| "old.example.com".to_string());
+        let plan = ImportPlan::new(&registry(), &imported, current, true);
+
+        assert_eq!(plan.unknown, vec!["mystery"]);
+        assert_eq!(
+            plan.renamed,
+            vec![("ts_authkey".to_string(), "tailscale_authkey".to_string())]
+        );
+        assert_eq!(plan.invalid.len(), 1);
+        assert_eq!(plan.invalid[0].0, "ssh_port");
+        assert_eq!(plan.conflicts, vec!["domain"]);
+        let entries: Vec<(&str, bool)> = plan
+            .entries
+            .iter()
+            .map(|e| (e.key.as_str(), e.store_secret))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("domain", false),
+                ("tailscale_authkey", true),
+                ("restic_password", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_keeps_matching_values_unchanged() {
+        let imported = ImportedValues {
+            values: vec![("domain".into(), "example.com".into())],
+            skipped: Vec::new(),
+        };
+        let plan = ImportPlan::new(
+            &registry(),
+            &imported,
+            |_| Some("example.com".to_string()),
+            false,
+        );
+        assert_eq!(plan.unchanged, vec!["domain"]);
+        assert!(plan.entries.is_empty() && plan.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_plan_imports_values_as_literals_by_default() {
+        let imported = ImportedValues {
+            values: vec![
+                ("domain".into(), "!abc".into()),
+                ("restic_password".into(), "pass:restic".into()),
+            ],
+            skipped: Vec::new(),
+        };
+        let plan = ImportPlan::new(&registry(), &imported, |_| None, false);
+
+        assert_eq!(plan.entries[0].value, "!!abc");
+        assert_eq!(
+            crate::secrets::resolve_value(&plan.entries[0].value).unwrap(),
+            "!abc"
+        );
+        assert_eq!(plan.entries[1].value, "pass::restic");
+        assert!(plan.entries[1].store_secret);
+        assert_eq!(
+            SecretStore::Literal.reference("restic_password", "pass:restic"),
+            "pass::restic"
+        );
+
+        let plan = ImportPlan::new(&registry(), &imported, |_| Some("!!abc".into()), false);
+        assert_eq!(plan.unchanged, vec!["domain"]);
+    }
+
+    #[test]
+    fn test_secret_store_parses_and_references() {
+        assert_eq!("literal".parse(), Ok(SecretStore::Literal));
+        assert_eq!(
+            "pass:auberge/".parse(),
+            Ok(SecretStore::Pass("auberge".to_string()))
+        );
+        assert!("pass:".parse::<SecretStore>().is_err());
+        assert!("vault".parse::<SecretStore>().is_err());
+        let store: SecretStore = "file:~/.secrets".parse().unwrap();
+        assert_eq!(store.reference("k", "v"), "file:~/.secrets/k");
+    }
+
+    #[test]
+    fn test_file_store_writes_a_private_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SecretStore::File(dir.path().join("s").display().to_string());
+        assert!(!store.holds("restic_password"));
+        let reference = store.store("restic_password", "hunter2").unwrap();
+        assert!(store.holds("restic_password"));
+        assert_eq!(
+            crate::secrets::resolve_value(&reference).unwrap(),
+            "hunter2"
+        );
+    }
+}
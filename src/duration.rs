@@ -0,0 +1,55 @@
+use chrono::TimeDelta;
+
+/// Parse a `<number><s|m|h|d>` duration, e.g. `90m` or `24h` — the spelling
+/// every duration flag takes. `None` for anything else, including a value
+/// too large for a [`TimeDelta`]; callers word the error for their flag.
+pub fn parse(input: &str) -> Option<TimeDelta> {
+    let mut chars = input.trim().chars();
+    let unit = chars.next_back()?;
+    let value: i64 = chars.as_str().parse().ok()?;
+    if value < 0 {
+        return None;
+    }
+    match unit {
+        's' => TimeDelta::try_seconds(value),
+        'm' => TimeDelta::try_minutes(value),
+        'h' => TimeDelta::try_hours(value),
+        'd' => TimeDelta::try_days(value),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_unit() {
+        assert_eq!(parse("45s"), Some(TimeDelta::seconds(45)));
+        assert_eq!(parse("90m"), Some(TimeDelta::minutes(90)));
+        assert_eq!(parse(" 24h "), Some(TimeDelta::hours(24)));
+        assert_eq!(parse("2d"), Some(TimeDelta::days(2)));
+    }
+
+    #[test]
+    fn zero_is_allowed() {
+        assert_eq!(parse("0h"), Some(TimeDelta::zero()));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        for input in ["", "24", "h", "abc", "-1h", "24x", "1.5h", "24 h"] {
+            assert_eq!(parse(input), None, "expected '{input}' to be rejected");
+        }
+    }
+
+    #[test]
+    fn rejects_multibyte_unit_without_panicking() {
+        assert_eq!(parse("24é"), None);
+    }
+
+    #[test]
+    fn rejects_overflowing_value() {
+        assert_eq!(parse("9999999999999999999d"), None);
+    }
+}
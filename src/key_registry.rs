@@ -1,16 +1,253 @@
+use crate::ansible_assets::AnsibleAssets;
 use eyre::{Result, WrapErr};
-use serde::{Deserialize, Serialize};
+use regex::Regex;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::json;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
+use std::net::IpAddr;
 use std::path::Path;
 
+static EMBEDDED_KEYS: &str = include_str!("../ansible/keys.yml");
+
+/// The shape a key's value must have, declared as `type:` in `keys.yml`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyType {
+    #[default]
+    String,
+    Int,
+    Bool,
+    Ip,
+    Email,
+    Url,
+    /// A number with a unit: `30s`, `15m`, `12h` or `7d`, as
+    /// [`crate::duration::parse`] reads it.
+    Duration,
+    /// Comma-separated items; `pattern` and `enum` apply to each item.
+    List,
+}
+
+impl KeyType {
+    /// The JSON Schema constraints for the type. TOML integers and
+    /// booleans are accepted as-is as well as in their string form.
+    fn json_schema(self) -> serde_json::Map<String, serde_json::Value> {
+        let schema = match self {
+            Self::String | Self::Ip | Self::List => json!({ "type": "string" }),
+            Self::Int => json!({ "type": ["integer", "string"], "pattern": "^-?[0-9]+$" }),
+            Self::Bool => json!({
+                "type": ["boolean", "string"],
+                "enum": [true, false, "true", "false"]
+            }),
+            Self::Email => json!({ "type": "string", "format": "email" }),
+            Self::Url => json!({ "type": "string", "format": "uri" }),
+            Self::Duration => json!({ "type": "string", "pattern": "^[0-9]+[smhd]$" }),
+        };
+        let serde_json::Value::Object(map) = schema else {
+            unreachable!("every type schema is an object")
+        };
+        map
+    }
+
+    fn expected(self) -> &'static str {
+        match self {
+            Self::String => "a string",
+            Self::Int => "an integer",
+            Self::Bool => "true or false",
+            Self::Ip => "an IP address",
+            Self::Email => "an email address",
+            Self::Url => "a URL",
+            Self::Duration => "a duration such as 30s, 15m, 12h or 7d",
+            Self::List => "a comma-separated list",
+        }
+    }
+
+    fn accepts(self, value: &str) -> bool {
+        match self {
+            Self::String | Self::List => true,
+            Self::Int => value.parse::<i64>().is_ok(),
+            Self::Bool => matches!(value, "true" | "false"),
+            Self::Ip => value.parse::<IpAddr>().is_ok(),
+            Self::Email => is_email(value),
+            Self::Url => reqwest::Url::parse(value).is_ok_and(|url| url.has_host()),
+            Self::Duration => is_duration(value),
+        }
+    }
+}
+
+/// How `auberge config generate` and `config init --generate-secrets` fill
+/// a key in, declared as `generate:` in `keys.yml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Generator {
+    /// `alnum: 32` — that many random characters from `A-Za-z0-9`.
+    Alnum(usize),
+    /// `hex: 32` — that many random bytes, hex-encoded (twice as many characters).
+    Hex(usize),
+    /// `bcrypt: other_key` — a bcrypt hash of another key's value.
+    Bcrypt(String),
+}
+
+impl Generator {
+    /// The key whose value this generator hashes, if any.
+    pub fn source(&self) -> Option<&str> {
+        match self {
+            Self::Bcrypt(source) => Some(source),
+            Self::Alnum(_) | Self::Hex(_) => None,
+        }
+    }
+
+    /// Generate a value; `source` is the value of [`Self::source`].
+    fn generate(&self, source: Option<&str>) -> Result<String> {
+        match self {
+            Self::Alnum(len) => random_alnum(*len),
+            Self::Hex(bytes) => {
+                let mut buf = vec![0u8; *bytes];
+                openssl::rand::rand_bytes(&mut buf)?;
+                Ok(buf.iter().map(|b| format!("{b:02x}")).collect())
+            }
+            Self::Bcrypt(key) => {
+                let Some(source) = source.filter(|v| !v.trim().is_empty()) else {
+                    eyre::bail!("'{key}' must be set first: its value is what gets hashed");
+                };
+                Ok(bcrypt::hash(source, bcrypt::DEFAULT_COST)?)
+            }
+        }
+    }
+}
+
 /// A single entry in the Key Registry describing one configuration key.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct KeyEntry {
     /// Whether the value should be treated as a secret (redacted in output).
     pub secret: bool,
     /// Human-readable description of what the key configures.
     pub doc: String,
+    #[serde(default, rename = "type")]
+    pub kind: KeyType,
+    /// Regex the whole value (each item, for a list) must match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// The only values the key accepts; empty means any.
+    #[serde(
+        default,
+        rename = "enum",
+        deserialize_with = "scalars",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub allowed: Vec<String>,
+    /// Value Ansible receives when config leaves the key unset or empty.
+    #[serde(
+        default,
+        deserialize_with = "optional_scalar",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub default: Option<String>,
+    /// Former names of this key, rewritten by `auberge config migrate`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replaces: Vec<String>,
+    /// Why the key is on its way out and what to do instead. A deprecated
+    /// key still works, but is left out of new scaffolds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+    /// How to fill the key in without asking the user to invent a value.
+    #[serde(
+        default,
+        with = "serde_yaml::with::singleton_map",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub generate: Option<Generator>,
+}
+
+impl KeyEntry {
+    /// The key's JSON Schema. The type, pattern and allowed values sit in
+    /// an `anyOf` beside `""`, the unset placeholder `config init` writes.
+    fn json_schema(&self, required_by: &[String]) -> serde_json::Value {
+        let mut constraints = self.kind.json_schema();
+        if self.kind != KeyType::List {
+            if let Some(pattern) = &self.pattern {
+                constraints.insert("pattern".into(), json!(format!("^(?:{pattern})$")));
+            }
+            if !self.allowed.is_empty() {
+                constraints.insert("enum".into(), json!(self.allowed));
+            }
+        }
+        let mut schema = serde_json::Map::new();
+        schema.insert("anyOf".into(), json!([{ "const": "" }, constraints]));
+        let mut description = self.doc.clone();
+        if self.secret {
+            description.push_str(" (secret)");
+        }
+        if !required_by.is_empty() {
+            let _ = write!(description, "\n\nRequired by: {}", required_by.join(", "));
+        }
+        if let Some(note) = &self.deprecated {
+            let _ = write!(description, "\n\nDeprecated: {note}");
+            schema.insert("deprecated".into(), json!(true));
+        }
+        schema.insert("description".into(), json!(description));
+        if let Some(default) = &self.default {
+            schema.insert("default".into(), json!(default));
+        }
+        schema.insert("x-secret".into(), json!(self.secret));
+        if !required_by.is_empty() {
+            schema.insert("x-required-by".into(), json!(required_by));
+        }
+        serde_json::Value::Object(schema)
+    }
+
+    /// Check `value` against the entry's type, pattern and allowed values,
+    /// returning why it fails. An empty value is unset and always passes:
+    /// whether a key is required is the Playbook Meta's business. A secret's
+    /// value never appears in the reason.
+    pub fn check(&self, value: &str) -> std::result::Result<(), String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(());
+        }
+        let got = |item: &str| {
+            if self.secret {
+                String::new()
+            } else {
+                format!(", got `{item}`")
+            }
+        };
+        if !self.kind.accepts(value) {
+            return Err(format!("expected {}{}", self.kind.expected(), got(value)));
+        }
+        let items: Vec<&str> = match self.kind {
+            KeyType::List => value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .collect(),
+            _ => vec![value],
+        };
+        let pattern = match &self.pattern {
+            Some(pattern) => Some(anchored(pattern).map_err(|e| e.to_string())?),
+            None => None,
+        };
+        for item in items {
+            if let Some(regex) = &pattern
+                && !regex.is_match(item)
+            {
+                return Err(format!(
+                    "expected a match for /{}/{}",
+                    regex.as_str(),
+                    got(item)
+                ));
+            }
+            if !self.allowed.is_empty() && !self.allowed.iter().any(|a| a == item) {
+                return Err(format!(
+                    "expected one of {}{}",
+                    self.allowed.join(", "),
+                    got(item)
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Raw deserialization wrapper for `keys.yml`.
@@ -30,9 +267,177 @@ impl KeyRegistry {
     pub fn load(path: &Path) -> Result<Self> {
         let contents = std::fs::read_to_string(path)
             .wrap_err_with(|| format!("Failed to read Key Registry from {}", path.display()))?;
-        let file: KeyRegistryFile = serde_yaml::from_str(&contents)
-            .wrap_err_with(|| format!("Failed to parse Key Registry from {}", path.display()))?;
-        Ok(Self { entries: file.keys })
+        Self::parse(&contents, &path.display().to_string())
+    }
+
+    /// Load the Key Registry shipped with the bundled Ansible assets.
+    pub fn bundled() -> Result<Self> {
+        let assets = AnsibleAssets::prepare()?;
+        Self::load(&assets.ansible_dir().join("keys.yml"))
+    }
+
+    /// The Key Registry compiled into the binary, read without extracting
+    /// the Ansible assets.
+    pub fn embedded() -> Result<Self> {
+        Self::parse(EMBEDDED_KEYS, "the embedded keys.yml")
+    }
+
+    fn parse(contents: &str, origin: &str) -> Result<Self> {
+        let file: KeyRegistryFile = serde_yaml::from_str(contents)
+            .wrap_err_with(|| format!("Failed to parse Key Registry from {origin}"))?;
+        let registry = Self { entries: file.keys };
+        registry
+            .validate()
+            .wrap_err_with(|| format!("Invalid Key Registry in {origin}"))?;
+        Ok(registry)
+    }
+
+    /// Every pattern must compile, every default must pass its own entry,
+    /// a former name must belong to exactly one key and no longer be a key
+    /// itself, and a generator must produce something: a non-zero length,
+    /// or a bcrypt of a key that is not itself a hash.
+    fn validate(&self) -> Result<()> {
+        let mut former: HashMap<&str, &str> = HashMap::new();
+        for (name, entry) in &self.entries {
+            for old in &entry.replaces {
+                if self.entries.contains_key(old) {
+                    eyre::bail!("Key '{name}' replaces '{old}', which is still a key");
+                }
+                if let Some(other) = former.insert(old, name) {
+                    eyre::bail!("Keys '{other}' and '{name}' both replace '{old}'");
+                }
+            }
+            if let Some(pattern) = &entry.pattern {
+                anchored(pattern)
+                    .wrap_err_with(|| format!("Key '{name}' has an invalid pattern"))?;
+            }
+            if let Some(default) = &entry.default
+                && let Err(reason) = entry.check(default)
+            {
+                eyre::bail!("Key '{name}' has an invalid default: {reason}");
+            }
+            match &entry.generate {
+                Some(Generator::Alnum(0) | Generator::Hex(0)) => {
+                    eyre::bail!("Key '{name}' generates an empty value")
+                }
+                Some(Generator::Bcrypt(source)) => match self.entries.get(source) {
+                    None => eyre::bail!("Key '{name}' hashes '{source}', which is not a key"),
+                    Some(other) if matches!(other.generate, Some(Generator::Bcrypt(_))) => {
+                        eyre::bail!("Key '{name}' hashes '{source}', which is itself a hash")
+                    }
+                    Some(_) => {}
+                },
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Check `value` for `key`, naming the key in the error. Keys unknown
+    /// to the registry pass.
+    pub fn check(&self, key: &str, value: &str) -> Result<()> {
+        match self.entries.get(key).map(|entry| entry.check(value)) {
+            Some(Err(reason)) => eyre::bail!("Invalid value for config key '{key}': {reason}"),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check every variable the registry knows, reporting all offending
+    /// keys at once.
+    pub fn check_all(&self, vars: &HashMap<String, String>) -> Result<()> {
+        let mut problems: Vec<String> = vars
+            .iter()
+            .filter_map(|(key, value)| {
+                let reason = self.entries.get(key)?.check(value).err()?;
+                Some(format!("{key}: {reason}"))
+            })
+            .collect();
+        if problems.is_empty() {
+            return Ok(());
+        }
+        problems.sort();
+        eyre::bail!("Invalid config values:\n  {}", problems.join("\n  "))
+    }
+
+    /// The key that replaced `old`, if `old` is a former name.
+    pub fn renamed_to(&self, old: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(_, entry)| entry.replaces.iter().any(|r| r == old))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Why `key` is deprecated, if it is.
+    pub fn deprecation(&self, key: &str) -> Option<&str> {
+        self.entries.get(key)?.deprecated.as_deref()
+    }
+
+    /// The registry default for `key`, if it declares one.
+    pub fn default_for(&self, key: &str) -> Option<&str> {
+        self.entries.get(key)?.default.as_deref()
+    }
+
+    /// Fill in the registry default for every key `vars` leaves unset or empty.
+    pub fn apply_defaults(&self, vars: &mut HashMap<String, String>) {
+        for (name, entry) in &self.entries {
+            let Some(default) = &entry.default else {
+                continue;
+            };
+            let unset = vars.get(name).is_none_or(|v| v.trim().is_empty());
+            if unset {
+                vars.insert(name.clone(), default.clone());
+            }
+        }
+    }
+
+    /// Generate a value for `key` from its registry generator. `lookup`
+    /// supplies the current value of the key a bcrypt generator hashes.
+    pub fn generate(&self, key: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String> {
+        let Some(generator) = self.entries.get(key).and_then(|e| e.generate.as_ref()) else {
+            eyre::bail!("Key '{key}' has no generator in the Key Registry");
+        };
+        let source = generator.source().and_then(&lookup);
+        let value = generator
+            .generate(source.as_deref())
+            .wrap_err_with(|| format!("Failed to generate '{key}'"))?;
+        self.check(key, &value)?;
+        Ok(value)
+    }
+
+    /// Generate every key in `names` that has a generator: random values
+    /// first, then the hashes of the values just generated. A hash whose
+    /// source was not generated is left out.
+    pub fn generate_all<'a>(
+        &self,
+        names: impl Iterator<Item = &'a str>,
+    ) -> Result<HashMap<String, String>> {
+        let (hashes, randoms): (Vec<&str>, Vec<&str>) = names
+            .filter(|name| {
+                self.entries
+                    .get(*name)
+                    .is_some_and(|e| e.generate.is_some())
+            })
+            .partition(|name| {
+                self.entries[*name]
+                    .generate
+                    .as_ref()
+                    .is_some_and(|g| g.source().is_some())
+            });
+        let mut values = HashMap::new();
+        for name in randoms {
+            values.insert(name.to_string(), self.generate(name, |_| None)?);
+        }
+        for name in hashes {
+            let source = self.entries[name]
+                .generate
+                .as_ref()
+                .and_then(Generator::source);
+            if let Some(value) = source.and_then(|s| values.get(s)).cloned() {
+                let hash = self.generate(name, |_| Some(value.clone()))?;
+                values.insert(name.to_string(), hash);
+            }
+        }
+        Ok(values)
     }
 
     /// Returns the entry for a key by name, if it exists.
@@ -59,37 +464,180 @@ impl KeyRegistry {
         self.entries.is_empty()
     }
 
-    /// Render a TOML scaffold containing every key in the registry, sorted by name.
+    /// A JSON Schema (draft-07) for `config.toml`, for TOML language servers
+    /// to complete keys and flag typos with. `required_by` maps a key to the
+    /// apps whose Playbook Meta requires it. Former names are accepted but
+    /// marked deprecated; any other unknown top-level name must be a table,
+    /// such as `[bichon]`, and `[hosts.<name>]` tables take registry keys only.
+    pub fn json_schema(&self, required_by: &HashMap<String, Vec<String>>) -> serde_json::Value {
+        let mut names: Vec<&String> = self.entries.keys().collect();
+        names.sort_unstable();
+        let mut keys = serde_json::Map::new();
+        for name in names {
+            let entry = &self.entries[name];
+            let apps = required_by.get(name).map(Vec::as_slice).unwrap_or_default();
+            keys.insert(name.clone(), entry.json_schema(apps));
+            for old in &entry.replaces {
+                keys.insert(
+                    old.clone(),
+                    json!({
+                        "description": format!("Renamed to `{name}`; run `auberge config migrate`"),
+                        "deprecated": true
+                    }),
+                );
+            }
+        }
+        let mut properties = keys.clone();
+        properties.insert(
+            "hosts".into(),
+            json!({
+                "type": "object",
+                "description": "Per-Host overrides: `[hosts.<name>]` overlays these keys when targeting that Host",
+                "additionalProperties": {
+                    "type": "object",
+                    "properties": keys,
+                    "additionalProperties": false
+                }
+            }),
+        );
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "auberge config.toml",
+            "description": "Generated from the Key Registry by `auberge config schema`",
+            "type": "object",
+            "properties": properties,
+            "additionalProperties": { "type": "object" }
+        })
+    }
+
+    /// Render a TOML scaffold containing every current key in the registry,
+    /// sorted by name. Deprecated keys are left out.
     pub fn scaffold(&self) -> String {
-        self.render(self.entries.keys().map(String::as_str))
+        self.render(self.current_keys(), &HashMap::new())
     }
 
     /// Render a TOML scaffold containing only keys whose name is in `selected`.
     /// Keys in `selected` that are absent from the registry are silently skipped.
     pub fn scaffold_filtered(&self, selected: &HashSet<String>) -> String {
-        self.render(
-            selected
-                .iter()
-                .filter(|k| self.entries.contains_key(*k))
-                .map(String::as_str),
-        )
+        self.render(self.selected_keys(selected), &HashMap::new())
     }
 
-    fn render<'a>(&self, names: impl Iterator<Item = &'a str>) -> String {
+    /// [`Self::scaffold`] or, given a selection, [`Self::scaffold_filtered`],
+    /// with every key that has a generator filled in.
+    pub fn scaffold_generated(&self, selected: Option<&HashSet<String>>) -> Result<String> {
+        let names: Vec<&str> = match selected {
+            Some(selected) => self.selected_keys(selected).collect(),
+            None => self.current_keys().collect(),
+        };
+        let values = self.generate_all(names.iter().copied())?;
+        Ok(self.render(names.into_iter(), &values))
+    }
+
+    fn current_keys(&self) -> impl Iterator<Item = &str> {
+        self.entries
+            .iter()
+            .filter(|(_, entry)| entry.deprecated.is_none())
+            .map(|(name, _)| name.as_str())
+    }
+
+    fn selected_keys<'a>(&self, selected: &'a HashSet<String>) -> impl Iterator<Item = &'a str> {
+        selected
+            .iter()
+            .filter(|k| self.entries.contains_key(*k))
+            .map(String::as_str)
+    }
+
+    fn render<'a>(
+        &self,
+        names: impl Iterator<Item = &'a str>,
+        values: &HashMap<String, String>,
+    ) -> String {
         let mut sorted: Vec<&str> = names.collect();
         sorted.sort_unstable();
         let mut out = String::new();
         for name in sorted {
             let entry = &self.entries[name];
             let marker = if entry.secret { " (secret)" } else { "" };
-            let _ = writeln!(out, "# {}{marker}", entry.doc);
-            let _ = writeln!(out, "{name} = \"\"");
+            let default = match &entry.default {
+                Some(default) => format!(" (default: {default})"),
+                None => String::new(),
+            };
+            let _ = writeln!(out, "# {}{marker}{default}", entry.doc);
+            let value = values.get(name).map(String::as_str).unwrap_or_default();
+            let _ = writeln!(out, "{name} = {}", toml::Value::from(value));
             let _ = writeln!(out);
         }
         out
     }
 }
 
+/// `len` random characters from `A-Za-z0-9`, drawn without modulo bias.
+fn random_alnum(len: usize) -> Result<String> {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+    // The largest multiple of the alphabet size that fits in a byte.
+    let limit = (256 / ALPHABET.len() * ALPHABET.len()) as u8;
+    let mut out = String::with_capacity(len);
+    let mut buf = [0u8; 64];
+    while out.len() < len {
+        openssl::rand::rand_bytes(&mut buf)?;
+        for &b in buf.iter().filter(|&&b| b < limit) {
+            if out.len() == len {
+                break;
+            }
+            out.push(ALPHABET[usize::from(b) % ALPHABET.len()] as char);
+        }
+    }
+    Ok(out)
+}
+
+/// `pattern` compiled to match the whole value, not a substring of it.
+fn anchored(pattern: &str) -> std::result::Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{pattern})$"))
+}
+
+fn is_email(value: &str) -> bool {
+    match value.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !value.contains(char::is_whitespace)
+                && !domain.contains('@')
+        }
+        None => false,
+    }
+}
+
+fn is_duration(value: &str) -> bool {
+    crate::duration::parse(value).is_some()
+}
+
+fn scalar(value: serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// `default: 22` and `default: "22"` both mean the string `22`.
+fn optional_scalar<'de, D: Deserializer<'de>>(
+    d: D,
+) -> std::result::Result<Option<String>, D::Error> {
+    Option::<serde_yaml::Value>::deserialize(d)?
+        .map(|value| scalar(value).ok_or_else(|| D::Error::custom("expected a scalar default")))
+        .transpose()
+}
+
+fn scalars<'de, D: Deserializer<'de>>(d: D) -> std::result::Result<Vec<String>, D::Error> {
+    Vec::<serde_yaml::Value>::deserialize(d)?
+        .into_iter()
+        .map(|value| scalar(value).ok_or_else(|| D::Error::custom("expected scalar enum values")))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,6 +654,14 @@ mod tests {
         assert!(!registry.is_empty());
     }
 
+    #[test]
+    fn test_embedded_registry_matches_the_file() {
+        let embedded = KeyRegistry::embedded().unwrap();
+        let loaded = KeyRegistry::load(&registry_path()).unwrap();
+        assert_eq!(embedded.len(), loaded.len());
+        assert!(embedded.get("ssh_port").is_some());
+    }
+
     #[test]
     fn test_key_registry_contains_required_ansible_runner_keys() {
         let registry = KeyRegistry::load(&registry_path()).unwrap();
@@ -195,6 +751,7 @@ mod tests {
             KeyEntry {
                 secret: false,
                 doc: "Primary domain name".into(),
+                ..KeyEntry::default()
             },
         );
         entries.insert(
@@ -202,6 +759,7 @@ mod tests {
             KeyEntry {
                 secret: true,
                 doc: "Tailscale auth key".into(),
+                ..KeyEntry::default()
             },
         );
         entries.insert(
@@ -209,6 +767,7 @@ mod tests {
             KeyEntry {
                 secret: false,
                 doc: "Admin username".into(),
+                ..KeyEntry::default()
             },
         );
         KeyRegistry { entries }
@@ -289,4 +848,380 @@ mod tests {
         let parsed: toml::Table = toml::from_str(&scaffold).unwrap();
         assert_eq!(parsed.len(), registry.len());
     }
+
+    const TYPED_YAML: &str = r#"
+keys:
+  ssh_port:
+    secret: false
+    doc: "SSH port"
+    type: int
+  admin_user_email:
+    secret: false
+    doc: "Admin email"
+    type: email
+  bichon_tailscale_ip:
+    secret: false
+    doc: "Tailscale IP"
+    type: ip
+  tailscale_login_server:
+    secret: false
+    doc: "Login server"
+    type: url
+  colporteur_freshrss_sync:
+    secret: false
+    doc: "Sync feeds"
+    type: bool
+    default: false
+  hermes_llm_provider:
+    secret: false
+    doc: "LLM provider"
+    enum: [openai, anthropic]
+  hermes_telegram_allowed_users:
+    secret: false
+    doc: "Allowed users"
+    type: list
+    pattern: "[A-Za-z0-9_]{5,32}"
+  immich_db_password:
+    secret: true
+    doc: "DB password"
+    pattern: "[A-Za-z0-9]{16,}"
+  gokapi_subdomain:
+    secret: false
+    doc: "Gokapi subdomain"
+    default: share
+"#;
+
+    fn typed_registry() -> KeyRegistry {
+        let file: KeyRegistryFile = serde_yaml::from_str(TYPED_YAML).unwrap();
+        let registry = KeyRegistry { entries: file.keys };
+        registry.validate().unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_check_accepts_well_typed_values() {
+        let registry = typed_registry();
+        for (key, value) in [
+            ("ssh_port", "22022"),
+            ("admin_user_email", "admin@example.com"),
+            ("bichon_tailscale_ip", "100.64.0.7"),
+            ("bichon_tailscale_ip", "fd7a:115c:a1e0::1"),
+            ("tailscale_login_server", "https://hs.example.com"),
+            ("colporteur_freshrss_sync", "true"),
+            ("hermes_llm_provider", "anthropic"),
+            ("hermes_telegram_allowed_users", "alice_b, carol_d"),
+            ("immich_db_password", "abcdefgh12345678"),
+            ("ssh_port", ""),
+            ("unknown_key", "anything"),
+        ] {
+            registry
+                .check(key, value)
+                .unwrap_or_else(|e| panic!("{key} = {value:?}: {e}"));
+        }
+    }
+
+    #[test]
+    fn test_check_names_the_key_and_the_expectation() {
+        let registry = typed_registry();
+        let cases = [
+            ("ssh_port", "22o22", "expected an integer, got `22o22`"),
+            ("admin_user_email", "admin", "expected an email address"),
+            ("bichon_tailscale_ip", "100.64.0", "expected an IP address"),
+            ("tailscale_login_server", "hs.example.com", "expected a URL"),
+            ("colporteur_freshrss_sync", "yes", "expected true or false"),
+            (
+                "hermes_llm_provider",
+                "gpt",
+                "expected one of openai, anthropic",
+            ),
+            ("hermes_telegram_allowed_users", "alice_b, bo", "got `bo`"),
+        ];
+        for (key, value, expected) in cases {
+            let err = registry.check(key, value).unwrap_err().to_string();
+            assert!(
+                err.starts_with(&format!("Invalid value for config key '{key}'")),
+                "{err}"
+            );
+            assert!(err.contains(expected), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_check_never_echoes_a_secret_value() {
+        let err = typed_registry()
+            .check("immich_db_password", "hunter2")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("expected a match for"), "{err}");
+        assert!(!err.contains("hunter2"), "{err}");
+    }
+
+    #[test]
+    fn test_check_all_reports_every_offending_key() {
+        let vars = HashMap::from([
+            ("ssh_port".to_string(), "abc".to_string()),
+            ("bichon_tailscale_ip".to_string(), "nope".to_string()),
+            ("domain".to_string(), "example.com".to_string()),
+        ]);
+        let err = typed_registry().check_all(&vars).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Invalid config values:\n  bichon_tailscale_ip: expected an IP address, got `nope`\n  ssh_port: expected an integer, got `abc`"
+        );
+    }
+
+    #[test]
+    fn test_apply_defaults_fills_unset_and_empty_keys_only() {
+        let mut vars = HashMap::from([
+            ("gokapi_subdomain".to_string(), String::new()),
+            ("colporteur_freshrss_sync".to_string(), "true".to_string()),
+        ]);
+        typed_registry().apply_defaults(&mut vars);
+        assert_eq!(vars["gokapi_subdomain"], "share");
+        assert_eq!(vars["colporteur_freshrss_sync"], "true");
+        assert_eq!(
+            typed_registry().default_for("gokapi_subdomain"),
+            Some("share")
+        );
+        assert_eq!(typed_registry().default_for("ssh_port"), None);
+    }
+
+    #[test]
+    fn test_load_rejects_a_default_that_fails_its_own_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.yml");
+        std::fs::write(
+            &path,
+            "keys:\n  ssh_port:\n    secret: false\n    doc: SSH port\n    type: int\n    default: twenty-two\n",
+        )
+        .unwrap();
+        let err = KeyRegistry::load(&path).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("Key 'ssh_port' has an invalid default"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn test_scaffold_shows_defaults() {
+        let scaffold = typed_registry().scaffold();
+        assert!(scaffold.contains("# Gokapi subdomain (default: share)\ngokapi_subdomain = \"\""));
+    }
+
+    #[test]
+    fn test_duration_requires_a_unit() {
+        assert!(is_duration("30s"));
+        assert!(is_duration("7d"));
+        assert!(!is_duration("2w"));
+        assert!(!is_duration("30"));
+        assert!(!is_duration("m"));
+        assert!(!is_duration("1.5h"));
+    }
+
+    const RENAMED_YAML: &str = r#"
+keys:
+  cloudflare_dns_api_token:
+    secret: true
+    doc: "Cloudflare token"
+    replaces: [cloudflare_api_token, cf_token]
+  bichon_subdomain:
+    secret: false
+    doc: "Bichon subdomain"
+    deprecated: "Bichon is served on its tailnet IP; remove this key"
+  domain:
+    secret: false
+    doc: "Primary domain"
+"#;
+
+    fn registry_from(yaml: &str) -> Result<KeyRegistry> {
+        let file: KeyRegistryFile = serde_yaml::from_str(yaml).unwrap();
+        let registry = KeyRegistry { entries: file.keys };
+        registry.validate()?;
+        Ok(registry)
+    }
+
+    #[test]
+    fn test_renamed_to_finds_the_successor_of_a_former_name() {
+        let registry = registry_from(RENAMED_YAML).unwrap();
+        assert_eq!(
+            registry.renamed_to("cf_token"),
+            Some("cloudflare_dns_api_token")
+        );
+        assert_eq!(registry.renamed_to("cloudflare_dns_api_token"), None);
+        assert_eq!(registry.renamed_to("domain"), None);
+    }
+
+    #[test]
+    fn test_deprecated_keys_are_left_out_of_the_scaffold() {
+        let registry = registry_from(RENAMED_YAML).unwrap();
+        assert_eq!(
+            registry.deprecation("bichon_subdomain"),
+            Some("Bichon is served on its tailnet IP; remove this key")
+        );
+        assert_eq!(registry.deprecation("domain"), None);
+        let scaffold = registry.scaffold();
+        assert!(!scaffold.contains("bichon_subdomain"));
+        assert!(scaffold.contains("domain = \"\""));
+    }
+
+    #[test]
+    fn test_validate_rejects_ambiguous_or_live_former_names() {
+        let live = "keys:\n  a:\n    secret: false\n    doc: A\n    replaces: [b]\n  b:\n    secret: false\n    doc: B\n";
+        assert_eq!(
+            registry_from(live).unwrap_err().to_string(),
+            "Key 'a' replaces 'b', which is still a key"
+        );
+        let twice = "keys:\n  a:\n    secret: false\n    doc: A\n    replaces: [old]\n  b:\n    secret: false\n    doc: B\n    replaces: [old]\n";
+        assert!(
+            registry_from(twice)
+                .unwrap_err()
+                .to_string()
+                .ends_with("both replace 'old'")
+        );
+    }
+
+    const GENERATED_YAML: &str = r#"
+keys:
+  radio_listener_password:
+    secret: true
+    doc: "Radio password"
+    generate: { alnum: 24 }
+  radio_listener_hash:
+    secret: true
+    doc: "Radio password hash"
+    generate: { bcrypt: radio_listener_password }
+  yourls_api_signature:
+    secret: true
+    doc: "YOURLS signature"
+    generate: { hex: 16 }
+  domain:
+    secret: false
+    doc: "Primary domain"
+"#;
+
+    #[test]
+    fn test_generate_alnum_and_hex_have_the_declared_shape() {
+        let registry = registry_from(GENERATED_YAML).unwrap();
+        let password = registry
+            .generate("radio_listener_password", |_| None)
+            .unwrap();
+        assert_eq!(password.len(), 24);
+        assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+        let token = registry.generate("yourls_api_signature", |_| None).unwrap();
+        assert_eq!(token.len(), 32);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(
+            token,
+            registry.generate("yourls_api_signature", |_| None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_generate_bcrypt_hashes_its_source_and_needs_it_set() {
+        let registry = registry_from(GENERATED_YAML).unwrap();
+        let hash = registry
+            .generate("radio_listener_hash", |key| {
+                (key == "radio_listener_password").then(|| "hunter2".to_string())
+            })
+            .unwrap();
+        assert!(bcrypt::verify("hunter2", &hash).unwrap());
+        let err = registry
+            .generate("radio_listener_hash", |_| None)
+            .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("'radio_listener_password' must be set first"),
+            "{err:#}"
+        );
+        assert_eq!(
+            registry
+                .generate("domain", |_| None)
+                .unwrap_err()
+                .to_string(),
+            "Key 'domain' has no generator in the Key Registry"
+        );
+    }
+
+    #[test]
+    fn test_scaffold_generated_fills_only_keys_with_generators() {
+        let registry = registry_from(GENERATED_YAML).unwrap();
+        let scaffold = registry.scaffold_generated(None).unwrap();
+        assert!(scaffold.contains("domain = \"\""));
+        assert!(!scaffold.contains("radio_listener_password = \"\""));
+        assert!(!scaffold.contains("radio_listener_hash = \"\""));
+
+        let selected = HashSet::from(["radio_listener_hash".to_string()]);
+        let scaffold = registry.scaffold_generated(Some(&selected)).unwrap();
+        assert_eq!(scaffold, registry.scaffold_filtered(&selected));
+    }
+
+    #[test]
+    fn test_validate_rejects_generators_that_cannot_produce_a_value() {
+        let empty = "keys:\n  a:\n    secret: true\n    doc: A\n    generate: { hex: 0 }\n";
+        assert_eq!(
+            registry_from(empty).unwrap_err().to_string(),
+            "Key 'a' generates an empty value"
+        );
+        let dangling = "keys:\n  a:\n    secret: true\n    doc: A\n    generate: { bcrypt: b }\n";
+        assert_eq!(
+            registry_from(dangling).unwrap_err().to_string(),
+            "Key 'a' hashes 'b', which is not a key"
+        );
+    }
+
+    #[test]
+    fn test_bundled_generated_secrets_pass_their_entries() {
+        let registry = KeyRegistry::load(&registry_path()).unwrap();
+        let values = registry.generate_all(registry.current_keys()).unwrap();
+        assert!(values.contains_key("immich_db_password"));
+        assert!(!values.contains_key("cloudflare_dns_api_token"));
+    }
+
+    #[test]
+    fn test_json_schema_describes_keys_former_names_and_host_overrides() {
+        let registry = registry_from(RENAMED_YAML).unwrap();
+        let required_by = HashMap::from([("domain".to_string(), vec!["apps".to_string()])]);
+        let schema = registry.json_schema(&required_by);
+        let properties = &schema["properties"];
+
+        assert_eq!(
+            properties["domain"]["anyOf"],
+            json!([{ "const": "" }, { "type": "string" }])
+        );
+        assert_eq!(properties["domain"]["x-required-by"], json!(["apps"]));
+        assert_eq!(
+            properties["domain"]["description"],
+            "Primary domain\n\nRequired by: apps"
+        );
+        assert_eq!(properties["cloudflare_dns_api_token"]["x-secret"], true);
+        assert_eq!(properties["bichon_subdomain"]["deprecated"], true);
+        assert_eq!(properties["cf_token"]["deprecated"], true);
+        assert_eq!(
+            properties["hosts"]["additionalProperties"]["properties"]["domain"],
+            properties["domain"]
+        );
+        assert_eq!(schema["additionalProperties"], json!({ "type": "object" }));
+    }
+
+    #[test]
+    fn test_json_schema_carries_type_pattern_enum_and_default() {
+        let entry = KeyEntry {
+            kind: KeyType::Int,
+            pattern: Some("[1-9][0-9]{0,4}".into()),
+            default: Some("22".into()),
+            ..KeyEntry::default()
+        };
+        let schema = entry.json_schema(&[]);
+        assert_eq!(schema["anyOf"][0], json!({ "const": "" }));
+        assert_eq!(schema["anyOf"][1]["type"], json!(["integer", "string"]));
+        assert_eq!(schema["anyOf"][1]["pattern"], "^(?:[1-9][0-9]{0,4})$");
+        assert_eq!(schema["default"], "22");
+        assert!(schema.get("x-required-by").is_none());
+
+        let list = KeyEntry {
+            kind: KeyType::List,
+            allowed: vec!["a".into()],
+            ..KeyEntry::default()
+        };
+        assert!(list.json_schema(&[])["anyOf"][1].get("enum").is_none());
+    }
 }
@@ -1,11 +1,15 @@
 mod ansible_assets;
 mod commands;
 mod config;
+mod config_import;
+mod duration;
 mod hosts;
 mod key_registry;
 mod output;
 mod playbook_meta;
+mod profile;
 mod prompt;
+mod secrets;
 mod services;
 mod signal;
 mod ssh_config;
@@ -21,10 +25,11 @@ use commands::backup::{
 };
 use commands::bichon::{BichonCommands, run_bichon_command};
 use commands::config_cmd::{
-    ConfigCommands, run_config_edit, run_config_get, run_config_init, run_config_list,
-    run_config_path, run_config_remove, run_config_set,
+    ConfigCommands, run_config_edit, run_config_generate, run_config_get, run_config_import,
+    run_config_init, run_config_list, run_config_migrate, run_config_path, run_config_remove,
+    run_config_rotate, run_config_schema, run_config_set, run_config_validate,
 };
-use commands::deploy::{DeployCmd, run_deploy};
+use commands::deploy::{DeployCmd, run_deploy, run_deploy_command};
 use commands::dns::{
     DnsCommands, run_dns_delete, run_dns_list, run_dns_migrate, run_dns_set, run_dns_set_all,
     run_dns_status,
@@ -37,10 +42,11 @@ use commands::host::{
     AddHostArgs, HostCommands, run_host_add, run_host_detect_tailscale_ip, run_host_edit,
     run_host_list, run_host_remove, run_host_rename, run_host_show,
 };
+use commands::profile::{ProfileCommands, run_profile_create, run_profile_list, run_profile_use};
 use commands::select::{SelectCommands, run_select_host, run_select_playbook};
 use commands::ssh::{SshCommands, run_ssh_add_key, run_ssh_keygen};
 use commands::sync::{SyncCommands, run_sync_hermes, run_sync_music};
-use commands::versions::{VersionsCmd, run_versions};
+use commands::versions::{VersionsCmd, run_versions, run_versions_command};
 use eyre::Result;
 
 #[derive(Parser)]
@@ -64,6 +70,13 @@ struct Cli {
         help = "Disable colored output (also honored via NO_COLOR env var)"
     )]
     no_color: bool,
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        help = "Use this profile's config.toml and hosts.toml (also honored via AUBERGE_PROFILE)"
+    )]
+    profile: Option<String>,
 
     #[command(subcommand)]
     command: Commands,
@@ -105,6 +118,12 @@ enum Commands {
         about = "DNS management via Namecheap"
     )]
     Dns(DnsCommands),
+    #[command(
+        subcommand,
+        visible_alias = "pf",
+        about = "Manage profiles: separate config, hosts and backups"
+    )]
+    Profile(ProfileCommands),
     #[command(subcommand, visible_alias = "c", about = "Manage user configuration")]
     Config(ConfigCommands),
     #[command(subcommand, about = "Manage Bichon email archive behavior")]
@@ -126,8 +145,16 @@ async fn main() -> Result<()> {
     output::set_verbose(cli.verbose);
     output::set_quiet(cli.quiet);
     output::set_no_color(cli.no_color);
+    profile::set_flag(cli.profile);
+    if !matches!(cli.command, Commands::Profile(_)) {
+        profile::ensure_active_exists()?;
+    }
 
     match cli.command {
+        Commands::Deploy(DeployCmd {
+            command: Some(command),
+            ..
+        }) => run_deploy_command(command),
         Commands::Deploy(cmd) => signal::with_ctrlc(|| run_deploy(cmd)),
         Commands::Select(cmd) => match cmd {
             SelectCommands::Host { group } => run_select_host(group),
@@ -227,12 +254,15 @@ async fn main() -> Result<()> {
                 ssh_key,
                 include_music,
                 dry_run,
-            } => {
-                signal::with_ctrlc(|| run_backup_sync(host, apps, ssh_key, include_music, dry_run))
-            }
+                repo,
+            } => signal::with_ctrlc(|| {
+                run_backup_sync(host, apps, ssh_key, include_music, dry_run, repo)
+            }),
             BackupCommands::List { host, app, output } => run_backup_list(host, app, output),
             BackupCommands::Restore {
                 backup_id,
+                from_restic,
+                repo,
                 host,
                 from_host,
                 apps,
@@ -243,6 +273,8 @@ async fn main() -> Result<()> {
             } => signal::with_ctrlc(|| {
                 run_backup_restore(RestoreOptions {
                     backup_id,
+                    from_restic,
+                    repo,
                     host_arg: host,
                     from_host_arg: from_host,
                     apps,
@@ -252,19 +284,29 @@ async fn main() -> Result<()> {
                     skip_playbook_unsafe,
                 })
             }),
-            BackupCommands::Push { host, backup_id } => {
-                signal::with_ctrlc(|| run_backup_push(host, backup_id))
+            BackupCommands::Push {
+                host,
+                backup_id,
+                repo,
+            } => signal::with_ctrlc(|| run_backup_push(host, backup_id, repo)),
+            BackupCommands::Prune { dry_run, repo } => {
+                signal::with_ctrlc(|| run_backup_prune(dry_run, repo))
             }
-            BackupCommands::Prune { dry_run } => signal::with_ctrlc(|| run_backup_prune(dry_run)),
             BackupCommands::Verify {
                 host,
                 app,
                 max_age,
+                deep,
+                read_data_subset,
+                repo,
                 output,
             } => std::process::exit(run_backup_verify(VerifyOptions {
                 host,
                 app,
                 max_age,
+                deep,
+                read_data_subset,
+                repo,
                 format: output,
             })),
             BackupCommands::ExportOpml {
@@ -357,13 +399,24 @@ async fn main() -> Result<()> {
         },
         Commands::Config(cmd) => match cmd {
             ConfigCommands::Init(args) => run_config_init(args),
-            ConfigCommands::Set { key, value } => run_config_set(key, value),
-            ConfigCommands::Get { key } => run_config_get(key),
-            ConfigCommands::List => run_config_list(),
-            ConfigCommands::Remove { key } => run_config_remove(key),
+            ConfigCommands::Generate { key, force } => run_config_generate(key, force),
+            ConfigCommands::Rotate(args) => run_config_rotate(args),
+            ConfigCommands::Import(args) => run_config_import(args),
+            ConfigCommands::Set { key, value, host } => run_config_set(key, value, host),
+            ConfigCommands::Get { key, host } => run_config_get(key, host),
+            ConfigCommands::List { host } => run_config_list(host),
+            ConfigCommands::Migrate => run_config_migrate(),
+            ConfigCommands::Schema { output } => run_config_schema(output),
+            ConfigCommands::Validate(args) => std::process::exit(run_config_validate(args)),
+            ConfigCommands::Remove { key, host } => run_config_remove(key, host),
             ConfigCommands::Edit => run_config_edit(),
             ConfigCommands::Path => run_config_path(),
         },
+        Commands::Profile(cmd) => match cmd {
+            ProfileCommands::List => run_profile_list(),
+            ProfileCommands::Create { name } => run_profile_create(name),
+            ProfileCommands::Use { name } => run_profile_use(name),
+        },
         Commands::Bichon(cmd) => {
             let code = run_bichon_command(cmd).await?;
             if code != 0 {
@@ -371,6 +424,10 @@ async fn main() -> Result<()> {
             }
             Ok(())
         }
+        Commands::Versions(VersionsCmd {
+            command: Some(command),
+            ..
+        }) => run_versions_command(command).await,
         Commands::Versions(cmd) => std::process::exit(run_versions(cmd).await),
         Commands::Completions { shell } => {
             clap_complete::generate(
@@ -437,6 +494,7 @@ mod tests {
             "sync",
             "dns",
             "config",
+            "profile",
             "bichon",
             "versions",
             "completions",
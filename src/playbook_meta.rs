@@ -10,6 +10,8 @@ pub struct PlaybookMeta {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub version: Option<VersionPin>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_probe: Option<VersionProbe>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub backup: Option<BackupRecipe>,
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub tailnet_only: bool,
@@ -45,6 +47,17 @@ pub struct VersionPin {
     pub extract_version: Option<String>,
 }
 
+/// How to ask a Host which App Version it runs: a shell command run over
+/// SSH, and optionally an `extract` regex whose `version` capture picks the
+/// version out of its stdout (the whole trimmed stdout otherwise). Kept apart
+/// from [`VersionPin`], whose fields are Renovate's vocabulary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionProbe {
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extract: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BackupRecipe {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -55,6 +68,14 @@ pub struct BackupRecipe {
     pub owner: Option<(String, String)>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub db: Option<DbRecipe>,
+    /// SQLite databases under `paths`, globs allowed, copied with SQLite's
+    /// online backup instead of from the live file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sqlite: Vec<String>,
+    /// Everything in `paths` besides the `sqlite` databases is safe to copy
+    /// while the app runs, so `backup create` leaves `systemd_services` up.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub copy_live: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub post_restore_command: Option<String>,
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
@@ -91,6 +112,8 @@ impl BackupRecipe {
                 name: sub(db.name),
                 dump_path: sub(db.dump_path),
             }),
+            sqlite: self.sqlite.into_iter().map(sub).collect(),
+            copy_live: self.copy_live,
             post_restore_command: self.post_restore_command.map(sub),
             parameters: self
                 .parameters
@@ -192,10 +215,66 @@ pub fn app_memory_vars(playbooks_dir: &Path) -> Result<Vec<(String, String)>> {
 pub fn app_version_vars(playbooks_dir: &Path) -> Result<Vec<(String, String)>> {
     Ok(declared_app_versions(playbooks_dir)?
         .into_iter()
-        .map(|(app, version)| (format!("{app}_version"), version.value))
+        .map(|(app, version)| (app_version_var(&app), version.value))
         .collect())
 }
 
+/// The extra var an App's declared App Version is injected as.
+pub fn app_version_var(app: &str) -> String {
+    format!("{app}_version")
+}
+
+/// Rewrite the `value:` of a Playbook Meta's `version:` block to `to`. Line
+/// based rather than a serde round-trip, which would drop comments and
+/// reorder keys: every other byte stays as committed.
+pub fn rewrite_app_version(content: &str, to: &str) -> Result<String> {
+    let mut in_version = false;
+    let mut rewritten = false;
+    let mut out = String::with_capacity(content.len());
+    for line in content.split_inclusive('\n') {
+        let top_level = !line.starts_with([' ', '\t', '#']) && !line.trim().is_empty();
+        if top_level {
+            in_version = line.trim_end() == "version:";
+        }
+        if in_version && !rewritten && line.trim_start().starts_with("value:") {
+            out.push_str(&replace_scalar(line, to)?);
+            rewritten = true;
+        } else {
+            out.push_str(line);
+        }
+    }
+    if !rewritten {
+        eyre::bail!("no `version:` block with a `value:` to rewrite");
+    }
+    Ok(out)
+}
+
+/// Replace the scalar of a `key: value` line, keeping its indentation, its
+/// quoting and any trailing comment.
+pub(crate) fn replace_scalar(line: &str, to: &str) -> Result<String> {
+    let (key, rest) = line
+        .split_once(':')
+        .ok_or_else(|| eyre::eyre!("`{}` is not a `key: value` line", line.trim_end()))?;
+    let value = rest.trim_start();
+    let spacing = &rest[..rest.len() - value.len()];
+    let tail = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            let close = value[1..]
+                .find(quote)
+                .ok_or_else(|| eyre::eyre!("`{}` has an unterminated quote", line.trim_end()))?;
+            return Ok(format!(
+                "{key}:{spacing}{quote}{to}{quote}{}",
+                &value[close + 2..]
+            ));
+        }
+        _ => value
+            .find(" #")
+            .or_else(|| value.find(['\r', '\n']))
+            .map_or("", |end| &value[end..]),
+    };
+    Ok(format!("{key}:{spacing}{to}{tail}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -323,6 +402,14 @@ mod tests {
         let backup = load_meta("actual").backup.unwrap();
         assert_eq!(backup.systemd_services, vec!["actual"]);
         assert_eq!(backup.paths, vec!["/var/lib/actual"]);
+        assert_eq!(
+            backup.sqlite,
+            vec![
+                "/var/lib/actual/server-files/account.sqlite",
+                "/var/lib/actual/user-files/*.sqlite",
+            ]
+        );
+        assert!(backup.copy_live);
         assert_eq!(
             backup.owner,
             Some(("actual".to_string(), "actual".to_string()))
@@ -593,6 +680,7 @@ version:
                 versioning: None,
                 extract_version: Some("^v(?<version>.+)$".to_string()),
             }),
+            version_probe: None,
             backup: None,
             tailnet_only: false,
             subdomain: None,
@@ -603,6 +691,87 @@ version:
         assert_eq!(reparsed, meta);
     }
 
+    #[test]
+    fn test_rewrite_app_version_touches_only_the_value() {
+        let content = "# comment stays\n\
+            required_keys:\n  - paperless_admin_password\n\
+            version:\n  value: \"3.0.5\" # pinned\n  datasource: github-releases\n\
+            \x20 depName: \"paperless-ngx/paperless-ngx\"\n\
+            backup:\n  value: untouched\n";
+
+        let rewritten = rewrite_app_version(content, "3.1.0").unwrap();
+
+        assert_eq!(
+            rewritten,
+            content.replace("\"3.0.5\" # pinned", "\"3.1.0\" # pinned")
+        );
+    }
+
+    #[test]
+    fn test_rewrite_app_version_keeps_unquoted_values_unquoted() {
+        let content = "version:\n  value: 26.8.0\n  datasource: npm\n";
+        assert_eq!(
+            rewrite_app_version(content, "26.9.0").unwrap(),
+            "version:\n  value: 26.9.0\n  datasource: npm\n"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_app_version_without_a_version_block_fails() {
+        let content = "required_keys: []\nbackup:\n  value: nope\n";
+        assert!(rewrite_app_version(content, "1.0.0").is_err());
+    }
+
+    #[test]
+    fn test_rewrite_app_version_round_trips_every_committed_meta() {
+        for (app, pin) in declared_app_versions(&playbooks_dir()).unwrap() {
+            let path = playbooks_dir().join(format!("{app}.meta.yml"));
+            let content = std::fs::read_to_string(&path).unwrap();
+            let rewritten = rewrite_app_version(&content, "99.0.0").unwrap();
+            let reparsed: PlaybookMeta = serde_yaml::from_str(&rewritten).unwrap();
+            let version = reparsed.version.unwrap();
+            assert_eq!(version.value, "99.0.0", "{app}");
+            assert_eq!(
+                rewrite_app_version(&rewritten, &pin.value).unwrap(),
+                content,
+                "{app}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_meta_version_probe_parses() {
+        let yaml = r#"
+required_keys: []
+version_probe:
+  command: "/usr/local/bin/blocky/blocky version"
+  extract: "Version: v(?<version>\\S+)"
+"#;
+        let meta: PlaybookMeta = serde_yaml::from_str(yaml).unwrap();
+        let probe = meta.version_probe.unwrap();
+        assert_eq!(probe.command, "/usr/local/bin/blocky/blocky version");
+        assert_eq!(probe.extract.as_deref(), Some("Version: v(?<version>\\S+)"));
+    }
+
+    #[test]
+    fn test_every_version_probe_belongs_to_a_versioned_app() {
+        for (app, meta) in load_all_metas(&playbooks_dir()).unwrap() {
+            if let Some(probe) = meta.version_probe {
+                assert!(
+                    meta.version.is_some(),
+                    "{app} probes without an App Version"
+                );
+                if let Some(extract) = probe.extract {
+                    let regex = regex::Regex::new(&extract).unwrap();
+                    assert!(
+                        regex.capture_names().any(|name| name == Some("version")),
+                        "{app}'s probe extract has no `version` capture"
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_meta_without_version_parses_to_none() {
         let yaml = "required_keys: []\n";
@@ -809,6 +978,8 @@ backup:
             paths: vec!["/var/lib/app".to_string()],
             owner: None,
             db: None,
+            sqlite: vec![],
+            copy_live: false,
             post_restore_command: None,
             parameters: HashMap::new(),
         };
@@ -831,6 +1002,8 @@ backup:
             paths: vec!["/var/lib/navidrome".to_string()],
             owner: None,
             db: None,
+            sqlite: vec![],
+            copy_live: false,
             post_restore_command: None,
             parameters,
         };
@@ -856,6 +1029,8 @@ backup:
             paths: vec!["/var/lib/navidrome".to_string()],
             owner: None,
             db: None,
+            sqlite: vec![],
+            copy_live: false,
             post_restore_command: None,
             parameters,
         };
@@ -878,6 +1053,8 @@ backup:
             paths: vec!["/home/{admin_user}/.local/state/syncthing/config.xml".to_string()],
             owner: Some(("{admin_user}".to_string(), "{admin_user}".to_string())),
             db: None,
+            sqlite: vec![],
+            copy_live: false,
             post_restore_command: Some("chown {admin_user} /tmp/x".to_string()),
             parameters,
         };
@@ -913,6 +1090,8 @@ backup:
                 name: "navidrome".to_string(),
                 dump_path: "/tmp/navidrome.dump".to_string(),
             }),
+            sqlite: vec![],
+            copy_live: false,
             post_restore_command: None,
             parameters: HashMap::new(),
         };
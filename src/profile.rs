@@ -0,0 +1,341 @@
+use eyre::{Result, WrapErr};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Environment variable that selects a Profile when `--profile` is absent.
+pub const PROFILE_ENV: &str = "AUBERGE_PROFILE";
+/// The Profile living at the top of the auberge directories, as before
+/// profiles existed.
+pub const DEFAULT_PROFILE: &str = "default";
+const PROFILES_DIR: &str = "profiles";
+/// File in the base config directory naming the Profile `profile use` chose.
+const CURRENT_FILE: &str = "profile";
+
+static FLAG: OnceLock<String> = OnceLock::new();
+
+/// Record the global `--profile` flag; it wins over every other source.
+pub fn set_flag(name: Option<String>) {
+    if let Some(name) = name {
+        let _ = FLAG.set(name);
+    }
+}
+
+/// Where the active Profile's name came from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProfileSource {
+    Flag,
+    Env,
+    Current,
+    Default,
+}
+
+impl ProfileSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Flag => "--profile",
+            Self::Env => PROFILE_ENV,
+            Self::Current => "profile use",
+            Self::Default => "default",
+        }
+    }
+}
+
+/// A named workspace: its own `config.toml`, `hosts.toml`, deploy journal,
+/// SSH Include file and backup directory. The default Profile keeps the
+/// paths auberge used before profiles, so existing setups need no move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub name: String,
+}
+
+impl Profile {
+    pub fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+        }
+    }
+
+    /// The Profile in effect: `--profile`, else `AUBERGE_PROFILE`, else the
+    /// one `auberge profile use` chose, else the default.
+    pub fn active() -> Self {
+        Self::named(&resolve().0)
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.name == DEFAULT_PROFILE
+    }
+
+    /// Holds `config.toml` and `hosts.toml`.
+    pub fn config_dir(&self) -> Result<PathBuf> {
+        Ok(self.scoped(base_config_dir()?))
+    }
+
+    /// Holds the deploy journal.
+    pub fn data_dir(&self) -> Result<PathBuf> {
+        let base = dirs::data_dir()
+            .map(|p| p.join("auberge"))
+            .ok_or_else(|| eyre::eyre!("Could not determine XDG data directory"))?;
+        Ok(self.scoped(base))
+    }
+
+    /// Where `auberge backup create` stages backups by default.
+    pub fn backup_dir(&self) -> PathBuf {
+        let base = dirs::data_local_dir()
+            .map(|d| d.join("auberge"))
+            .unwrap_or_else(|| PathBuf::from("~/.local/share/auberge"));
+        self.scoped(base).join("backups")
+    }
+
+    /// File name of the SSH Include file in `~/.ssh/config.d`.
+    pub fn ssh_include_name(&self) -> String {
+        if self.is_default() {
+            "auberge.conf".to_string()
+        } else {
+            format!("auberge-{}.conf", self.name)
+        }
+    }
+
+    pub fn exists(&self) -> Result<bool> {
+        Ok(self.is_default() || self.config_dir()?.is_dir())
+    }
+
+    fn scoped(&self, base: PathBuf) -> PathBuf {
+        if self.is_default() {
+            base
+        } else {
+            base.join(PROFILES_DIR).join(&self.name)
+        }
+    }
+}
+
+/// The active Profile's name and where it came from.
+pub fn resolve() -> (String, ProfileSource) {
+    resolve_from(
+        FLAG.get().map(String::as_str),
+        std::env::var(PROFILE_ENV).ok().as_deref(),
+        base_config_dir().ok().as_deref(),
+    )
+}
+
+/// `resolve` over its sources: the flag, the environment variable and the
+/// base config directory holding the current-Profile file.
+fn resolve_from(
+    flag: Option<&str>,
+    env: Option<&str>,
+    base: Option<&Path>,
+) -> (String, ProfileSource) {
+    if let Some(name) = flag {
+        return (name.to_string(), ProfileSource::Flag);
+    }
+    if let Some(name) = env
+        && !name.trim().is_empty()
+    {
+        return (name.trim().to_string(), ProfileSource::Env);
+    }
+    match base.and_then(read_current) {
+        Some(name) => (name, ProfileSource::Current),
+        None => (DEFAULT_PROFILE.to_string(), ProfileSource::Default),
+    }
+}
+
+/// Fail early on a Profile that was never created, rather than on a
+/// `config.toml` missing from a directory that does not exist.
+pub fn ensure_active_exists() -> Result<()> {
+    let (name, source) = resolve();
+    validate_name(&name)?;
+    if !Profile::named(&name).exists()? {
+        eyre::bail!(
+            "Profile '{name}' (from {}) does not exist; create it with `auberge profile create {name}`",
+            source.as_str()
+        );
+    }
+    Ok(())
+}
+
+/// Every Profile: the default first, then the created ones by name.
+pub fn list() -> Result<Vec<Profile>> {
+    let mut names = created_profiles(&base_config_dir()?.join(PROFILES_DIR))?;
+    names.sort();
+    Ok(std::iter::once(DEFAULT_PROFILE.to_string())
+        .chain(names)
+        .map(|name| Profile { name })
+        .collect())
+}
+
+/// Create a Profile's config directory, private to the user.
+pub fn create(name: &str) -> Result<Profile> {
+    validate_name(name)?;
+    let profile = Profile::named(name);
+    let dir = profile.config_dir()?;
+    if profile.is_default() || dir.exists() {
+        eyre::bail!("Profile '{name}' already exists");
+    }
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder
+        .create(&dir)
+        .wrap_err_with(|| format!("Failed to create {}", dir.display()))?;
+    Ok(profile)
+}
+
+/// Make `name` the Profile used when neither `--profile` nor
+/// `AUBERGE_PROFILE` names one.
+pub fn use_profile(name: &str) -> Result<()> {
+    validate_name(name)?;
+    let profile = Profile::named(name);
+    if !profile.exists()? {
+        eyre::bail!(
+            "Profile '{name}' does not exist; create it with `auberge profile create {name}`"
+        );
+    }
+    let base = base_config_dir()?;
+    write_current(&base, &profile)
+}
+
+fn base_config_dir() -> Result<PathBuf> {
+    dirs::config_dir()
+        .map(|p| p.join("auberge"))
+        .ok_or_else(|| eyre::eyre!("Could not determine XDG config directory"))
+}
+
+fn read_current(base: &Path) -> Option<String> {
+    let name = fs::read_to_string(base.join(CURRENT_FILE)).ok()?;
+    let name = name.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn write_current(base: &Path, profile: &Profile) -> Result<()> {
+    let path = base.join(CURRENT_FILE);
+    if profile.is_default() {
+        return match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).wrap_err_with(|| format!("Failed to remove {}", path.display()))
+            }
+            _ => Ok(()),
+        };
+    }
+    fs::create_dir_all(base).wrap_err_with(|| format!("Failed to create {}", base.display()))?;
+    fs::write(&path, format!("{}\n", profile.name))
+        .wrap_err_with(|| format!("Failed to write {}", path.display()))
+}
+
+fn created_profiles(dir: &Path) -> Result<Vec<String>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).wrap_err_with(|| format!("Failed to read {}", dir.display())),
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        let entry = entry.wrap_err_with(|| format!("Failed to read {}", dir.display()))?;
+        if entry.path().is_dir()
+            && let Some(name) = entry.file_name().to_str()
+        {
+            names.push(name.to_string());
+        }
+    }
+    Ok(names)
+}
+
+/// A Profile name becomes a directory and part of a file name, so it is
+/// held to letters, digits, `-` and `_`.
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        eyre::bail!("Invalid profile name '{name}': use letters, digits, '-' and '_'");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_profile_keeps_the_unscoped_paths() {
+        let default = Profile::named(DEFAULT_PROFILE);
+        assert_eq!(
+            default.scoped(PathBuf::from("/c/auberge")),
+            PathBuf::from("/c/auberge")
+        );
+        assert_eq!(default.ssh_include_name(), "auberge.conf");
+
+        let family = Profile::named("family");
+        assert_eq!(
+            family.scoped(PathBuf::from("/c/auberge")),
+            PathBuf::from("/c/auberge/profiles/family")
+        );
+        assert_eq!(family.ssh_include_name(), "auberge-family.conf");
+    }
+
+    #[test]
+    fn test_validate_name_rejects_path_characters() {
+        assert!(validate_name("home-lab_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../x").is_err());
+        assert!(validate_name("a b").is_err());
+    }
+
+    #[test]
+    fn test_use_profile_rejects_an_invalid_name() {
+        let err = use_profile("../x").unwrap_err();
+        assert!(err.to_string().contains("Invalid profile name '../x'"));
+    }
+
+    #[test]
+    fn test_resolve_prefers_flag_then_env_then_current_then_default() {
+        let dir = tempfile::tempdir().unwrap();
+        write_current(dir.path(), &Profile::named("family")).unwrap();
+        let base = Some(dir.path());
+
+        assert_eq!(
+            resolve_from(Some("work"), Some("lab"), base),
+            ("work".to_string(), ProfileSource::Flag)
+        );
+        assert_eq!(
+            resolve_from(None, Some(" lab "), base),
+            ("lab".to_string(), ProfileSource::Env)
+        );
+        assert_eq!(
+            resolve_from(None, Some(" "), base),
+            ("family".to_string(), ProfileSource::Current)
+        );
+        assert_eq!(
+            resolve_from(None, None, Some(&dir.path().join("empty"))),
+            (DEFAULT_PROFILE.to_string(), ProfileSource::Default)
+        );
+    }
+
+    #[test]
+    fn test_current_profile_round_trips_and_default_clears_it() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(read_current(dir.path()), None);
+        write_current(dir.path(), &Profile::named("family")).unwrap();
+        assert_eq!(read_current(dir.path()).as_deref(), Some("family"));
+        write_current(dir.path(), &Profile::named(DEFAULT_PROFILE)).unwrap();
+        assert_eq!(read_current(dir.path()), None);
+        write_current(dir.path(), &Profile::named(DEFAULT_PROFILE)).unwrap();
+    }
+
+    #[test]
+    fn test_created_profiles_lists_directories_only() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(
+            created_profiles(&dir.path().join("missing"))
+                .unwrap()
+                .is_empty()
+        );
+        fs::create_dir(dir.path().join("family")).unwrap();
+        fs::write(dir.path().join("stray.txt"), "").unwrap();
+        assert_eq!(created_profiles(dir.path()).unwrap(), vec!["family"]);
+    }
+}
@@ -0,0 +1,414 @@
+use eyre::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+/// Environment variable naming the age identity file, shared with sops so
+/// one key decrypts both `age:` and `sops:` references.
+const AGE_KEY_FILE_VAR: &str = "SOPS_AGE_KEY_FILE";
+
+/// The `scheme:` prefixes that name a backend. Doubling the colon escapes
+/// one, the way `!!` escapes `!`: `pass::text` is the literal `pass:text`.
+const SCHEMES: [&str; 5] = ["pass", "env", "file", "age", "sops"];
+
+/// Every secret resolved so far, keyed by its raw config value: a deploy
+/// reads the same keys several times, and `pass` may prompt for a GPG
+/// passphrase on each invocation.
+static RESOLVED: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// Where a config value comes from, parsed from its raw string form.
+///
+/// | Raw value       | Source                                            |
+/// | --------------- | ------------------------------------------------- |
+/// | `!cmd`          | stdout of `sh -c cmd`                             |
+/// | `!!text`        | the literal `!text`                               |
+/// | `pass:name`     | first line of `pass show name`                    |
+/// | `env:VAR`       | the environment variable `VAR`                    |
+/// | `file:path`     | contents of `path`                                |
+/// | `age:file#key`  | `key` of the age-encrypted YAML/JSON in `file`    |
+/// | `sops:file#a.b` | `a.b` of the sops-encrypted `file`                |
+/// | `pass::text` …  | the literal `pass:text`, likewise for each scheme |
+/// | anything else   | the value itself                                  |
+#[derive(Debug, Clone, PartialEq)]
+pub enum SecretRef {
+    Literal(String),
+    Shell(String),
+    Pass(String),
+    Env(String),
+    File(PathBuf),
+    Age { file: PathBuf, key: Option<String> },
+    Sops { file: PathBuf, path: Option<String> },
+}
+
+impl SecretRef {
+    pub fn parse(value: &str) -> Self {
+        if let Some(rest) = value.strip_prefix("!!") {
+            return Self::Literal(format!("!{rest}"));
+        }
+        if let Some(cmd) = value.strip_prefix('!') {
+            return Self::Shell(cmd.to_string());
+        }
+        let Some((scheme, rest)) = value.split_once(':') else {
+            return Self::Literal(value.to_string());
+        };
+        match scheme {
+            _ if SCHEMES.contains(&scheme) && rest.starts_with(':') => {
+                Self::Literal(format!("{scheme}{rest}"))
+            }
+            "pass" => Self::Pass(rest.to_string()),
+            "env" => Self::Env(rest.to_string()),
+            "file" => Self::File(expand_home(rest)),
+            "age" => {
+                let (file, key) = split_fragment(rest);
+                Self::Age { file, key }
+            }
+            "sops" => {
+                let (file, path) = split_fragment(rest);
+                Self::Sops { file, path }
+            }
+            _ => Self::Literal(value.to_string()),
+        }
+    }
+
+    /// The backend's name as shown by `config list`; `None` for a literal.
+    pub fn backend(&self) -> Option<&'static str> {
+        match self {
+            Self::Literal(_) => None,
+            Self::Shell(_) => Some("shell"),
+            Self::Pass(_) => Some("pass"),
+            Self::Env(_) => Some("env"),
+            Self::File(_) => Some("file"),
+            Self::Age { .. } => Some("age"),
+            Self::Sops { .. } => Some("sops"),
+        }
+    }
+
+    fn fetch(&self) -> Result<String> {
+        match self {
+            Self::Literal(value) => Ok(value.clone()),
+            Self::Shell(cmd) => {
+                let mut command = Command::new("sh");
+                command.arg("-c").arg(cmd);
+                run("Shell command", command)
+            }
+            Self::Pass(name) => {
+                let mut command = Command::new("pass");
+                command.arg("show").arg(name);
+                let output = run(&format!("pass backend (`pass show {name}`)"), command)?;
+                Ok(output.lines().next().unwrap_or_default().to_string())
+            }
+            Self::Env(var) => match std::env::var(var) {
+                Ok(value) if !value.trim().is_empty() => Ok(value.trim().to_string()),
+                Ok(_) => eyre::bail!("env backend: {var} is empty"),
+                Err(_) => eyre::bail!("env backend: {var} is not set"),
+            },
+            Self::File(path) => {
+                let content = std::fs::read_to_string(path)
+                    .wrap_err_with(|| format!("file backend: failed to read {}", path.display()))?;
+                let value = content.trim();
+                if value.is_empty() {
+                    eyre::bail!("file backend: {} is empty", path.display());
+                }
+                Ok(value.to_string())
+            }
+            Self::Age { file, key } => {
+                let mut command = Command::new("age");
+                command
+                    .arg("--decrypt")
+                    .arg("--identity")
+                    .arg(age_identity()?)
+                    .arg(file);
+                let label = format!("age backend (`age --decrypt {}`)", file.display());
+                let plaintext = run(&label, command)?;
+                match key {
+                    Some(key) => lookup(&plaintext, key)
+                        .wrap_err_with(|| format!("age backend: no `{key}` in {}", file.display())),
+                    None => Ok(plaintext),
+                }
+            }
+            Self::Sops { file, path } => {
+                let mut command = Command::new("sops");
+                command.arg("--decrypt");
+                if let Some(path) = path {
+                    command.arg("--extract").arg(sops_extract(path));
+                }
+                command.arg(file);
+                run(
+                    &format!("sops backend (`sops --decrypt {}`)", file.display()),
+                    command,
+                )
+            }
+        }
+    }
+}
+
+/// The raw config value that reads back as `value` itself: a leading `!`
+/// becomes `!!` and a backend `scheme:` becomes `scheme::`.
+pub fn escape_literal(value: &str) -> String {
+    if value.starts_with('!') {
+        return format!("!{value}");
+    }
+    match value.split_once(':') {
+        Some((scheme, rest)) if SCHEMES.contains(&scheme) => format!("{scheme}::{rest}"),
+        _ => value.to_string(),
+    }
+}
+
+/// Resolve a raw config value through its backend. Each distinct value is
+/// fetched at most once per process; failures are not cached.
+pub fn resolve_value(value: &str) -> Result<String> {
+    let secret = SecretRef::parse(value);
+    if let SecretRef::Literal(literal) = secret {
+        return Ok(literal);
+    }
+    if let Ok(resolved) = RESOLVED.lock()
+        && let Some(hit) = resolved.get(value)
+    {
+        return Ok(hit.clone());
+    }
+    let fetched = secret.fetch()?;
+    if let Ok(mut resolved) = RESOLVED.lock() {
+        resolved.insert(value.to_string(), fetched.clone());
+    }
+    Ok(fetched)
+}
+
+/// Run `command` and return its trimmed stdout. Errors start with `label`
+/// and carry the first line of stderr, which usually says what went wrong.
+fn run(label: &str, mut command: Command) -> Result<String> {
+    let output = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .wrap_err_with(|| format!("{label} could not be executed"))?;
+    if !output.status.success() {
+        let code = output
+            .status
+            .code()
+            .map_or("signal".to_string(), |c| c.to_string());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        match stderr.lines().map(str::trim).find(|l| !l.is_empty()) {
+            Some(reason) => eyre::bail!("{label} failed (exit {code}): {reason}"),
+            None => eyre::bail!("{label} failed (exit {code})"),
+        }
+    }
+    let stdout = String::from_utf8(output.stdout)
+        .wrap_err_with(|| format!("{label} output is not valid UTF-8"))?;
+    let resolved = stdout.trim().to_string();
+    if resolved.is_empty() {
+        eyre::bail!("{label} produced empty output");
+    }
+    Ok(resolved)
+}
+
+fn split_fragment(rest: &str) -> (PathBuf, Option<String>) {
+    match rest.rsplit_once('#') {
+        Some((file, fragment)) if !fragment.is_empty() => {
+            (expand_home(file), Some(fragment.to_string()))
+        }
+        Some((file, _)) => (expand_home(file), None),
+        None => (expand_home(rest), None),
+    }
+}
+
+/// `raw` with a leading `~/` replaced by the home directory.
+pub fn expand_home(raw: &str) -> PathBuf {
+    match (raw.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(raw),
+    }
+}
+
+fn age_identity() -> Result<PathBuf> {
+    if let Ok(path) = std::env::var(AGE_KEY_FILE_VAR)
+        && !path.is_empty()
+    {
+        return Ok(expand_home(&path));
+    }
+    dirs::config_dir()
+        .map(|dir| dir.join("sops/age/keys.txt"))
+        .ok_or_else(|| eyre::eyre!("age backend: set {AGE_KEY_FILE_VAR} to your identity file"))
+}
+
+/// The scalar at dotted `path` in a YAML (or JSON) document.
+fn lookup(document: &str, path: &str) -> Result<String> {
+    let mut node: serde_yaml::Value =
+        serde_yaml::from_str(document).wrap_err("decrypted content is not YAML or JSON")?;
+    for segment in path.split('.') {
+        node = node
+            .get(segment)
+            .cloned()
+            .ok_or_else(|| eyre::eyre!("missing `{segment}`"))?;
+    }
+    match node {
+        serde_yaml::Value::String(s) => Ok(s),
+        serde_yaml::Value::Number(n) => Ok(n.to_string()),
+        serde_yaml::Value::Bool(b) => Ok(b.to_string()),
+        _ => eyre::bail!("`{path}` is not a scalar"),
+    }
+}
+
+/// `a.b` → `["a"]["b"]`, the form `sops --extract` expects.
+fn sops_extract(path: &str) -> String {
+    path.split('.')
+        .map(|segment| format!("[\"{segment}\"]"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_recognises_each_backend() {
+        assert_eq!(
+            SecretRef::parse("pass:auberge/restic"),
+            SecretRef::Pass("auberge/restic".to_string())
+        );
+        assert_eq!(
+            SecretRef::parse("env:RESTIC_PASSWORD"),
+            SecretRef::Env("RESTIC_PASSWORD".to_string())
+        );
+        assert_eq!(
+            SecretRef::parse("file:/run/secrets/token"),
+            SecretRef::File(PathBuf::from("/run/secrets/token"))
+        );
+        assert_eq!(
+            SecretRef::parse("age:/etc/secrets.age#restic.password"),
+            SecretRef::Age {
+                file: PathBuf::from("/etc/secrets.age"),
+                key: Some("restic.password".to_string()),
+            }
+        );
+        assert_eq!(
+            SecretRef::parse("sops:secrets.yaml"),
+            SecretRef::Sops {
+                file: PathBuf::from("secrets.yaml"),
+                path: None,
+            }
+        );
+        assert_eq!(
+            SecretRef::parse("!pass show x"),
+            SecretRef::Shell("pass show x".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_leaves_other_schemes_literal() {
+        for value in [
+            "rclone:filen:auberge-backup",
+            "https://hs.example.com",
+            "s3:s3.amazonaws.com/bucket",
+            "plain",
+        ] {
+            let secret = SecretRef::parse(value);
+            assert_eq!(secret, SecretRef::Literal(value.to_string()));
+            assert_eq!(secret.backend(), None);
+        }
+    }
+
+    #[test]
+    fn parse_reads_a_doubled_colon_as_an_escaped_literal() {
+        assert_eq!(
+            SecretRef::parse("pass::word"),
+            SecretRef::Literal("pass:word".to_string())
+        );
+        assert_eq!(
+            SecretRef::parse("env:::x"),
+            SecretRef::Literal("env::x".to_string())
+        );
+        assert_eq!(
+            SecretRef::parse("!!abc"),
+            SecretRef::Literal("!abc".to_string())
+        );
+        assert_eq!(SecretRef::parse("sops::a#b").backend(), None);
+    }
+
+    #[test]
+    fn escape_literal_round_trips_through_parse() {
+        for value in [
+            "!abc",
+            "!!x",
+            "pass:word",
+            "env::x",
+            "age:f#k",
+            "plain",
+            "http://x",
+        ] {
+            assert_eq!(
+                SecretRef::parse(&escape_literal(value)),
+                SecretRef::Literal(value.to_string())
+            );
+        }
+        assert_eq!(escape_literal("plain"), "plain");
+        assert_eq!(escape_literal("file:/x"), "file::/x");
+    }
+
+    #[test]
+    fn resolve_reads_env_and_file_backends() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token");
+        std::fs::write(&path, "file-secret\n").unwrap();
+        // SAFETY: the variable name is unique to this test.
+        unsafe { std::env::set_var("AUBERGE_TEST_SECRET_ENV", "env-secret") };
+
+        assert_eq!(
+            resolve_value("env:AUBERGE_TEST_SECRET_ENV").unwrap(),
+            "env-secret"
+        );
+        assert_eq!(
+            resolve_value(&format!("file:{}", path.display())).unwrap(),
+            "file-secret"
+        );
+    }
+
+    #[test]
+    fn resolve_errors_name_the_backend() {
+        let err = resolve_value("env:AUBERGE_TEST_SECRET_UNSET").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "env backend: AUBERGE_TEST_SECRET_UNSET is not set"
+        );
+        let err = resolve_value("file:/nonexistent/auberge-secret").unwrap_err();
+        assert!(err.to_string().starts_with("file backend:"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_runs_each_command_once_per_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let calls = dir.path().join("calls");
+        let value = format!("!echo x >> {}; echo cached", calls.display());
+
+        assert_eq!(resolve_value(&value).unwrap(), "cached");
+        assert_eq!(resolve_value(&value).unwrap(), "cached");
+
+        assert_eq!(std::fs::read_to_string(&calls).unwrap(), "x\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shell_failure_reports_stderr() {
+        let err = resolve_value("!echo 'no such entry' >&2; exit 3").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Shell command failed (exit 3): no such entry"
+        );
+    }
+
+    #[test]
+    fn lookup_follows_a_dotted_path() {
+        let yaml = "restic:\n  password: hunter2\n  port: 8000\n";
+        assert_eq!(lookup(yaml, "restic.password").unwrap(), "hunter2");
+        assert_eq!(lookup(yaml, "restic.port").unwrap(), "8000");
+        assert!(lookup(yaml, "restic").is_err());
+        assert!(lookup(yaml, "missing").is_err());
+    }
+
+    #[test]
+    fn sops_extract_quotes_each_segment() {
+        assert_eq!(sops_extract("restic.password"), r#"["restic"]["password"]"#);
+    }
+}
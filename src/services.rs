@@ -2,8 +2,10 @@ pub mod ansible_runner;
 pub mod backup;
 pub mod bichon;
 pub mod dependency_resolver;
+pub mod deploy_journal;
 pub mod dns;
 pub mod dns_verify;
+pub mod http_cache;
 pub mod inventory;
 pub mod progress;
 pub mod rsync;
@@ -1,5 +1,7 @@
 pub mod executor;
 pub mod recipe;
+pub mod repository;
 pub mod restic;
+pub mod retention;
 pub mod session;
 pub mod verify;
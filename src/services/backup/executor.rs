@@ -5,6 +5,13 @@ use eyre::Result;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+/// Where a staged backup keeps the app's database dump, beside its paths.
+pub const DB_DUMP_FILE: &str = "db.dump";
+
+/// The files SQLite keeps beside a database while it is open. A live copy of
+/// these replayed over an online snapshot would undo its consistency.
+const SQLITE_SIDECARS: [&str; 3] = ["-wal", "-shm", "-journal"];
+
 pub struct RecipeExecutor<'a, S: SshSession + ?Sized> {
     session: &'a S,
 }
@@ -21,8 +28,13 @@ impl<'a, S: SshSession + ?Sized> RecipeExecutor<'a, S> {
         parameters: &HashMap<String, bool>,
         progress: &mut dyn Progress,
     ) -> Result<()> {
+        let services: &[String] = if recipe.copy_live {
+            &[]
+        } else {
+            &recipe.systemd_services
+        };
         let mut stopped: Vec<&str> = Vec::new();
-        for service in &recipe.systemd_services {
+        for service in services {
             progress.task_started(&format!("Stopping {}", service));
             if let Err(e) = self.session.systemctl("stop", service) {
                 self.restart_all(&stopped);
@@ -55,9 +67,13 @@ impl<'a, S: SshSession + ?Sized> RecipeExecutor<'a, S> {
                 self.session.rsync_from(path, dest_dir)?;
             }
 
+            if !recipe.sqlite.is_empty() {
+                self.snapshot_sqlite(&recipe.sqlite, dest_dir, progress)?;
+            }
+
             if let Some(db) = &recipe.db {
                 progress.task_started("Fetching database dump");
-                let local_dump = dest_dir.join("db.dump");
+                let local_dump = dest_dir.join(DB_DUMP_FILE);
                 self.session.scp_from(&db.dump_path, &local_dump)?;
                 let _ = self.session.run(&format!("rm -f {}", db.dump_path));
             }
@@ -103,49 +119,9 @@ impl<'a, S: SshSession + ?Sized> RecipeExecutor<'a, S> {
             stopped.push(service);
         }
 
-        let result = (|| -> Result<()> {
-            let paths = staged_paths(recipe, source_dir);
-            for path in &paths {
-                progress.task_started(&format!("rsync {}", path));
-                self.session
-                    .rsync_to(&staged_copy(source_dir, path), path)?;
-            }
-
-            if let Some((user, group)) = &recipe.owner {
-                for path in &paths {
-                    progress.task_started(&format!("chown {}", path));
-                    self.session.set_ownership(path, user, group)?;
-                }
-            }
-
-            if let Some(db) = &recipe.db {
-                let local_dump = source_dir.join("db.dump");
-                if local_dump.exists() {
-                    progress.task_started(&format!("pg_restore {}", db.name));
-                    self.session.scp_to(&local_dump, &db.dump_path)?;
-                    self.session.run(&format!("chmod 644 {}", db.dump_path))?;
-                    let cmd = format!(
-                        "sudo -u postgres pg_restore --clean --if-exists -d {} {} 2>&1",
-                        db.name, db.dump_path
-                    );
-                    let restore = self.session.run(&cmd)?;
-                    let _ = self.session.run(&format!("rm -f {}", db.dump_path));
-                    if !restore.success && !is_warnings_only(&restore.stdout_str()) {
-                        eyre::bail!("pg_restore failed: {}", restore.stdout_str().trim());
-                    }
-                }
-            }
-
-            if let Some(cmd) = &recipe.post_restore_command {
-                progress.task_started("Running post_restore_command");
-                let post = self.session.run(cmd)?;
-                if !post.success {
-                    eyre::bail!("post_restore_command failed: {}", post.stderr_str().trim());
-                }
-            }
-
-            Ok(())
-        })();
+        let result = self
+            .restore_data(recipe, source_dir, progress)
+            .and_then(|()| self.post_restore(recipe, progress));
 
         for service in &stopped {
             progress.task_started(&format!("Starting {}", service));
@@ -167,6 +143,144 @@ impl<'a, S: SshSession + ?Sized> RecipeExecutor<'a, S> {
         }
     }
 
+    /// Restore like [`restore`](Self::restore), but leave the Recipe's
+    /// services stopped and its `post_restore_command` unrun, for a caller
+    /// that deploys another version onto the data before anything runs
+    /// against it. On failure the services stay stopped too.
+    pub fn restore_stopped(
+        &self,
+        recipe: &BackupRecipe,
+        source_dir: &Path,
+        progress: &mut dyn Progress,
+    ) -> Result<()> {
+        for service in &recipe.systemd_services {
+            progress.task_started(&format!("Stopping {}", service));
+            self.session.systemctl("stop", service)?;
+        }
+        let result = self.restore_data(recipe, source_dir, progress);
+        progress.task_done();
+        result
+    }
+
+    /// Run the Recipe's `post_restore_command`, if any.
+    pub fn post_restore(&self, recipe: &BackupRecipe, progress: &mut dyn Progress) -> Result<()> {
+        if let Some(cmd) = &recipe.post_restore_command {
+            progress.task_started("Running post_restore_command");
+            let post = self.session.run(cmd)?;
+            if !post.success {
+                eyre::bail!("post_restore_command failed: {}", post.stderr_str().trim());
+            }
+        }
+        Ok(())
+    }
+
+    fn restore_data(
+        &self,
+        recipe: &BackupRecipe,
+        source_dir: &Path,
+        progress: &mut dyn Progress,
+    ) -> Result<()> {
+        // Snapshots are staged without their sidecars, so the live ones
+        // must go before the snapshot lands or SQLite replays them over it.
+        for pattern in &recipe.sqlite {
+            progress.task_started(&format!("Clearing SQLite journals of {}", pattern));
+            let clear = self.session.run(&clear_sidecars_command(pattern))?;
+            if !clear.success {
+                eyre::bail!(
+                    "Failed to clear SQLite journals of {}: {}",
+                    pattern,
+                    clear.stderr_str().trim()
+                );
+            }
+        }
+
+        let paths = staged_paths(recipe, source_dir);
+        for path in &paths {
+            progress.task_started(&format!("rsync {}", path));
+            self.session
+                .rsync_to(&staged_copy(source_dir, path), path)?;
+        }
+
+        if let Some((user, group)) = &recipe.owner {
+            for path in &paths {
+                progress.task_started(&format!("chown {}", path));
+                self.session.set_ownership(path, user, group)?;
+            }
+        }
+
+        if let Some(db) = &recipe.db {
+            let local_dump = source_dir.join(DB_DUMP_FILE);
+            if local_dump.exists() {
+                progress.task_started(&format!("pg_restore {}", db.name));
+                self.session.scp_to(&local_dump, &db.dump_path)?;
+                self.session.run(&format!("chmod 644 {}", db.dump_path))?;
+                let cmd = format!(
+                    "sudo -u postgres pg_restore --clean --if-exists -d {} {} 2>&1",
+                    db.name, db.dump_path
+                );
+                let restore = self.session.run(&cmd)?;
+                let _ = self.session.run(&format!("rm -f {}", db.dump_path));
+                if !restore.success && !is_warnings_only(&restore.stdout_str()) {
+                    eyre::bail!("pg_restore failed: {}", restore.stdout_str().trim());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Overwrite the live copies rsync just staged of the databases matching
+    /// `patterns` with snapshots from SQLite's online backup, taken while the
+    /// service keeps writing. Patterns matching no file are skipped.
+    fn snapshot_sqlite(
+        &self,
+        patterns: &[String],
+        dest_dir: &Path,
+        progress: &mut dyn Progress,
+    ) -> Result<()> {
+        let scratch = self.session.run("mktemp -d /tmp/auberge-sqlite.XXXXXX")?;
+        let scratch_dir = scratch.stdout_str().trim().to_string();
+        if !scratch.success || !scratch_dir.starts_with("/tmp/auberge-sqlite.") {
+            eyre::bail!(
+                "Failed to create a directory for SQLite snapshots: {}",
+                scratch.stderr_str().trim()
+            );
+        }
+
+        let result = (|| -> Result<()> {
+            for pattern in patterns {
+                progress.task_started(&format!("sqlite3 .backup {}", pattern));
+                let snapshot = self
+                    .session
+                    .run(&sqlite_backup_command(pattern, &scratch_dir))?;
+                if !snapshot.success {
+                    eyre::bail!(
+                        "SQLite backup failed for {}: {}",
+                        pattern,
+                        snapshot.stderr_str().trim()
+                    );
+                }
+                for database in snapshot.stdout_str().lines().map(str::trim) {
+                    if database.is_empty() {
+                        continue;
+                    }
+                    let staged = staged_copy(dest_dir, database);
+                    self.session
+                        .scp_from(&format!("{}{}", scratch_dir, database), &staged)?;
+                    for suffix in SQLITE_SIDECARS {
+                        let mut sidecar = staged.clone().into_os_string();
+                        sidecar.push(suffix);
+                        let _ = std::fs::remove_file(sidecar);
+                    }
+                }
+            }
+            Ok(())
+        })();
+
+        let _ = self.session.run(&format!("sudo rm -rf {}", scratch_dir));
+        result
+    }
+
     fn restart_all(&self, services: &[&str]) {
         for service in services {
             let _ = self.session.systemctl("start", service);
@@ -233,6 +347,27 @@ fn staged_copy(source_dir: &Path, path: &str) -> PathBuf {
     source_dir.join(path.trim_start_matches('/'))
 }
 
+/// Snapshot every database matching `pattern` into the same path under
+/// `scratch_dir`, printing each one taken. Runs as root throughout: the glob
+/// has to see into the service's private data directory.
+fn sqlite_backup_command(pattern: &str, scratch_dir: &str) -> String {
+    format!(
+        "sudo sh -c 'for db in {pattern}; do \
+         [ -f \"$db\" ] || continue; \
+         mkdir -p \"{scratch_dir}$(dirname \"$db\")\" \
+         && sqlite3 \"$db\" \".backup {scratch_dir}$db\" || exit 1; \
+         echo \"$db\"; done'"
+    )
+}
+
+fn clear_sidecars_command(pattern: &str) -> String {
+    let sidecars: Vec<String> = SQLITE_SIDECARS
+        .iter()
+        .map(|suffix| format!("{pattern}{suffix}"))
+        .collect();
+    format!("sudo sh -c 'rm -f -- {}'", sidecars.join(" "))
+}
+
 fn is_warnings_only(text: &str) -> bool {
     text.lines().all(|line| {
         let trimmed = line.trim().to_lowercase();
@@ -246,7 +381,7 @@ fn is_warnings_only(text: &str) -> bool {
 mod tests {
     use super::*;
     use crate::playbook_meta::{BackupParameter, DbRecipe};
-    use crate::services::ssh::{MockSshSession, SshOp};
+    use crate::services::ssh::{CommandResult, MockSshSession, SshOp};
 
     fn baikal_recipe() -> BackupRecipe {
         BackupRecipe {
@@ -254,6 +389,8 @@ mod tests {
             paths: vec!["/opt/baikal/Specific".to_string()],
             owner: Some(("baikal".to_string(), "baikal".to_string())),
             db: None,
+            sqlite: vec![],
+            copy_live: false,
             post_restore_command: None,
             parameters: HashMap::new(),
         }
@@ -268,6 +405,8 @@ mod tests {
                 name: "paperless".to_string(),
                 dump_path: "/tmp/paperless_db.dump".to_string(),
             }),
+            sqlite: vec![],
+            copy_live: false,
             post_restore_command: Some("sudo -u paperless ./manage.py migrate".to_string()),
             parameters: HashMap::new(),
         }
@@ -287,6 +426,8 @@ mod tests {
             paths: vec!["/var/lib/navidrome".to_string()],
             owner: Some(("navidrome".to_string(), "navidrome".to_string())),
             db: None,
+            sqlite: vec![],
+            copy_live: false,
             post_restore_command: None,
             parameters: params,
         }
@@ -302,11 +443,182 @@ mod tests {
             ],
             owner: Some(("alice".to_string(), "alice".to_string())),
             db: None,
+            sqlite: vec![],
+            copy_live: false,
             post_restore_command: None,
             parameters: HashMap::new(),
         }
     }
 
+    fn actual_recipe() -> BackupRecipe {
+        BackupRecipe {
+            systemd_services: vec!["actual".to_string()],
+            paths: vec!["/var/lib/actual".to_string()],
+            owner: Some(("actual".to_string(), "actual".to_string())),
+            db: None,
+            sqlite: vec![
+                "/var/lib/actual/server-files/account.sqlite".to_string(),
+                "/var/lib/actual/user-files/*.sqlite".to_string(),
+            ],
+            copy_live: false,
+            post_restore_command: None,
+            parameters: HashMap::new(),
+        }
+    }
+
+    fn stdout(text: &str) -> CommandResult {
+        CommandResult {
+            stdout: text.as_bytes().to_vec(),
+            ..CommandResult::ok()
+        }
+    }
+
+    #[test]
+    fn test_backup_with_sqlite_keeps_services_running_and_fetches_snapshots() {
+        let tmp = tempfile::tempdir().unwrap();
+        let staged = tmp.path().join("var/lib/actual/user-files/budget.sqlite");
+        std::fs::create_dir_all(staged.parent().unwrap()).unwrap();
+        std::fs::write(&staged, b"torn").unwrap();
+        std::fs::write(
+            tmp.path()
+                .join("var/lib/actual/user-files/budget.sqlite-wal"),
+            b"",
+        )
+        .unwrap();
+
+        let mock = MockSshSession::new();
+        mock.stage_run_result(stdout("/tmp/auberge-sqlite.AbC123\n"));
+        mock.stage_run_result(stdout("/var/lib/actual/server-files/account.sqlite\n"));
+        mock.stage_run_result(stdout("/var/lib/actual/user-files/budget.sqlite\n"));
+        let executor = RecipeExecutor::new(&mock);
+        let mut progress = crate::services::progress::MockProgress::new();
+        let recipe = BackupRecipe {
+            copy_live: true,
+            ..actual_recipe()
+        };
+        executor
+            .backup(&recipe, tmp.path(), &HashMap::new(), &mut progress)
+            .unwrap();
+
+        let calls = mock.calls();
+        assert!(!calls.iter().any(|c| matches!(c, SshOp::Systemctl { .. })));
+        assert!(matches!(
+            &calls[0],
+            SshOp::RsyncFrom { remote, .. } if remote == "/var/lib/actual"
+        ));
+        match &calls[2] {
+            SshOp::Run(cmd) => {
+                assert!(cmd.contains("for db in /var/lib/actual/server-files/account.sqlite;"));
+                assert!(cmd.contains(".backup /tmp/auberge-sqlite.AbC123$db"));
+            }
+            other => panic!("expected sqlite3 .backup Run, got {other:?}"),
+        }
+        assert!(calls.contains(&SshOp::ScpFrom {
+            remote:
+                "/tmp/auberge-sqlite.AbC123/var/lib/actual/user-files/budget.sqlite".to_string(),
+            local: staged.clone(),
+        }));
+        assert_eq!(
+            calls.last().unwrap(),
+            &SshOp::Run("sudo rm -rf /tmp/auberge-sqlite.AbC123".to_string())
+        );
+        assert!(staged.exists());
+        assert!(!staged.with_extension("sqlite-wal").exists());
+    }
+
+    #[test]
+    fn test_backup_with_sqlite_stops_services_unless_the_recipe_copies_live() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mock = MockSshSession::new();
+        mock.stage_run_result(stdout("/tmp/auberge-sqlite.AbC123\n"));
+        mock.stage_run_result(stdout(""));
+        mock.stage_run_result(stdout(""));
+        let executor = RecipeExecutor::new(&mock);
+        let mut progress = crate::services::progress::MockProgress::new();
+        executor
+            .backup(&actual_recipe(), tmp.path(), &HashMap::new(), &mut progress)
+            .unwrap();
+
+        let calls = mock.calls();
+        let stop = SshOp::Systemctl {
+            action: "stop".to_string(),
+            service: "actual".to_string(),
+        };
+        assert_eq!(calls[0], stop);
+        assert!(matches!(
+            &calls[1],
+            SshOp::RsyncFrom { remote, .. } if remote == "/var/lib/actual"
+        ));
+        assert_eq!(
+            calls.last().unwrap(),
+            &SshOp::Systemctl {
+                action: "start".to_string(),
+                service: "actual".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_backup_with_sqlite_failure_still_removes_scratch_dir() {
+        let mock = MockSshSession::new();
+        mock.stage_run_result(stdout("/tmp/auberge-sqlite.AbC123\n"));
+        mock.stage_run_result(CommandResult {
+            success: false,
+            exit_code: Some(1),
+            stdout: Vec::new(),
+            stderr: b"sqlite3: command not found".to_vec(),
+        });
+        let executor = RecipeExecutor::new(&mock);
+        let mut progress = crate::services::progress::MockProgress::new();
+        let err = executor
+            .backup(
+                &actual_recipe(),
+                Path::new("/tmp/dest"),
+                &HashMap::new(),
+                &mut progress,
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "SQLite backup failed for /var/lib/actual/server-files/account.sqlite: \
+             sqlite3: command not found"
+        );
+        assert!(mock.calls().contains(&SshOp::Run(
+            "sudo rm -rf /tmp/auberge-sqlite.AbC123".to_string()
+        )));
+    }
+
+    #[test]
+    fn test_restore_with_sqlite_clears_journals_before_rsync_then_chowns() {
+        let mock = MockSshSession::new();
+        let executor = RecipeExecutor::new(&mock);
+        let mut progress = crate::services::progress::MockProgress::new();
+        executor
+            .restore(&actual_recipe(), Path::new("/tmp/source"), &mut progress)
+            .unwrap();
+
+        let calls = mock.calls();
+        assert_eq!(
+            calls[1],
+            SshOp::Run(
+                "sudo sh -c 'rm -f -- /var/lib/actual/server-files/account.sqlite-wal \
+                 /var/lib/actual/server-files/account.sqlite-shm \
+                 /var/lib/actual/server-files/account.sqlite-journal'"
+                    .to_string()
+            )
+        );
+        assert!(matches!(&calls[3], SshOp::RsyncTo { remote, .. } if remote == "/var/lib/actual"));
+        assert_eq!(
+            calls[4],
+            SshOp::SetOwnership {
+                remote: "/var/lib/actual".to_string(),
+                user: "actual".to_string(),
+                group: "actual".to_string(),
+            }
+        );
+    }
+
     #[test]
     fn test_backup_syncthing_stops_unit_rsyncs_identity_files_then_starts() {
         let mock = MockSshSession::new();
@@ -406,6 +718,8 @@ mod tests {
             paths: vec!["/opt/bichon/data".to_string()],
             owner: None,
             db: None,
+            sqlite: vec![],
+            copy_live: false,
             post_restore_command: None,
             parameters: HashMap::new(),
         };
@@ -549,6 +863,8 @@ mod tests {
             paths: vec!["/var/lib/freshrss".to_string()],
             owner: Some(("freshrss".to_string(), "freshrss".to_string())),
             db: None,
+            sqlite: vec![],
+            copy_live: false,
             post_restore_command: None,
             parameters: HashMap::new(),
         };
@@ -586,6 +902,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_restore_stopped_leaves_services_stopped_and_skips_post_restore() {
+        let mock = MockSshSession::new();
+        let executor = RecipeExecutor::new(&mock);
+        let mut progress = crate::services::progress::MockProgress::new();
+        executor
+            .restore_stopped(&paperless_recipe(), Path::new("/tmp/source"), &mut progress)
+            .unwrap();
+
+        let calls = mock.calls();
+        assert_eq!(
+            calls[0],
+            SshOp::Systemctl {
+                action: "stop".to_string(),
+                service: "paperless-webserver".to_string(),
+            }
+        );
+        assert!(matches!(&calls[1], SshOp::RsyncTo { .. }));
+        assert!(
+            !calls
+                .iter()
+                .any(|c| matches!(c, SshOp::Systemctl { action, .. } if action == "start"))
+        );
+        assert!(
+            !calls
+                .iter()
+                .any(|c| matches!(c, SshOp::Run(cmd) if cmd.contains("manage.py migrate")))
+        );
+    }
+
     #[test]
     fn test_restore_runs_post_restore_command_after_db_restore() {
         let tmp = tempfile::tempdir().unwrap();
@@ -114,6 +114,23 @@ mod tests {
         assert_eq!(apps, expected);
     }
 
+    #[test]
+    fn test_every_sqlite_database_lies_under_a_backed_up_path() {
+        let dir = project_playbooks_dir();
+        for app in discover_backuppable_apps(&dir).unwrap() {
+            let recipe = load_app_recipe(&dir, &app, "alice").unwrap();
+            for database in &recipe.sqlite {
+                assert!(
+                    recipe
+                        .paths
+                        .iter()
+                        .any(|path| database.starts_with(&format!("{path}/"))),
+                    "{app}: sqlite entry {database} is outside its paths"
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_discover_backuppable_apps_excludes_non_backup_metas() {
         let apps = discover_backuppable_apps(&project_playbooks_dir()).unwrap();
@@ -0,0 +1,240 @@
+use crate::config::Config;
+use crate::secrets::resolve_value;
+use crate::services::backup::retention::Retention;
+use eyre::{Context, Result, eyre};
+
+/// The name the legacy `restic_repository` / `restic_password` pair goes by.
+pub const DEFAULT_NAME: &str = "default";
+
+/// One offsite restic repository, its credentials resolved.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    pub name: String,
+    /// `RESTIC_REPOSITORY`: a path, `sftp:…`, `rclone:…`.
+    pub location: String,
+    pub password: String,
+    pub retention: Retention,
+}
+
+/// A configured repository before its location and password are resolved.
+/// A fan-out resolves each one as it reaches it, so one failing password
+/// command fails only its own repository.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepositorySpec {
+    pub name: String,
+    pub location: String,
+    pub password: String,
+    pub retention: Retention,
+}
+
+impl RepositorySpec {
+    pub fn resolve(&self) -> Result<Repository> {
+        let resolved = |value: &str, what: &str| {
+            resolve_value(value).wrap_err_with(|| {
+                format!(
+                    "Failed to resolve the {what} of restic repository '{}'",
+                    self.name
+                )
+            })
+        };
+        Ok(Repository {
+            name: self.name.clone(),
+            location: resolved(&self.location, "location")?,
+            password: resolved(&self.password, "password")?,
+            retention: self.retention.clone(),
+        })
+    }
+}
+
+/// Every repository backups go to — or only `only` — sorted by name, none
+/// of them resolved yet.
+///
+/// `[backup.repositories.<name>]` tables each set `repository`, `password`
+/// and optionally a `retention` table laid over `[backup.retention]`.
+/// Without them the legacy `restic_repository` / `restic_password` pair is
+/// the one repository, named `default`.
+pub fn load(config: &Config, only: Option<&str>) -> Result<Vec<RepositorySpec>> {
+    select(specs(config)?, only)
+}
+
+/// The one repository restoring reads from: `only`, or the sole configured
+/// one. Restore never fans out, so several repositories need `--repo`.
+pub fn load_one(config: &Config, only: Option<&str>) -> Result<Repository> {
+    let specs = specs(config)?;
+    if only.is_none() && specs.len() > 1 {
+        let names: Vec<&str> = specs.iter().map(|spec| spec.name.as_str()).collect();
+        eyre::bail!(
+            "{} restic repositories configured; pass --repo <{}>",
+            names.len(),
+            names.join("|")
+        );
+    }
+    select(specs, only)?.remove(0).resolve()
+}
+
+fn specs(config: &Config) -> Result<Vec<RepositorySpec>> {
+    let Some(tables) = config.backup_repositories() else {
+        return legacy_spec(config).map(|spec| vec![spec]);
+    };
+    if tables.is_empty() {
+        eyre::bail!("[backup.repositories] lists no repositories");
+    }
+    tables
+        .iter()
+        .map(|(name, table)| {
+            let section = format!("backup.repositories.{name}");
+            let table = table
+                .as_table()
+                .ok_or_else(|| eyre!("[{section}] must be a table"))?;
+            let field = |key: &str| match table.get(key).and_then(toml::Value::as_str) {
+                Some(value) if !value.trim().is_empty() => Ok(value.to_string()),
+                _ => Err(eyre!("[{section}] needs a `{key}` string")),
+            };
+            let retention = match table.get("retention") {
+                Some(toml::Value::Table(retention)) => Some(retention),
+                Some(_) => eyre::bail!("[{section}.retention] must be a table"),
+                None => None,
+            };
+            Ok(RepositorySpec {
+                name: name.clone(),
+                location: field("repository")?,
+                password: field("password")?,
+                retention: Retention::layered(
+                    config.backup_retention(),
+                    retention,
+                    &format!("{section}.retention"),
+                )?,
+            })
+        })
+        .collect()
+}
+
+fn legacy_spec(config: &Config) -> Result<RepositorySpec> {
+    let missing = config.validate_required(&["restic_repository", "restic_password"]);
+    if !missing.is_empty() {
+        eyre::bail!(
+            "Missing restic config: {}. Set with `auberge config set <key> <value>`, or list repositories under [backup.repositories]",
+            missing.join(", ")
+        );
+    }
+    let value = |key: &str| {
+        config
+            .get(key)
+            .ok_or_else(|| eyre!("{key} is missing or not a valid value"))
+    };
+    Ok(RepositorySpec {
+        name: DEFAULT_NAME.to_string(),
+        location: value("restic_repository")?,
+        password: value("restic_password")?,
+        retention: Retention::from_table(config.backup_retention())?,
+    })
+}
+
+fn select(specs: Vec<RepositorySpec>, only: Option<&str>) -> Result<Vec<RepositorySpec>> {
+    let Some(only) = only else {
+        return Ok(specs);
+    };
+    let names: Vec<&str> = specs.iter().map(|spec| spec.name.as_str()).collect();
+    let known = names.join(", ");
+    specs
+        .into_iter()
+        .find(|spec| spec.name == only)
+        .map(|spec| vec![spec])
+        .ok_or_else(|| eyre!("No restic repository named '{only}' (configured: {known})"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(toml_str: &str) -> Config {
+        Config::from_toml_str(toml_str).unwrap()
+    }
+
+    #[test]
+    fn test_legacy_pair_is_the_default_repository() {
+        let legacy = specs(&config(
+            r#"
+            restic_repository = "rclone:filen:auberge-backup"
+            restic_password = "!pass show restic"
+            [backup.retention]
+            keep_daily = 3
+        "#,
+        ))
+        .unwrap();
+        assert_eq!(legacy.len(), 1);
+        assert_eq!(legacy[0].name, DEFAULT_NAME);
+        assert_eq!(legacy[0].location, "rclone:filen:auberge-backup");
+        assert_eq!(legacy[0].password, "!pass show restic");
+        assert_eq!(
+            legacy[0].retention.global.describe(),
+            "3 daily, 4 weekly, 12 monthly"
+        );
+
+        let err = specs(&config("restic_repository = \"/mnt/usb\"")).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Missing restic config: restic_password")
+        );
+    }
+
+    #[test]
+    fn test_repositories_table_replaces_the_legacy_pair() {
+        let specs = specs(&config(
+            r#"
+            restic_repository = "ignored"
+            restic_password = "ignored"
+            [backup.retention]
+            keep_daily = 3
+            [backup.repositories.usb]
+            repository = "/mnt/usb/auberge"
+            password = "!pass show restic/usb"
+            [backup.repositories.usb.retention]
+            keep_daily = 30
+            [backup.repositories.nas]
+            repository = "sftp:nas:/srv/restic"
+            password = "hunter2"
+        "#,
+        ))
+        .unwrap();
+        let names: Vec<&str> = specs.iter().map(|spec| spec.name.as_str()).collect();
+        assert_eq!(names, ["nas", "usb"]);
+        assert_eq!(
+            specs[0].retention.global.describe(),
+            "3 daily, 4 weekly, 12 monthly"
+        );
+        assert_eq!(specs[1].location, "/mnt/usb/auberge");
+        assert_eq!(
+            specs[1].retention.global.describe(),
+            "30 daily, 4 weekly, 12 monthly"
+        );
+    }
+
+    #[test]
+    fn test_repository_without_password_is_rejected() {
+        let err = specs(&config(
+            "[backup.repositories.usb]\nrepository = \"/mnt/usb/auberge\"",
+        ))
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "[backup.repositories.usb] needs a `password` string"
+        );
+    }
+
+    #[test]
+    fn test_select_narrows_to_the_named_repository() {
+        let all = specs(&config(
+            "[backup.repositories.usb]\nrepository = \"/mnt/usb\"\npassword = \"a\"\n\
+             [backup.repositories.nas]\nrepository = \"sftp:nas:/r\"\npassword = \"b\"",
+        ))
+        .unwrap();
+        assert_eq!(select(all.clone(), None).unwrap().len(), 2);
+        assert_eq!(select(all.clone(), Some("usb")).unwrap()[0].name, "usb");
+        let err = select(all, Some("s3")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No restic repository named 's3' (configured: nas, usb)"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use eyre::{Context, Result};
 use serde::Deserialize;
 use std::io::{BufRead, BufReader};
+use std::path::Path;
 use std::process::{Command, Stdio};
 
 #[derive(Debug, Deserialize)]
@@ -84,6 +85,27 @@ pub fn snapshots_json(repo: &str, password: &str) -> Result<String> {
     Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
 
+/// Raw `restic forget --json` output for `args`, which should include
+/// `--dry-run` unless the caller means to forget.
+pub fn forget_json(repo: &str, password: &str, args: &[String]) -> Result<String> {
+    let output = command(repo, password)
+        .arg("forget")
+        .arg("--json")
+        .args(args)
+        .output()
+        .wrap_err("Failed to run restic. Install restic: https://restic.net")?;
+
+    if !output.status.success() {
+        let reason = error_message(&String::from_utf8_lossy(&output.stderr));
+        eyre::bail!(match reason.is_empty() {
+            true => format!("restic forget failed ({})", output.status),
+            false => reason,
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
 /// Whether `path` is present inside a snapshot.
 ///
 /// `restic ls <id> <dir>` walks only that subtree and exits 0 whether or not
@@ -125,6 +147,109 @@ pub fn snapshot_contains_path(
     Ok(false)
 }
 
+/// Restore `includes` of a snapshot under `target`, where each lands at its
+/// absolute path (`<target>/<path>`); no `includes` restores everything.
+pub fn restore(
+    repo: &str,
+    password: &str,
+    snapshot_id: &str,
+    includes: &[String],
+    target: &Path,
+) -> Result<()> {
+    match try_restore(repo, password, snapshot_id, includes, target)? {
+        None => Ok(()),
+        Some(reason) => Err(eyre::eyre!(reason)),
+    }
+}
+
+/// `restic restore`, telling a snapshot that does not restore apart from a
+/// restore that could not run. `Ok(Some(reason))` when restic ran and could
+/// not restore the data; an `Err` when restic could not run, open, lock or
+/// decrypt the repository, or write the target.
+pub fn try_restore(
+    repo: &str,
+    password: &str,
+    snapshot_id: &str,
+    includes: &[String],
+    target: &Path,
+) -> Result<Option<String>> {
+    let output = restore_command(repo, password, snapshot_id, includes, target)
+        .output()
+        .wrap_err("Failed to run restic. Install restic: https://restic.net")?;
+
+    if output.status.success() {
+        return Ok(None);
+    }
+    let reason = error_message(&String::from_utf8_lossy(&output.stderr));
+    let reason = match reason.is_empty() {
+        true => format!("restic restore failed ({})", output.status),
+        false => format!("restic restore failed: {reason}"),
+    };
+    if is_operational(output.status.code(), &reason) {
+        eyre::bail!(reason);
+    }
+    Ok(Some(reason))
+}
+
+/// Local disk errors writing a restore target, in the wording restic passes
+/// on from the OS.
+const DISK_ERRORS: [&str; 3] = [
+    "no space left on device",
+    "read-only file system",
+    "permission denied",
+];
+
+/// Whether a failed restic run failed around the repository's data rather
+/// than on it: killed, or exiting 10 (no repository), 11 (locked) or 12
+/// (wrong password), or unable to write locally.
+fn is_operational(code: Option<i32>, reason: &str) -> bool {
+    let reason = reason.to_lowercase();
+    matches!(code, None | Some(10..=12)) || DISK_ERRORS.iter().any(|e| reason.contains(e))
+}
+
+fn restore_command(
+    repo: &str,
+    password: &str,
+    snapshot_id: &str,
+    includes: &[String],
+    target: &Path,
+) -> Command {
+    let mut cmd = command(repo, password);
+    cmd.arg("restore")
+        .arg(snapshot_id)
+        .arg("--target")
+        .arg(target);
+    for include in includes {
+        cmd.arg("--include").arg(include);
+    }
+    cmd
+}
+
+/// `restic check` reading back `read_data_subset` of the pack files (`5%`,
+/// `1/10`). `Ok(Some(reason))` when restic ran and found the repository
+/// damaged; `Err` when it could not run at all.
+pub fn check(repo: &str, password: &str, read_data_subset: &str) -> Result<Option<String>> {
+    let output = check_command(repo, password, read_data_subset)
+        .output()
+        .wrap_err("Failed to run restic. Install restic: https://restic.net")?;
+
+    if output.status.success() {
+        return Ok(None);
+    }
+    let reason = error_message(&String::from_utf8_lossy(&output.stderr));
+    Ok(Some(match reason.is_empty() {
+        true => format!("restic check failed ({})", output.status),
+        false => reason,
+    }))
+}
+
+fn check_command(repo: &str, password: &str, read_data_subset: &str) -> Command {
+    let mut cmd = command(repo, password);
+    cmd.arg("check")
+        .arg(format!("--read-data-subset={read_data_subset}"));
+    cmd
+}
+
 /// `--no-lock` because the caller SIGKILLs on first match: a killed `ls`
 /// skips restic's cleanup and would leave its default non-exclusive lock
 /// behind on every matching verify.
@@ -200,6 +325,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn failed_restores_around_the_data_are_operational() {
+        assert!(is_operational(
+            Some(11),
+            "restic restore failed: repository is already locked"
+        ));
+        assert!(is_operational(None, "restic restore failed (signal: 9)"));
+        assert!(is_operational(
+            Some(1),
+            "restic restore failed: write /tmp/x: No space left on device"
+        ));
+        assert!(!is_operational(
+            Some(1),
+            "restic restore failed: ciphertext verification failed"
+        ));
+    }
+
     #[test]
     fn parse_restic_exit_error_line() {
         let line = r#"{"message_type":"exit_error","code":12,"message":"Fatal: wrong password or no key found"}"#;
@@ -243,6 +385,44 @@ mod tests {
         assert_eq!(args, ["ls", "--no-lock", "abc123", "/backups/x"]);
     }
 
+    #[test]
+    fn restore_command_includes_each_app_path() {
+        let includes = [
+            "/backups/x/ts/bichon".to_string(),
+            "/backups/x/ts/navidrome".to_string(),
+        ];
+        let cmd = restore_command("repo", "", "abc123", &includes, Path::new("/tmp/stage"));
+        let args: Vec<String> = cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+
+        assert_eq!(
+            args,
+            [
+                "restore",
+                "abc123",
+                "--target",
+                "/tmp/stage",
+                "--include",
+                "/backups/x/ts/bichon",
+                "--include",
+                "/backups/x/ts/navidrome",
+            ]
+        );
+    }
+
+    #[test]
+    fn check_command_reads_back_the_subset() {
+        let cmd = check_command("repo", "", "5%");
+        let args: Vec<String> = cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+
+        assert_eq!(args, ["check", "--read-data-subset=5%"]);
+    }
+
     #[test]
     fn error_message_extracts_exit_error_message() {
         let stderr = r#"{"message_type":"exit_error","code":10,"message":"Fatal: repository does not exist"}"#;
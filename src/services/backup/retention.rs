@@ -0,0 +1,519 @@
+use crate::services::backup::verify::Snapshot;
+use eyre::{Result, eyre};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// One `keep_*` rule of a retention policy: a `restic forget --keep-*` flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    Last,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 6] = [
+        Self::Last,
+        Self::Hourly,
+        Self::Daily,
+        Self::Weekly,
+        Self::Monthly,
+        Self::Yearly,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Last => "last",
+            Self::Hourly => "hourly",
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+            Self::Yearly => "yearly",
+        }
+    }
+
+    /// The config key setting the rule's count: `keep_daily`.
+    pub fn key(self) -> String {
+        format!("keep_{}", self.as_str())
+    }
+}
+
+/// How many snapshots each [`Rule`] keeps. A snapshot any rule keeps stays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RetentionPolicy {
+    counts: [u32; 6],
+}
+
+impl RetentionPolicy {
+    /// What `backup prune` kept before retention was configurable: 7 daily,
+    /// 4 weekly and 12 monthly snapshots.
+    pub const DEFAULT: Self = Self {
+        counts: [0, 0, 7, 4, 12, 0],
+    };
+
+    pub fn count(&self, rule: Rule) -> u32 {
+        self.counts[rule as usize]
+    }
+
+    /// This policy with the `keep_*` keys of `table` laid over it.
+    fn with_overrides(mut self, table: &toml::Table, section: &str) -> Result<Self> {
+        for (key, value) in table {
+            let Some(rule) = Rule::ALL.into_iter().find(|rule| rule.key() == *key) else {
+                return Err(eyre!(
+                    "Unknown key '{key}' in [{section}]; expected keep_last, keep_hourly, keep_daily, keep_weekly, keep_monthly or keep_yearly"
+                ));
+            };
+            let count = match value {
+                toml::Value::Integer(n) => u32::try_from(*n).ok(),
+                toml::Value::String(s) => s.trim().parse().ok(),
+                _ => None,
+            }
+            .ok_or_else(|| eyre!("[{section}] {key} must be a whole number, not {value}"))?;
+            self.counts[rule as usize] = count;
+        }
+        if self.counts.iter().all(|count| *count == 0) {
+            return Err(eyre!(
+                "[{section}] keeps no snapshots: every keep_* is 0, so prune would forget them all; set at least one above 0"
+            ));
+        }
+        Ok(self)
+    }
+
+    /// The `restic forget` flags of the policy: `--keep-daily 7`.
+    pub fn keep_args(&self) -> Vec<String> {
+        Rule::ALL
+            .into_iter()
+            .filter(|rule| self.count(*rule) > 0)
+            .flat_map(|rule| {
+                [
+                    format!("--keep-{}", rule.as_str()),
+                    self.count(rule).to_string(),
+                ]
+            })
+            .collect()
+    }
+
+    /// `7 daily, 4 weekly, 12 monthly`.
+    pub fn describe(&self) -> String {
+        let parts: Vec<String> = Rule::ALL
+            .into_iter()
+            .filter(|rule| self.count(*rule) > 0)
+            .map(|rule| format!("{} {}", self.count(rule), rule.as_str()))
+            .collect();
+        match parts.is_empty() {
+            true => "nothing".to_string(),
+            false => parts.join(", "),
+        }
+    }
+}
+
+/// The retention policies of `[backup.retention]`: the table's own `keep_*`
+/// keys, over [`RetentionPolicy::DEFAULT`], and one `[backup.retention.apps.<app>]`
+/// table per app whose keys override those.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Retention {
+    pub global: RetentionPolicy,
+    pub apps: BTreeMap<String, RetentionPolicy>,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            global: RetentionPolicy::DEFAULT,
+            apps: BTreeMap::new(),
+        }
+    }
+}
+
+impl Retention {
+    pub fn from_table(table: Option<&toml::Table>) -> Result<Self> {
+        match table {
+            Some(table) => Self::from_section(table, "backup.retention"),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// The retention of one repository: `[backup.retention]` with the
+    /// repository's own `retention` table laid over it key by key, app
+    /// tables included. `section` names that table in errors.
+    pub fn layered(
+        global: Option<&toml::Table>,
+        repository: Option<&toml::Table>,
+        section: &str,
+    ) -> Result<Self> {
+        let retention = Self::from_table(global)?;
+        let Some(repository) = repository else {
+            return Ok(retention);
+        };
+
+        let mut merged = global.cloned().unwrap_or_default();
+        for (key, value) in repository {
+            match (merged.get_mut(key), value) {
+                (Some(toml::Value::Table(apps)), toml::Value::Table(overrides))
+                    if key == "apps" =>
+                {
+                    for (app, keys) in overrides {
+                        match (apps.get_mut(app), keys) {
+                            (Some(toml::Value::Table(base)), toml::Value::Table(keys)) => {
+                                base.extend(keys.clone())
+                            }
+                            _ => {
+                                apps.insert(app.clone(), keys.clone());
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    merged.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        Self::from_section(&merged, section)
+    }
+
+    fn from_section(table: &toml::Table, section: &str) -> Result<Self> {
+        let mut own = table.clone();
+        let apps = own.remove("apps");
+        let global = RetentionPolicy::DEFAULT.with_overrides(&own, section)?;
+
+        let mut retention = Self {
+            global,
+            apps: BTreeMap::new(),
+        };
+        let Some(apps) = apps else {
+            return Ok(retention);
+        };
+        let apps = apps
+            .as_table()
+            .ok_or_else(|| eyre!("[{section}.apps] must be a table of apps"))?;
+        for (app, overrides) in apps {
+            let section = format!("{section}.apps.{app}");
+            let overrides = overrides
+                .as_table()
+                .ok_or_else(|| eyre!("[{section}] must be a table of keep_* keys"))?;
+            retention
+                .apps
+                .insert(app.clone(), global.with_overrides(overrides, &section)?);
+        }
+        Ok(retention)
+    }
+
+    pub fn for_app(&self, app: &str) -> RetentionPolicy {
+        self.apps.get(app).copied().unwrap_or(self.global)
+    }
+}
+
+/// What pruning does with one snapshot, and why.
+#[derive(Debug)]
+pub struct Decision<'a> {
+    pub snapshot: &'a Snapshot,
+    /// Every rule that keeps the snapshot, as `paperless: monthly` (or just
+    /// `daily` for a snapshot without app tags); empty means it is dropped.
+    pub kept_by: Vec<String>,
+}
+
+impl Decision<'_> {
+    pub fn keep(&self) -> bool {
+        !self.kept_by.is_empty()
+    }
+}
+
+/// One group of `restic forget --json` output. Only the reasons are read:
+/// each kept snapshot with the rules keeping it.
+#[derive(Debug, Deserialize)]
+pub struct ForgetGroup {
+    #[serde(default)]
+    reasons: Option<Vec<KeepReason>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeepReason {
+    snapshot: KeptSnapshot,
+    matches: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeptSnapshot {
+    id: String,
+}
+
+/// What restic decided for one policy: an app's policy over its snapshots,
+/// or the global policy (`app` is `None`), which only decides for snapshots
+/// without app tags.
+#[derive(Debug)]
+pub struct PolicyRun<'a> {
+    pub app: Option<&'a str>,
+    pub groups: Vec<ForgetGroup>,
+}
+
+/// Decide every snapshot's fate from restic's own keep decisions, newest
+/// first within each Host.
+///
+/// A snapshot holds every app it was pushed with, so it stays as long as any
+/// of their policies keeps it; restic forgetting per app would drop it as
+/// soon as one of them does not.
+pub fn plan<'a>(snapshots: &'a [Snapshot], runs: &[PolicyRun]) -> Vec<Decision<'a>> {
+    let mut ordered: Vec<&Snapshot> = snapshots.iter().collect();
+    ordered.sort_by(|a, b| a.host().cmp(&b.host()).then(b.time.cmp(&a.time)));
+    ordered
+        .into_iter()
+        .map(|snapshot| {
+            let untagged = snapshot.apps().is_empty();
+            let mut kept_by = Vec::new();
+            for run in runs {
+                if run.app.is_none() && !untagged {
+                    continue;
+                }
+                let reasons = run
+                    .groups
+                    .iter()
+                    .flat_map(|group| group.reasons.iter().flatten());
+                for reason in reasons.filter(|reason| reason.snapshot.id == snapshot.id) {
+                    for matched in &reason.matches {
+                        let rule = matched.strip_suffix(" snapshot").unwrap_or(matched);
+                        kept_by.push(match run.app {
+                            Some(app) => format!("{app}: {rule}"),
+                            None => rule.to_string(),
+                        });
+                    }
+                }
+            }
+            Decision { snapshot, kept_by }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(id: &str, time: &str, tags: &[&str]) -> Snapshot {
+        Snapshot {
+            id: id.to_string(),
+            time: time.parse().unwrap(),
+            paths: vec![format!("/b/backups/myserver/{id}")],
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        }
+    }
+
+    fn retention(toml_str: &str) -> Result<Retention> {
+        let table: toml::Table = toml::from_str(toml_str).unwrap();
+        Retention::from_table(Some(&table))
+    }
+
+    fn kept<'a>(decisions: &'a [Decision<'_>]) -> Vec<&'a str> {
+        decisions
+            .iter()
+            .filter(|d| d.keep())
+            .map(|d| d.snapshot.id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_no_section_keeps_the_old_default() {
+        let retention = Retention::from_table(None).unwrap();
+        assert_eq!(retention.global, RetentionPolicy::DEFAULT);
+        assert_eq!(retention.global.describe(), "7 daily, 4 weekly, 12 monthly");
+    }
+
+    #[test]
+    fn test_app_tables_override_the_global_keys() {
+        let retention = retention(
+            "keep_daily = 3\n\
+             [apps.paperless]\n\
+             keep_yearly = \"5\"\n\
+             [apps.freshrss]\n\
+             keep_monthly = 0\n",
+        )
+        .unwrap();
+        assert_eq!(retention.global.describe(), "3 daily, 4 weekly, 12 monthly");
+        assert_eq!(
+            retention.for_app("paperless").describe(),
+            "3 daily, 4 weekly, 12 monthly, 5 yearly"
+        );
+        assert_eq!(
+            retention.for_app("freshrss").describe(),
+            "3 daily, 4 weekly"
+        );
+        assert_eq!(retention.for_app("baikal"), retention.global);
+    }
+
+    #[test]
+    fn test_bad_keys_and_counts_are_rejected() {
+        let err = retention("keep_dayly = 3").unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Unknown key 'keep_dayly' in [backup.retention]")
+        );
+        let err = retention("[apps.paperless]\nkeep_daily = -1").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "[backup.retention.apps.paperless] keep_daily must be a whole number, not -1"
+        );
+    }
+
+    #[test]
+    fn test_a_policy_keeping_nothing_is_rejected() {
+        let err = retention("[apps.freshrss]\nkeep_daily = 0\nkeep_weekly = 0\nkeep_monthly = 0")
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("[backup.retention.apps.freshrss] keeps no snapshots"),
+            "{err}"
+        );
+        let err = Retention::layered(
+            None,
+            Some(&toml::from_str("keep_daily = 0\nkeep_weekly = 0\nkeep_monthly = 0").unwrap()),
+            "backup.repositories.usb.retention",
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("[backup.repositories.usb.retention] keeps no snapshots"),
+            "{err}"
+        );
+        assert!(
+            retention(
+                "keep_last = 1\n[apps.freshrss]\nkeep_daily = 0\nkeep_weekly = 0\nkeep_monthly = 0"
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_layered_lays_a_repository_table_over_the_global_one() {
+        let global: toml::Table =
+            toml::from_str("keep_daily = 3\n[apps.paperless]\nkeep_yearly = 5").unwrap();
+        let usb: toml::Table = toml::from_str(
+            "keep_weekly = 0\n[apps.paperless]\nkeep_monthly = 24\n[apps.freshrss]\nkeep_monthly = 1",
+        )
+        .unwrap();
+        let retention = Retention::layered(
+            Some(&global),
+            Some(&usb),
+            "backup.repositories.usb.retention",
+        )
+        .unwrap();
+        assert_eq!(retention.global.describe(), "3 daily, 12 monthly");
+        assert_eq!(
+            retention.for_app("paperless").describe(),
+            "3 daily, 24 monthly, 5 yearly"
+        );
+        assert_eq!(
+            retention.for_app("freshrss").describe(),
+            "3 daily, 1 monthly"
+        );
+
+        let err = Retention::layered(
+            None,
+            Some(&toml::from_str("keep_dayly = 1").unwrap()),
+            "backup.repositories.usb.retention",
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("[backup.repositories.usb.retention]"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_keep_args_are_the_nonzero_rules() {
+        let retention = retention("keep_last = 2\nkeep_weekly = 0").unwrap();
+        assert_eq!(
+            retention.global.keep_args(),
+            [
+                "--keep-last",
+                "2",
+                "--keep-daily",
+                "7",
+                "--keep-monthly",
+                "12"
+            ]
+        );
+        assert!(RetentionPolicy::default().keep_args().is_empty());
+    }
+
+    fn groups(kept: &[(&str, &[&str])]) -> Vec<ForgetGroup> {
+        let reasons: Vec<serde_json::Value> = kept
+            .iter()
+            .map(|(id, matches)| {
+                serde_json::json!({
+                    "snapshot": { "id": id, "time": "2026-07-29T03:00:00Z" },
+                    "matches": matches,
+                    "counters": {}
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!([
+            { "tags": ["myserver"], "keep": null, "remove": null, "reasons": reasons },
+            { "tags": ["other"], "keep": null, "remove": null, "reasons": null }
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_plan_keeps_what_any_app_policy_keeps() {
+        let snapshots = vec![
+            snapshot("new", "2026-07-29T03:00:00Z", &["myserver", "app:freshrss"]),
+            snapshot(
+                "mid",
+                "2026-06-15T03:00:00Z",
+                &["myserver", "app:freshrss", "app:paperless"],
+            ),
+            snapshot(
+                "old",
+                "2026-05-15T03:00:00Z",
+                &["myserver", "app:freshrss", "app:paperless"],
+            ),
+        ];
+        let runs = [
+            PolicyRun {
+                app: Some("freshrss"),
+                groups: groups(&[("new", &["last snapshot"])]),
+            },
+            PolicyRun {
+                app: Some("paperless"),
+                groups: groups(&[("mid", &["last snapshot", "monthly snapshot"])]),
+            },
+        ];
+
+        let decisions = plan(&snapshots, &runs);
+
+        assert_eq!(kept(&decisions), vec!["new", "mid"]);
+        assert_eq!(decisions[0].kept_by, vec!["freshrss: last"]);
+        assert_eq!(
+            decisions[1].kept_by,
+            vec!["paperless: last", "paperless: monthly"]
+        );
+        assert!(!decisions[2].keep());
+    }
+
+    #[test]
+    fn test_global_policy_only_decides_for_untagged_snapshots() {
+        let snapshots = vec![
+            snapshot(
+                "tagged",
+                "2026-07-29T03:00:00Z",
+                &["myserver", "app:baikal"],
+            ),
+            snapshot("legacy", "2026-07-28T03:00:00Z", &["myserver"]),
+            snapshot("b", "2026-07-28T03:00:00Z", &["other"]),
+        ];
+        let runs = [PolicyRun {
+            app: None,
+            groups: groups(&[
+                ("tagged", &["daily snapshot"]),
+                ("legacy", &["daily snapshot"]),
+            ]),
+        }];
+
+        let decisions = plan(&snapshots, &runs);
+
+        assert_eq!(kept(&decisions), vec!["legacy"]);
+        assert_eq!(decisions[1].kept_by, vec!["daily"]);
+    }
+}
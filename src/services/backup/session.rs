@@ -1,13 +1,17 @@
 use crate::output;
 use crate::playbook_meta::BackupRecipe;
 use crate::services::backup::executor::RecipeExecutor;
+use crate::services::backup::repository::Repository;
 use crate::services::backup::restic::{self, ResticMessage, parse_restic_message};
+use crate::services::backup::retention::{self, ForgetGroup, PolicyRun, RetentionPolicy};
+use crate::services::backup::verify::{APP_TAG_PREFIX, Snapshot};
 use crate::services::progress::{Progress, TerminalProgress};
 use crate::services::ssh::SshSession;
 use eyre::{Context, Result};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
+use tabled::Tabled;
 
 #[derive(Debug, Clone)]
 pub struct SessionOpts {
@@ -70,10 +74,24 @@ impl<'a, S: SshSession + ?Sized> BackupSession<'a, S> {
     }
 
     pub fn create(&self) -> Result<CreateOutcome> {
+        let missing_sqlite3 = self.missing_sqlite3()?;
         let executor = RecipeExecutor::new(self.ssh);
         let mut results = Vec::with_capacity(self.recipes.len());
 
         for (app_name, recipe) in &self.recipes {
+            if let Some(reason) = missing_sqlite3
+                .as_ref()
+                .filter(|_| !recipe.sqlite.is_empty())
+            {
+                eprintln!("✗ {} backup failed: {}", app_name, reason);
+                results.push(RecipeOutcome {
+                    app: app_name.clone(),
+                    size_bytes: None,
+                    error: Some(reason.clone()),
+                });
+                continue;
+            }
+
             let app_dir = self
                 .opts
                 .dest
@@ -124,6 +142,21 @@ impl<'a, S: SshSession + ?Sized> BackupSession<'a, S> {
             timestamp: self.opts.timestamp.clone(),
         })
     }
+
+    /// Why the Recipes that take SQLite snapshots cannot run, checked before
+    /// any app is stopped: the server has no `sqlite3`, which the `apt` role
+    /// only installs on its next run.
+    fn missing_sqlite3(&self) -> Result<Option<String>> {
+        let needed = self.recipes.iter().any(|(_, r)| !r.sqlite.is_empty());
+        if !needed || self.ssh.run("command -v sqlite3")?.success {
+            return Ok(None);
+        }
+        Ok(Some(format!(
+            "sqlite3 is not installed on {}; re-run provisioning with \
+             `auberge ansible run --host {}` to install it",
+            self.opts.host_name, self.opts.host_name
+        )))
+    }
 }
 
 #[cfg(not(test))]
@@ -136,45 +169,67 @@ fn make_recipe_progress(app: &str) -> Box<dyn Progress> {
     Box::new(TerminalProgress::hidden(&format!("Backing up {}", app)))
 }
 
-fn backup_args(backup_dir: &Path, host: &str) -> Vec<std::ffi::OsString> {
-    vec![
+/// Tags the snapshot with the Host, which verify and restore find it by,
+/// and with `app:<name>` for each app, which retention is applied by.
+fn backup_args(backup_dir: &Path, host: &str, apps: &[String]) -> Vec<std::ffi::OsString> {
+    let mut args: Vec<std::ffi::OsString> = vec![
         "backup".into(),
         "--json".into(),
         "--tag".into(),
         host.into(),
-        backup_dir.into(),
-    ]
+    ];
+    for app in apps {
+        args.push("--tag".into());
+        args.push(format!("{APP_TAG_PREFIX}{app}").into());
+    }
+    args.push(backup_dir.into());
+    args
 }
 
-fn forget_args(dry_run: bool) -> Vec<&'static str> {
+/// The app directories of a staged backup, sorted.
+fn staged_apps(backup_dir: &Path) -> Result<Vec<String>> {
+    let mut apps: Vec<String> = fs::read_dir(backup_dir)
+        .wrap_err_with(|| format!("Failed to read {}", backup_dir.display()))?
+        .filter_map(Result::ok)
+        .filter(|e| !e.path().is_symlink() && e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+    apps.sort();
+    Ok(apps)
+}
+
+/// `restic forget --dry-run` arguments asking what `policy` keeps: of
+/// `app`'s snapshots, or of every snapshot for the global policy. Grouping
+/// by tags counts each Host's snapshots apart; their paths differ per push.
+fn policy_args(app: Option<&str>, policy: &RetentionPolicy) -> Vec<String> {
     let mut args = vec![
-        "forget",
-        "--group-by",
-        "tags",
-        "--keep-daily",
-        "7",
-        "--keep-weekly",
-        "4",
-        "--keep-monthly",
-        "12",
-        "--prune",
+        "--dry-run".to_string(),
+        "--group-by".to_string(),
+        "tags".to_string(),
     ];
-    if dry_run {
-        args.push("--dry-run");
+    if let Some(app) = app {
+        args.push("--tag".to_string());
+        args.push(format!("{APP_TAG_PREFIX}{app}"));
     }
+    args.extend(policy.keep_args());
+    args
+}
+
+fn forget_args(snapshot_ids: &[&str]) -> Vec<String> {
+    let mut args = vec!["forget".to_string(), "--prune".to_string()];
+    args.extend(snapshot_ids.iter().map(|id| id.to_string()));
     args
 }
 
-pub fn restic_push(
-    restic_repo: &str,
-    restic_password: &str,
-    backup_dir: &Path,
-    host: &str,
-) -> Result<()> {
-    output::info(&format!("Pushing {} to restic", backup_dir.display()));
+pub fn restic_push(repository: &Repository, backup_dir: &Path, host: &str) -> Result<()> {
+    output::info(&format!(
+        "Pushing {} to restic repository '{}'",
+        backup_dir.display(),
+        repository.name
+    ));
 
     let mut progress = TerminalProgress::new("Checking restic repository");
-    let snapshots_check = restic::command(restic_repo, restic_password)
+    let snapshots_check = restic::command(&repository.location, &repository.password)
         .arg("snapshots")
         .arg("--json")
         .output();
@@ -200,7 +255,7 @@ pub fn restic_push(
 
     if needs_init {
         progress.task_started("Initializing restic repository");
-        let init_output = restic::command(restic_repo, restic_password)
+        let init_output = restic::command(&repository.location, &repository.password)
             .arg("init")
             .output()
             .wrap_err("Failed to initialize restic repository")?;
@@ -223,7 +278,11 @@ pub fn restic_push(
 
     let result = output::stream_command_stdout(
         "restic",
-        restic::command(restic_repo, restic_password).args(backup_args(backup_dir, host)),
+        restic::command(&repository.location, &repository.password).args(backup_args(
+            backup_dir,
+            host,
+            &staged_apps(backup_dir)?,
+        )),
         |line| match parse_restic_message(line) {
             Some(ResticMessage::Status(s)) => {
                 if let (Some(total), Some(done)) = (s.total_bytes, s.bytes_done) {
@@ -261,14 +320,116 @@ pub fn restic_push(
     Ok(())
 }
 
-pub fn restic_prune(restic_repo: &str, restic_password: &str, dry_run: bool) -> Result<()> {
-    let mut progress = TerminalProgress::new("Pruning restic snapshots");
+#[derive(Tabled)]
+struct RetentionRow {
+    #[tabled(rename = "HOST")]
+    host: String,
+    #[tabled(rename = "SNAPSHOT")]
+    snapshot: String,
+    #[tabled(rename = "TIME")]
+    time: String,
+    #[tabled(rename = "APPS")]
+    apps: String,
+    #[tabled(rename = "ACTION")]
+    action: &'static str,
+    #[tabled(rename = "KEPT BY")]
+    kept_by: String,
+}
 
-    let mut cmd = restic::command(restic_repo, restic_password);
-    cmd.args(forget_args(dry_run));
+/// Forget and prune every snapshot no rule of the repository's retention
+/// keeps. restic applies each policy with `forget --dry-run`; the snapshots
+/// none of them keeps are then forgotten by id. A dry run prints each
+/// snapshot's fate and the rules keeping it, and deletes nothing.
+pub fn restic_prune(repository: &Repository, dry_run: bool) -> Result<()> {
+    let retention = &repository.retention;
+    let mut progress =
+        TerminalProgress::new(&format!("Pruning restic repository '{}'", repository.name));
+    progress.task_started("Reading snapshots");
+    let snapshots: Vec<Snapshot> = serde_json::from_str(&restic::snapshots_json(
+        &repository.location,
+        &repository.password,
+    )?)
+    .wrap_err("Unreadable restic snapshot list")?;
+    progress.task_done();
+
+    progress.task_started("Applying retention policies");
+    let apps: BTreeSet<&str> = snapshots.iter().flat_map(Snapshot::apps).collect();
+    let untagged = snapshots.iter().any(|snapshot| snapshot.apps().is_empty());
+    let mut runs = Vec::new();
+    for app in untagged
+        .then_some(None)
+        .into_iter()
+        .chain(apps.into_iter().map(Some))
+    {
+        let policy = match app {
+            Some(app) => retention.for_app(app),
+            None => retention.global,
+        };
+        let groups: Option<Vec<ForgetGroup>> = serde_json::from_str(&restic::forget_json(
+            &repository.location,
+            &repository.password,
+            &policy_args(app, &policy),
+        )?)
+        .wrap_err("Unreadable restic forget output")?;
+        runs.push(PolicyRun {
+            app,
+            groups: groups.unwrap_or_default(),
+        });
+    }
+    progress.task_done();
 
-    let prune_output = cmd.output().wrap_err("Failed to run restic forget")?;
+    let decisions = retention::plan(&snapshots, &runs);
+    let dropped: Vec<&str> = decisions
+        .iter()
+        .filter(|decision| !decision.keep())
+        .map(|decision| decision.snapshot.id.as_str())
+        .collect();
 
+    if dry_run {
+        output::info(&format!(
+            "Default retention: {}",
+            retention.global.describe()
+        ));
+        for (app, policy) in &retention.apps {
+            output::info(&format!("{app} retention: {}", policy.describe()));
+        }
+        let rows: Vec<RetentionRow> = decisions
+            .iter()
+            .map(|decision| RetentionRow {
+                host: decision.snapshot.host().unwrap_or("-").to_string(),
+                snapshot: decision.snapshot.short_id().to_string(),
+                time: decision.snapshot.time.format("%Y-%m-%d %H:%M").to_string(),
+                apps: match decision.snapshot.apps() {
+                    apps if apps.is_empty() => "-".to_string(),
+                    apps => apps.join(", "),
+                },
+                action: if decision.keep() { "keep" } else { "drop" },
+                kept_by: decision.kept_by.join(", "),
+            })
+            .collect();
+        output::print_table(&rows);
+        output::info(&format!(
+            "Dry run: would keep {} and drop {} snapshot(s) in '{}' (no changes made)",
+            decisions.len() - dropped.len(),
+            dropped.len(),
+            repository.name
+        ));
+        return Ok(());
+    }
+
+    if dropped.is_empty() {
+        output::success(&format!(
+            "Prune complete for '{}': every snapshot is kept by a retention rule",
+            repository.name
+        ));
+        return Ok(());
+    }
+
+    progress.task_started(&format!("Forgetting {} snapshot(s)", dropped.len()));
+    let prune_output = restic::command(&repository.location, &repository.password)
+        .args(forget_args(&dropped))
+        .output()
+        .wrap_err("Failed to run restic forget")?;
     progress.task_done();
 
     let stderr_text = String::from_utf8_lossy(&prune_output.stderr);
@@ -281,16 +442,12 @@ pub fn restic_prune(restic_repo: &str, restic_password: &str, dry_run: bool) ->
         eyre::bail!("restic prune failed: {}", stderr_text.trim());
     }
 
-    let stdout = String::from_utf8_lossy(&prune_output.stdout);
-    if !stdout.is_empty() {
-        eprintln!("{}", stdout.trim());
-    }
-
-    if dry_run {
-        output::info("Dry run completed (no changes made)");
-    } else {
-        output::success("Prune complete");
-    }
+    output::success(&format!(
+        "Prune complete for '{}': dropped {} of {} snapshot(s)",
+        repository.name,
+        dropped.len(),
+        decisions.len()
+    ));
 
     Ok(())
 }
@@ -330,6 +487,8 @@ mod tests {
             paths: vec!["/opt/baikal/Specific".to_string()],
             owner: Some(("baikal".to_string(), "baikal".to_string())),
             db: None,
+            sqlite: vec![],
+            copy_live: false,
             post_restore_command: None,
             parameters: HashMap::new(),
         }
@@ -341,6 +500,8 @@ mod tests {
             paths: vec!["/opt/bichon/data".to_string()],
             owner: None,
             db: None,
+            sqlite: vec![],
+            copy_live: false,
             post_restore_command: None,
             parameters: HashMap::new(),
         }
@@ -355,6 +516,21 @@ mod tests {
                 name: "paperless".to_string(),
                 dump_path: "/tmp/paperless_db.dump".to_string(),
             }),
+            sqlite: vec![],
+            copy_live: false,
+            post_restore_command: None,
+            parameters: HashMap::new(),
+        }
+    }
+
+    fn actual_recipe() -> BackupRecipe {
+        BackupRecipe {
+            systemd_services: vec!["actual".to_string()],
+            paths: vec!["/var/lib/actual".to_string()],
+            owner: None,
+            db: None,
+            sqlite: vec!["/var/lib/actual/server-files/account.sqlite".to_string()],
+            copy_live: false,
             post_restore_command: None,
             parameters: HashMap::new(),
         }
@@ -370,14 +546,19 @@ mod tests {
     }
 
     #[test]
-    fn backup_args_tags_snapshot_with_host() {
+    fn backup_args_tags_snapshot_with_host_and_apps() {
         let args = backup_args(
             Path::new("/backups/myserver/2026-04-28_03-00-00"),
             "myserver",
+            &["baikal".to_string(), "paperless".to_string()],
         );
 
-        let tag_pos = args.iter().position(|a| a == "--tag").unwrap();
-        assert_eq!(args[tag_pos + 1], "myserver");
+        let tags: Vec<&std::ffi::OsString> = args
+            .windows(2)
+            .filter(|w| w[0] == "--tag")
+            .map(|w| &w[1])
+            .collect();
+        assert_eq!(tags, ["myserver", "app:baikal", "app:paperless"]);
         assert_eq!(
             args.last().unwrap(),
             &std::ffi::OsString::from("/backups/myserver/2026-04-28_03-00-00")
@@ -385,18 +566,43 @@ mod tests {
     }
 
     #[test]
-    fn forget_args_group_by_tags_so_retention_spans_snapshots() {
-        let args = forget_args(false);
+    fn staged_apps_lists_app_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("paperless")).unwrap();
+        fs::create_dir(dir.path().join("baikal")).unwrap();
+        fs::write(dir.path().join("manifest.json"), "{}").unwrap();
 
-        assert!(args.windows(2).any(|w| w == ["--group-by", "tags"]));
-        assert!(args.windows(2).any(|w| w == ["--keep-daily", "7"]));
-        assert!(args.contains(&"--prune"));
-        assert!(!args.contains(&"--dry-run"));
+        assert_eq!(staged_apps(dir.path()).unwrap(), ["baikal", "paperless"]);
     }
 
     #[test]
-    fn forget_args_dry_run_appends_flag() {
-        assert!(forget_args(true).contains(&"--dry-run"));
+    fn policy_args_ask_restic_per_app_grouped_by_tags() {
+        let policy = retention::Retention::default().global;
+        assert_eq!(
+            policy_args(Some("paperless"), &policy),
+            [
+                "--dry-run",
+                "--group-by",
+                "tags",
+                "--tag",
+                "app:paperless",
+                "--keep-daily",
+                "7",
+                "--keep-weekly",
+                "4",
+                "--keep-monthly",
+                "12"
+            ]
+        );
+        assert!(!policy_args(None, &policy).contains(&"--tag".to_string()));
+    }
+
+    #[test]
+    fn forget_args_name_the_dropped_snapshots() {
+        assert_eq!(
+            forget_args(&["abc", "def"]),
+            ["forget", "--prune", "abc", "def"]
+        );
     }
 
     #[test]
@@ -432,6 +638,44 @@ mod tests {
         assert!(baikal_rsync.unwrap() < bichon_stop.unwrap());
     }
 
+    #[test]
+    fn create_fails_only_sqlite_recipes_when_sqlite3_is_missing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mock = MockSshSession::new();
+        mock.stage_run_result(crate::services::ssh::CommandResult {
+            success: false,
+            exit_code: Some(1),
+            stdout: vec![],
+            stderr: vec![],
+        });
+        let recipes = vec![
+            ("actual".to_string(), actual_recipe()),
+            ("baikal".to_string(), baikal_recipe()),
+        ];
+        let session = BackupSession::new(&mock, recipes, opts(tmp.path()));
+
+        let outcome = session.create().unwrap();
+
+        assert_eq!(outcome.successful_apps(), ["baikal"]);
+        let failed = outcome.failed_apps();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, "actual");
+        assert!(failed[0].1.contains("sqlite3 is not installed on myserver"));
+        assert!(failed[0].1.contains("auberge ansible run --host myserver"));
+
+        let calls = mock.calls();
+        assert_eq!(calls[0], SshOp::Run("command -v sqlite3".to_string()));
+        assert!(!calls.iter().any(|c| matches!(
+            c,
+            SshOp::Systemctl { service, .. } if service == "actual"
+        )));
+        assert!(
+            !tmp.path()
+                .join("myserver/2026-04-28_03-00-00/actual")
+                .exists()
+        );
+    }
+
     #[test]
     fn create_creates_per_app_dest_directories() {
         let tmp = tempfile::tempdir().unwrap();
@@ -562,6 +806,8 @@ mod tests {
             paths: vec!["/var/lib/navidrome".to_string()],
             owner: None,
             db: None,
+            sqlite: vec![],
+            copy_live: false,
             post_restore_command: None,
             parameters: params,
         };
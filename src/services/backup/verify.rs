@@ -1,12 +1,24 @@
+use crate::duration;
+use crate::playbook_meta::BackupRecipe;
+use crate::services::backup::executor::DB_DUMP_FILE;
 use chrono::{DateTime, TimeDelta, Utc};
 use eyre::{Result, eyre};
 use serde::Deserialize;
 use std::fmt;
+use std::path::{Path, PathBuf};
 
 pub const CHECK_REACHABLE: &str = "repository_reachable";
 pub const CHECK_SNAPSHOT: &str = "snapshot_exists";
 pub const CHECK_CONTAINS_APP: &str = "contains_app";
 pub const CHECK_FRESH: &str = "fresh";
+pub const CHECK_INTEGRITY: &str = "repository_integrity";
+pub const CHECK_TEST_RESTORE: &str = "test_restore";
+pub const CHECK_RECIPE_PATHS: &str = "recipe_paths";
+pub const CHECK_DB_DUMP: &str = "db_dump";
+
+/// Prefix of the tag `backup push` gives a snapshot for each app it holds,
+/// next to the Host tag: `app:paperless`.
+pub const APP_TAG_PREFIX: &str = "app:";
 
 /// Freshness threshold for the latest snapshot, e.g. `24h`.
 ///
@@ -20,29 +32,12 @@ pub struct MaxAge {
 
 impl MaxAge {
     pub fn parse(input: &str) -> Result<Self> {
-        let label = input.trim();
-        let invalid =
-            || eyre!("Invalid --max-age '{input}': expected <number><s|m|h|d>, for example 24h");
-
-        let mut chars = label.chars();
-        let unit = chars.next_back().ok_or_else(invalid)?;
-        let value: i64 = chars.as_str().parse().map_err(|_| invalid())?;
-        if value < 0 {
-            return Err(invalid());
-        }
-
-        let duration = match unit {
-            's' => TimeDelta::try_seconds(value),
-            'm' => TimeDelta::try_minutes(value),
-            'h' => TimeDelta::try_hours(value),
-            'd' => TimeDelta::try_days(value),
-            _ => return Err(invalid()),
-        }
-        .ok_or_else(invalid)?;
-
+        let duration = duration::parse(input).ok_or_else(|| {
+            eyre!("Invalid --max-age '{input}': expected <number><s|m|h|d>, for example 24h")
+        })?;
         Ok(Self {
             duration,
-            label: label.to_string(),
+            label: input.trim().to_string(),
         })
     }
 
@@ -64,7 +59,8 @@ impl fmt::Display for MaxAge {
 /// One entry of `restic snapshots --json`.
 ///
 /// `tags` is absent from the JSON for snapshots pushed before `backup push`
-/// started tagging, hence the default.
+/// started tagging, hence the default. Snapshots pushed before app tags carry
+/// only the Host tag.
 #[derive(Debug, Clone, Deserialize)]
 pub struct Snapshot {
     pub id: String,
@@ -79,9 +75,28 @@ impl Snapshot {
     pub fn short_id(&self) -> &str {
         self.id.get(..8).unwrap_or(&self.id)
     }
+
+    /// The Host the snapshot belongs to: its Host tag, else the Host segment
+    /// of its path.
+    pub fn host(&self) -> Option<&str> {
+        self.tags
+            .iter()
+            .map(String::as_str)
+            .find(|tag| !tag.starts_with(APP_TAG_PREFIX))
+            .or_else(|| self.paths.iter().find_map(|path| host_from_path(path)))
+    }
+
+    /// The apps its `app:` tags name; empty for snapshots pushed before them.
+    pub fn apps(&self) -> Vec<&str> {
+        self.tags
+            .iter()
+            .filter_map(|tag| tag.strip_prefix(APP_TAG_PREFIX))
+            .collect()
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Ordered by severity, mildest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Status {
     Verified,
     CheckFailed,
@@ -97,6 +112,11 @@ impl Status {
         }
     }
 
+    /// The most severe of `statuses`; `Verified` when there are none.
+    pub fn worst(statuses: impl IntoIterator<Item = Status>) -> Status {
+        statuses.into_iter().max().unwrap_or(Self::Verified)
+    }
+
     pub fn as_str(self) -> &'static str {
         match self {
             Self::Verified => "verified",
@@ -141,6 +161,8 @@ pub struct SnapshotSummary {
     pub short_id: String,
     pub time: DateTime<Utc>,
     pub age_seconds: i64,
+    /// The app's directory inside the snapshot, when the verdict checked one.
+    pub app_path: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -163,6 +185,12 @@ impl Verdict {
         self.status == Status::Verified
     }
 
+    fn fail(mut self, status: Status, check: Check) -> Self {
+        self.checks.push(check);
+        self.status = status;
+        self
+    }
+
     /// The snapshot list could not be read, so no later check ran.
     pub fn unreachable(reason: &str) -> Self {
         Self::new(
@@ -170,7 +198,7 @@ impl Verdict {
             vec![Check::failed(
                 CHECK_REACHABLE,
                 format!("repository reachable: {}", one_line(reason)),
-                "check restic is installed and restic_repository / restic_password are right (auberge config list)"
+                "check restic is installed and the repository location and password are right (auberge config list)"
                     .to_string(),
             )],
             None,
@@ -237,6 +265,10 @@ pub fn verdict(
         short_id: snapshot.short_id().to_string(),
         time: snapshot.time,
         age_seconds: age.num_seconds(),
+        app_path: match &held {
+            Ok(Some(held)) => Some(held.app_path.clone()),
+            _ => None,
+        },
     });
 
     // Says what the snapshot was selected for: with `--app` it may be older than
@@ -297,6 +329,287 @@ pub fn verdict(
     Verdict::new(Status::Verified, checks, summary)
 }
 
+/// How a deep-verification step went when it could run at all; an `Err`
+/// from a [`DeepProbe`] means it could not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeOutcome {
+    Passed,
+    Failed(String),
+}
+
+/// How a test restore went when restic could run at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestoreOutcome {
+    /// The restored app directory.
+    Restored(PathBuf),
+    Failed(String),
+}
+
+/// The restic and PostgreSQL calls `backup verify --deep` makes, so the
+/// checklist logic runs without either.
+pub trait DeepProbe {
+    /// `restic check --read-data-subset`, over the whole repository.
+    fn check_repository(&mut self) -> Result<ProbeOutcome>;
+
+    /// Restore `app_path` of `snapshot_id` into scratch space. An `Err`
+    /// means the restore could not run: no scratch space, or restic unable
+    /// to reach the repository.
+    fn restore_app(&mut self, snapshot_id: &str, app_path: &str) -> Result<RestoreOutcome>;
+
+    /// `pg_restore --list` over a restored database dump.
+    fn list_dump(&mut self, dump: &Path) -> Result<ProbeOutcome>;
+}
+
+/// Extend a verified app verdict with the deep checks, fail-fast like the
+/// rest: the repository's data reads back, the app's snapshot restores, every
+/// Recipe path is in it and non-empty, and its database dump is readable.
+///
+/// Anything short of a verified verdict is returned as it is — there is no
+/// snapshot worth restoring.
+pub fn deepen(
+    mut verdict: Verdict,
+    host: &str,
+    app: &str,
+    recipe: &BackupRecipe,
+    probe: &mut impl DeepProbe,
+) -> Verdict {
+    let Some((snapshot_id, app_path)) = verdict
+        .snapshot
+        .as_ref()
+        .and_then(|snapshot| Some((snapshot.id.clone(), snapshot.app_path.clone()?)))
+    else {
+        return verdict;
+    };
+    if !verdict.is_verified() {
+        return verdict;
+    }
+    let resync = format!("run: auberge backup sync --host {host} --apps {app}");
+
+    match probe.check_repository() {
+        Ok(ProbeOutcome::Passed) => verdict.checks.push(Check::passed(
+            CHECK_INTEGRITY,
+            "repository data reads back".to_string(),
+        )),
+        Ok(ProbeOutcome::Failed(reason)) => {
+            return verdict.fail(
+                Status::CheckFailed,
+                Check::failed(
+                    CHECK_INTEGRITY,
+                    format!("repository data reads back: {}", one_line(&reason)),
+                    "run: restic check --read-data, then push a fresh snapshot".to_string(),
+                ),
+            );
+        }
+        Err(e) => {
+            return verdict.fail(
+                Status::OperationalError,
+                Check::failed(
+                    CHECK_INTEGRITY,
+                    format!(
+                        "repository data reads back: {}",
+                        one_line(&format!("{e:#}"))
+                    ),
+                    "check restic is installed and the repository is not locked".to_string(),
+                ),
+            );
+        }
+    }
+
+    let restored = match probe.restore_app(&snapshot_id, &app_path) {
+        Ok(RestoreOutcome::Restored(restored)) => restored,
+        Ok(RestoreOutcome::Failed(reason)) => {
+            return verdict.fail(
+                Status::CheckFailed,
+                Check::failed(
+                    CHECK_TEST_RESTORE,
+                    format!("restores {app}: {}", one_line(&reason)),
+                    resync,
+                ),
+            );
+        }
+        Err(e) => {
+            return verdict.fail(
+                Status::OperationalError,
+                Check::failed(
+                    CHECK_TEST_RESTORE,
+                    format!("restores {app}: {}", one_line(&format!("{e:#}"))),
+                    "check the repository is reachable and not locked, and the scratch disk has room"
+                        .to_string(),
+                ),
+            );
+        }
+    };
+    verdict
+        .checks
+        .push(Check::passed(CHECK_TEST_RESTORE, format!("restores {app}")));
+
+    let unusable = unusable_recipe_paths(recipe, &restored);
+    if !unusable.is_empty() {
+        return verdict.fail(
+            Status::CheckFailed,
+            Check::failed(
+                CHECK_RECIPE_PATHS,
+                format!(
+                    "recipe paths present: missing or empty {}",
+                    unusable.join(", ")
+                ),
+                resync,
+            ),
+        );
+    }
+    verdict.checks.push(Check::passed(
+        CHECK_RECIPE_PATHS,
+        format!("recipe paths present ({})", recipe.paths.len()),
+    ));
+
+    if recipe.db.is_none() {
+        return verdict;
+    }
+    let dump = restored.join(DB_DUMP_FILE);
+    let outcome = match dump.is_file() {
+        true => probe.list_dump(&dump),
+        false => Ok(ProbeOutcome::Failed(format!("no {DB_DUMP_FILE}"))),
+    };
+    match outcome {
+        Ok(ProbeOutcome::Passed) => verdict.checks.push(Check::passed(
+            CHECK_DB_DUMP,
+            format!("{DB_DUMP_FILE} lists with pg_restore"),
+        )),
+        Ok(ProbeOutcome::Failed(reason)) => {
+            return verdict.fail(
+                Status::CheckFailed,
+                Check::failed(
+                    CHECK_DB_DUMP,
+                    format!(
+                        "{DB_DUMP_FILE} lists with pg_restore: {}",
+                        one_line(&reason)
+                    ),
+                    resync,
+                ),
+            );
+        }
+        Err(e) => {
+            return verdict.fail(
+                Status::OperationalError,
+                Check::failed(
+                    CHECK_DB_DUMP,
+                    format!(
+                        "{DB_DUMP_FILE} lists with pg_restore: {}",
+                        one_line(&format!("{e:#}"))
+                    ),
+                    "install the PostgreSQL client tools (pg_restore)".to_string(),
+                ),
+            );
+        }
+    }
+    verdict
+}
+
+/// The Recipe's paths a restored app directory lacks, or holds only as an
+/// empty file or directory. Only `paths` is checked: a parameter's
+/// `adds_paths` are in the backup only when it was taken with the parameter
+/// on, so they are never looked for.
+fn unusable_recipe_paths(recipe: &BackupRecipe, restored: &Path) -> Vec<String> {
+    recipe
+        .paths
+        .iter()
+        .filter(|path| {
+            let copy = restored.join(path.trim_start_matches('/'));
+            match std::fs::metadata(&copy) {
+                Ok(meta) if meta.is_dir() => std::fs::read_dir(&copy)
+                    .map(|mut entries| entries.next().is_none())
+                    .unwrap_or(true),
+                Ok(meta) => meta.len() == 0,
+                Err(_) => true,
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+/// The snapshot `backup restore --from-restic` restores from, and the staged
+/// backup directory inside it that holds one subdirectory per app.
+pub struct RestoreSource<'a> {
+    pub snapshot: &'a Snapshot,
+    pub root: &'a str,
+}
+
+/// Pick `host`'s snapshot to restore `apps` from.
+///
+/// `latest` is the newest snapshot holding every app in `apps`, for the same
+/// reason verify walks back: a partial sync leaves newer snapshots without
+/// them. Anything else is a snapshot id or a unique prefix of one, which must
+/// belong to `host`; its apps are not probed, a missing one is skipped later.
+pub fn restore_source<'a>(
+    snapshots: &'a [Snapshot],
+    host: &str,
+    snapshot_id: &str,
+    apps: &[String],
+    mut contains_app: impl FnMut(&Snapshot, &str) -> Result<bool>,
+) -> Result<RestoreSource<'a>> {
+    if snapshot_id != "latest" {
+        let matching: Vec<&Snapshot> = snapshots
+            .iter()
+            .filter(|snapshot| snapshot.id.starts_with(snapshot_id))
+            .collect();
+        let snapshot = match matching.as_slice() {
+            [] => {
+                return Err(eyre!(
+                    "No snapshot '{snapshot_id}' in the restic repository"
+                ));
+            }
+            [only] => *only,
+            _ => {
+                return Err(eyre!(
+                    "Snapshot id '{snapshot_id}' is ambiguous; give more of it"
+                ));
+            }
+        };
+        let held = host_snapshot(snapshot, host).ok_or_else(|| {
+            eyre!(
+                "Snapshot {} is not a backup of {host}; pass --from-host to restore another host's data",
+                snapshot.short_id()
+            )
+        })?;
+        return Ok(RestoreSource {
+            snapshot: held.snapshot,
+            root: held.root,
+        });
+    }
+
+    let candidates = host_snapshots_newest_first(snapshots, host);
+    if candidates.is_empty() {
+        let hosts = snapshot_hosts(snapshots);
+        return Err(match hosts.is_empty() {
+            true => eyre!("No snapshot for {host}: the repository holds no auberge backups"),
+            false => eyre!(
+                "No snapshot for {host}: the repository holds snapshots for {}",
+                hosts.join(", ")
+            ),
+        });
+    }
+    for candidate in &candidates {
+        let root = candidate.root.trim_end_matches('/');
+        let mut holds_all = true;
+        for app in apps {
+            if !contains_app(candidate.snapshot, &format!("{root}/{app}"))? {
+                holds_all = false;
+                break;
+            }
+        }
+        if holds_all {
+            return Ok(RestoreSource {
+                snapshot: candidate.snapshot,
+                root: candidate.root,
+            });
+        }
+    }
+    Err(eyre!(
+        "No snapshot for {host} holds all of {}; restore them one at a time or name a snapshot",
+        apps.join(", ")
+    ))
+}
+
 struct HostSnapshot<'a> {
     snapshot: &'a Snapshot,
     root: &'a str,
@@ -342,7 +655,7 @@ fn newest_holding_app<'a>(
 
 /// A snapshot belongs to a Host if `backup push` tagged it with the Host name,
 /// or — for snapshots pushed before tagging landed — if its path carries the
-/// Host segment. The same tag is what `backup prune` groups retention by.
+/// Host segment. `backup prune` applies retention per Host by the same rule.
 fn host_snapshot<'a>(snapshot: &'a Snapshot, host: &str) -> Option<HostSnapshot<'a>> {
     let by_path = snapshot
         .paths
@@ -371,6 +684,20 @@ fn host_from_path(path: &str) -> Option<&str> {
 /// Names the hosts the repository does hold snapshots for, by the same rule
 /// membership uses, so the hint never contradicts the check above it.
 fn missing_snapshot_remediation(snapshots: &[Snapshot], host: &str) -> String {
+    let hosts = snapshot_hosts(snapshots);
+    match hosts.is_empty() {
+        true => {
+            format!("repository holds no auberge backups — run: auberge backup sync --host {host}")
+        }
+        false => format!(
+            "repository holds snapshots for {} — run: auberge backup sync --host {host}",
+            hosts.join(", ")
+        ),
+    }
+}
+
+/// Every Host the repository holds snapshots for, sorted.
+fn snapshot_hosts(snapshots: &[Snapshot]) -> Vec<&str> {
     let mut hosts: Vec<&str> = snapshots
         .iter()
         .flat_map(|snapshot| {
@@ -378,21 +705,18 @@ fn missing_snapshot_remediation(snapshots: &[Snapshot], host: &str) -> String {
                 .paths
                 .iter()
                 .filter_map(|path| host_from_path(path))
-                .chain(snapshot.tags.iter().map(String::as_str))
+                .chain(
+                    snapshot
+                        .tags
+                        .iter()
+                        .map(String::as_str)
+                        .filter(|tag| !tag.starts_with(APP_TAG_PREFIX)),
+                )
         })
         .collect();
     hosts.sort_unstable();
     hosts.dedup();
-
-    match hosts.is_empty() {
-        true => {
-            format!("repository holds no auberge backups — run: auberge backup sync --host {host}")
-        }
-        false => format!(
-            "repository holds snapshots for {} — run: auberge backup sync --host {host}",
-            hosts.join(", ")
-        ),
-    }
+    hosts
 }
 
 fn format_age(age: TimeDelta) -> String {
@@ -516,43 +840,20 @@ mod tests {
         verdict.checks.iter().find(|c| c.name == name)
     }
 
-    #[test]
-    fn max_age_parses_every_unit() {
-        assert_eq!(max_age("45s").duration(), TimeDelta::seconds(45));
-        assert_eq!(max_age("90m").duration(), TimeDelta::minutes(90));
-        assert_eq!(max_age("24h").duration(), TimeDelta::hours(24));
-        assert_eq!(max_age("2d").duration(), TimeDelta::days(2));
-    }
-
     #[test]
     fn max_age_keeps_the_operators_spelling() {
+        assert_eq!(max_age("90m").duration(), TimeDelta::minutes(90));
         assert_eq!(max_age("90m").label(), "90m");
         assert_eq!(max_age(" 24h ").to_string(), "24h");
     }
 
-    #[test]
-    fn max_age_zero_is_allowed() {
-        assert_eq!(max_age("0h").duration(), TimeDelta::zero());
-    }
-
     #[test]
     fn max_age_rejects_malformed_input() {
-        for input in ["", "24", "h", "abc", "-1h", "24x", "1.5h", "24 h"] {
-            assert!(
-                MaxAge::parse(input).is_err(),
-                "expected '{input}' to be rejected"
-            );
-        }
-    }
-
-    #[test]
-    fn max_age_rejects_multibyte_unit_without_panicking() {
-        assert!(MaxAge::parse("24é").is_err());
-    }
-
-    #[test]
-    fn max_age_rejects_overflowing_value() {
-        assert!(MaxAge::parse("9999999999999999999d").is_err());
+        let err = MaxAge::parse("24x").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid --max-age '24x': expected <number><s|m|h|d>, for example 24h"
+        );
     }
 
     #[test]
@@ -1130,4 +1431,346 @@ mod tests {
         assert_eq!(Status::CheckFailed.as_str(), "check_failed");
         assert_eq!(Status::OperationalError.as_str(), "operational_error");
     }
+
+    #[test]
+    fn status_worst_is_the_most_severe() {
+        assert_eq!(Status::worst([]), Status::Verified);
+        assert_eq!(
+            Status::worst([
+                Status::Verified,
+                Status::OperationalError,
+                Status::CheckFailed
+            ]),
+            Status::OperationalError
+        );
+    }
+
+    /// Restores into `dir` whatever `files` lists, relative to the app path.
+    struct FakeProbe {
+        dir: tempfile::TempDir,
+        files: Vec<(&'static str, &'static str)>,
+        integrity: ProbeOutcome,
+        restore: Result<(), Result<&'static str, &'static str>>,
+        dump: Result<ProbeOutcome, &'static str>,
+        restored: Vec<String>,
+    }
+
+    impl FakeProbe {
+        fn new(files: Vec<(&'static str, &'static str)>) -> Self {
+            Self {
+                dir: tempfile::tempdir().unwrap(),
+                files,
+                integrity: ProbeOutcome::Passed,
+                restore: Ok(()),
+                dump: Ok(ProbeOutcome::Passed),
+                restored: Vec::new(),
+            }
+        }
+    }
+
+    impl DeepProbe for FakeProbe {
+        fn check_repository(&mut self) -> Result<ProbeOutcome> {
+            Ok(self.integrity.clone())
+        }
+
+        fn restore_app(&mut self, snapshot_id: &str, app_path: &str) -> Result<RestoreOutcome> {
+            self.restored.push(format!("{snapshot_id} {app_path}"));
+            match self.restore {
+                Ok(()) => {}
+                Err(Ok(reason)) => return Ok(RestoreOutcome::Failed(reason.to_string())),
+                Err(Err(e)) => return Err(eyre!(e)),
+            }
+            let restored = self.dir.path().join("app");
+            for (file, contents) in &self.files {
+                let path = restored.join(file);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(path, contents).unwrap();
+            }
+            Ok(RestoreOutcome::Restored(restored))
+        }
+
+        fn list_dump(&mut self, _dump: &Path) -> Result<ProbeOutcome> {
+            self.dump.clone().map_err(|e| eyre!(e))
+        }
+    }
+
+    fn paperless_recipe() -> BackupRecipe {
+        BackupRecipe {
+            systemd_services: vec![],
+            paths: vec![
+                "/opt/paperless/data".to_string(),
+                "/opt/paperless/media".to_string(),
+            ],
+            owner: None,
+            db: Some(crate::playbook_meta::DbRecipe {
+                name: "paperless".to_string(),
+                dump_path: "/tmp/paperless_db.dump".to_string(),
+            }),
+            sqlite: vec![],
+            copy_live: false,
+            post_restore_command: None,
+            parameters: Default::default(),
+        }
+    }
+
+    fn verified_app_verdict() -> Verdict {
+        let age = max_age("24h");
+        verdict(
+            &request("myserver", Some("paperless"), &age),
+            &myserver_snapshots(),
+            |_, _| Ok(true),
+        )
+    }
+
+    #[test]
+    fn deepen_adds_a_row_per_deep_check() {
+        let mut probe = FakeProbe::new(vec![
+            ("opt/paperless/data/index.db", "x"),
+            ("opt/paperless/media/doc.pdf", "%PDF"),
+            ("db.dump", "PGDMP"),
+        ]);
+        let verdict = deepen(
+            verified_app_verdict(),
+            "myserver",
+            "paperless",
+            &paperless_recipe(),
+            &mut probe,
+        );
+
+        assert_eq!(verdict.status, Status::Verified);
+        let names: Vec<&str> = verdict.checks.iter().map(|c| c.name).collect();
+        assert_eq!(
+            names[names.len() - 4..],
+            [
+                CHECK_INTEGRITY,
+                CHECK_TEST_RESTORE,
+                CHECK_RECIPE_PATHS,
+                CHECK_DB_DUMP
+            ]
+        );
+        assert_eq!(
+            probe.restored,
+            [format!(
+                "a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c1d2e3f4a5b6c7d8e9f0a1b2 {ROOT}/myserver/2026-07-29_03-00-00/paperless"
+            )]
+        );
+    }
+
+    #[test]
+    fn deepen_fails_on_an_empty_recipe_path() {
+        let mut probe = FakeProbe::new(vec![
+            ("opt/paperless/data/index.db", ""),
+            ("opt/paperless/media/doc.pdf", "%PDF"),
+            ("db.dump", "PGDMP"),
+        ]);
+        std::fs::create_dir_all(probe.dir.path().join("app/opt/paperless/data")).unwrap();
+        let mut recipe = paperless_recipe();
+        recipe
+            .paths
+            .push("/opt/paperless/data/index.db".to_string());
+        let verdict = deepen(
+            verified_app_verdict(),
+            "myserver",
+            "paperless",
+            &recipe,
+            &mut probe,
+        );
+
+        assert_eq!(verdict.status, Status::CheckFailed);
+        let failed = check(&verdict, CHECK_RECIPE_PATHS).unwrap();
+        assert_eq!(
+            failed.message,
+            "recipe paths present: missing or empty /opt/paperless/data/index.db"
+        );
+        assert_eq!(
+            failed.remediation.as_deref(),
+            Some("run: auberge backup sync --host myserver --apps paperless")
+        );
+        assert!(check(&verdict, CHECK_DB_DUMP).is_none());
+    }
+
+    #[test]
+    fn deepen_reports_damaged_repository_data_as_a_failed_check() {
+        let mut probe = FakeProbe::new(vec![]);
+        probe.integrity = ProbeOutcome::Failed("pack 4f2a: hash mismatch\n".to_string());
+        let verdict = deepen(
+            verified_app_verdict(),
+            "myserver",
+            "paperless",
+            &paperless_recipe(),
+            &mut probe,
+        );
+
+        assert_eq!(verdict.status, Status::CheckFailed);
+        assert_eq!(
+            verdict.checks.last().unwrap().message,
+            "repository data reads back: pack 4f2a: hash mismatch"
+        );
+        assert!(probe.restored.is_empty());
+    }
+
+    #[test]
+    fn deepen_tells_a_snapshot_that_does_not_restore_from_a_restore_that_could_not_run() {
+        let deepened = |restore| {
+            let mut probe = FakeProbe::new(vec![]);
+            probe.restore = Err(restore);
+            deepen(
+                verified_app_verdict(),
+                "myserver",
+                "paperless",
+                &paperless_recipe(),
+                &mut probe,
+            )
+        };
+
+        let damaged = deepened(Ok("restic restore failed: ciphertext verification failed"));
+        assert_eq!(damaged.status, Status::CheckFailed);
+        assert_eq!(
+            check(&damaged, CHECK_TEST_RESTORE)
+                .unwrap()
+                .remediation
+                .as_deref(),
+            Some("run: auberge backup sync --host myserver --apps paperless")
+        );
+
+        let locked = deepened(Err("restic restore failed: repository is already locked"));
+        assert_eq!(locked.status, Status::OperationalError);
+        assert!(!check(&locked, CHECK_TEST_RESTORE).unwrap().passed);
+    }
+
+    #[test]
+    fn deepen_without_pg_restore_is_an_operational_error() {
+        let mut probe = FakeProbe::new(vec![
+            ("opt/paperless/data/index.db", "x"),
+            ("opt/paperless/media/doc.pdf", "%PDF"),
+            ("db.dump", "PGDMP"),
+        ]);
+        probe.dump = Err("No such file or directory");
+        let verdict = deepen(
+            verified_app_verdict(),
+            "myserver",
+            "paperless",
+            &paperless_recipe(),
+            &mut probe,
+        );
+
+        assert_eq!(verdict.status, Status::OperationalError);
+        assert!(!check(&verdict, CHECK_DB_DUMP).unwrap().passed);
+    }
+
+    #[test]
+    fn deepen_leaves_a_failed_verdict_alone() {
+        let age = max_age("1s");
+        let stale = verdict(
+            &request("myserver", Some("paperless"), &age),
+            &myserver_snapshots(),
+            |_, _| Ok(true),
+        );
+        let checks = stale.checks.len();
+        let mut probe = FakeProbe::new(vec![]);
+        let verdict = deepen(
+            stale,
+            "myserver",
+            "paperless",
+            &paperless_recipe(),
+            &mut probe,
+        );
+
+        assert_eq!(verdict.status, Status::CheckFailed);
+        assert_eq!(verdict.checks.len(), checks);
+        assert!(probe.restored.is_empty());
+    }
+
+    fn apps(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn restore_source_latest_walks_back_to_a_snapshot_holding_the_apps() {
+        let snapshots: Vec<Snapshot> = serde_json::from_str(&partial_sync_snapshots()).unwrap();
+        let probed = RefCell::new(Vec::new());
+
+        let source = restore_source(
+            &snapshots,
+            "myserver",
+            "latest",
+            &apps(&["bichon"]),
+            bichon_in_full_sync(&probed),
+        )
+        .unwrap();
+
+        assert_eq!(source.snapshot.short_id(), "full1111");
+        assert_eq!(source.root, format!("{ROOT}/myserver/2026-07-29_00-00-00"));
+        assert_eq!(*probed.borrow(), vec!["part2222", "full1111"]);
+    }
+
+    #[test]
+    fn restore_source_latest_without_apps_is_the_newest_push() {
+        let snapshots: Vec<Snapshot> = serde_json::from_str(&partial_sync_snapshots()).unwrap();
+        let source = restore_source(&snapshots, "myserver", "latest", &[], |_, _| {
+            panic!("no app to probe")
+        })
+        .unwrap();
+        assert_eq!(source.snapshot.short_id(), "part2222");
+    }
+
+    #[test]
+    fn restore_source_latest_fails_when_no_snapshot_holds_every_app() {
+        let snapshots: Vec<Snapshot> = serde_json::from_str(&partial_sync_snapshots()).unwrap();
+        let probed = RefCell::new(Vec::new());
+        let err = restore_source(
+            &snapshots,
+            "myserver",
+            "latest",
+            &apps(&["bichon", "paperless"]),
+            bichon_in_full_sync(&probed),
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("holds all of bichon, paperless"));
+    }
+
+    #[test]
+    fn restore_source_takes_an_id_prefix_of_the_hosts_snapshot() {
+        let snapshots: Vec<Snapshot> = serde_json::from_str(&partial_sync_snapshots()).unwrap();
+        let source = restore_source(
+            &snapshots,
+            "myserver",
+            "full11",
+            &apps(&["bichon"]),
+            |_, _| panic!("a named snapshot is not probed"),
+        )
+        .unwrap();
+        assert_eq!(source.snapshot.short_id(), "full1111");
+    }
+
+    #[test]
+    fn restore_source_rejects_unknown_and_foreign_snapshots() {
+        let snapshots: Vec<Snapshot> = serde_json::from_str(&myserver_snapshots()).unwrap();
+        let no_probe = |_: &Snapshot, _: &str| -> Result<bool> { panic!("not probed") };
+
+        let err = restore_source(&snapshots, "myserver", "ffff", &[], no_probe)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "No snapshot 'ffff' in the restic repository"
+        );
+
+        let err = restore_source(&snapshots, "otherhost", "a1b2", &[], no_probe)
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string()
+                .starts_with("Snapshot a1b2c3d4 is not a backup of otherhost")
+        );
+
+        let err = restore_source(&snapshots, "otherhost", "latest", &[], no_probe)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "No snapshot for otherhost: the repository holds snapshots for myserver"
+        );
+    }
 }
@@ -0,0 +1,630 @@
+use crate::output;
+use crate::profile::Profile;
+use crate::services::dependency_resolver::PlaybookRun;
+use chrono::{DateTime, Utc};
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const JOURNAL_DIR: &str = "deploys";
+const ID_TIMESTAMP: &str = "%Y-%m-%d_%H-%M-%S";
+
+/// Which command produced a record: `auberge deploy`, `auberge deploy
+/// rollback`, `auberge ansible run` or `auberge config rotate`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeploySource {
+    Deploy,
+    Rollback,
+    AnsibleRun,
+    Rotate,
+}
+
+impl DeploySource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Deploy => "deploy",
+            Self::Rollback => "deploy rollback",
+            Self::AnsibleRun => "ansible run",
+            Self::Rotate => "config rotate",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRun {
+    pub playbook: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl From<&PlaybookRun> for RecordedRun {
+    fn from(run: &PlaybookRun) -> Self {
+        Self {
+            playbook: playbook_stem(&run.path),
+            tags: run.tags.clone(),
+        }
+    }
+}
+
+/// The apps a set of runs deploys: the tags of its apps.yml runs.
+pub fn apps_in(runs: &[PlaybookRun]) -> Vec<String> {
+    runs.iter()
+        .filter(|run| run.is_apps())
+        .flat_map(|run| run.tags.iter().cloned())
+        .collect()
+}
+
+pub fn playbook_stem(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// What a run is about to do, captured before the first playbook starts so a
+/// failed run records the plan it failed against, not just what it reached.
+pub struct DeployEntry {
+    pub source: DeploySource,
+    pub host: String,
+    pub apps: Vec<String>,
+    pub runs: Vec<RecordedRun>,
+    pub app_versions: Vec<(String, String)>,
+    pub memory_budgets: Vec<(String, String)>,
+    pub check: bool,
+    pub pre_upgrade_backup: Option<PreUpgradeBackup>,
+}
+
+/// The staged backup a deploy took before changing App Versions: its ID as
+/// `backup restore` takes it, and the apps it holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreUpgradeBackup {
+    pub id: String,
+    pub apps: Vec<String>,
+}
+
+/// One journaled deploy or `ansible run` against one Host.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeployRecord {
+    pub id: String,
+    pub source: DeploySource,
+    pub host: String,
+    #[serde(default)]
+    pub apps: Vec<String>,
+    #[serde(default)]
+    pub runs: Vec<RecordedRun>,
+    #[serde(default)]
+    pub app_versions: BTreeMap<String, String>,
+    #[serde(default)]
+    pub memory_budgets: BTreeMap<String, String>,
+    #[serde(default)]
+    pub check: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_upgrade_backup: Option<PreUpgradeBackup>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+}
+
+impl DeployRecord {
+    fn new(
+        entry: DeployEntry,
+        started_at: DateTime<Utc>,
+        finished_at: DateTime<Utc>,
+        outcome: &Result<()>,
+    ) -> Self {
+        Self {
+            id: format!("{}_{}", started_at.format(ID_TIMESTAMP), entry.host),
+            source: entry.source,
+            host: entry.host,
+            apps: entry.apps,
+            runs: entry.runs,
+            app_versions: entry.app_versions.into_iter().collect(),
+            memory_budgets: entry.memory_budgets.into_iter().collect(),
+            check: entry.check,
+            pre_upgrade_backup: entry.pre_upgrade_backup,
+            started_at,
+            finished_at,
+            success: outcome.is_ok(),
+            failure: outcome.as_ref().err().map(|e| format!("{e:#}")),
+        }
+    }
+
+    pub fn duration_secs(&self) -> u64 {
+        u64::try_from((self.finished_at - self.started_at).num_seconds()).unwrap_or(0)
+    }
+}
+
+/// What `auberge deploy rollback` returns an App to.
+#[derive(Debug, PartialEq)]
+pub struct RollbackTarget<'a> {
+    /// The App Version to redeploy.
+    pub version: &'a str,
+    /// The successful deploy that last ran `version`.
+    pub deployed: &'a DeployRecord,
+    /// The first later deploy with another version: the upgrade being rolled
+    /// back. `None` when the journal never saw it.
+    pub upgrade: Option<&'a DeployRecord>,
+}
+
+impl RollbackTarget<'_> {
+    /// The ID of the backup the upgrade took of `app` before it ran.
+    pub fn pre_upgrade_backup(&self, app: &str) -> Option<&str> {
+        self.upgrade
+            .and_then(|r| r.pre_upgrade_backup.as_ref())
+            .filter(|b| b.apps.iter().any(|a| a == app))
+            .map(|b| b.id.as_str())
+    }
+}
+
+/// The App Version `record` injected for `app` on `host`, if it was a real
+/// (non-check) deploy of that app there.
+fn deployed_version<'a>(
+    record: &'a DeployRecord,
+    host: &str,
+    app: &str,
+    version_var: &str,
+) -> Option<&'a str> {
+    if record.host != host || record.check || !record.apps.iter().any(|a| a == app) {
+        return None;
+    }
+    record.app_versions.get(version_var).map(String::as_str)
+}
+
+/// The App Version the newest successful deploy of `app` to `host` ran:
+/// what the journal believes the Host runs now. `records` must be oldest
+/// first, as [`DeployJournal::records`] returns them.
+pub fn last_deployed_version<'a>(
+    records: &'a [DeployRecord],
+    host: &str,
+    app: &str,
+    version_var: &str,
+) -> Option<&'a str> {
+    records
+        .iter()
+        .rev()
+        .filter(|r| r.success)
+        .find_map(|r| deployed_version(r, host, app, version_var))
+}
+
+/// Find the App Version `app` ran before `current` on `host`: the newest
+/// successful, non-check deploy whose injected `version_var` differs from
+/// `current`. Not simply the last successful deploy — when the upgrade itself
+/// deployed cleanly and broke the App at run time, that deploy is the one
+/// being rolled back. `records` must be oldest first.
+pub fn rollback_target<'a>(
+    records: &'a [DeployRecord],
+    host: &str,
+    app: &str,
+    version_var: &str,
+    current: &str,
+) -> Option<RollbackTarget<'a>> {
+    let version_of = |r: &'a DeployRecord| deployed_version(r, host, app, version_var);
+
+    let index = records
+        .iter()
+        .rposition(|r| r.success && version_of(r).is_some_and(|v| v != current))?;
+    let deployed = &records[index];
+    let version = version_of(deployed)?;
+    let upgrade = records[index + 1..]
+        .iter()
+        .find(|r| version_of(r).is_some_and(|v| v != version));
+
+    Some(RollbackTarget {
+        version,
+        deployed,
+        upgrade,
+    })
+}
+
+/// Narrows `auberge deploy history`; every field left unset matches all.
+#[derive(Debug, Default)]
+pub struct HistoryFilter {
+    pub host: Option<String>,
+    pub app: Option<String>,
+    pub failed_only: bool,
+}
+
+impl HistoryFilter {
+    fn matches(&self, record: &DeployRecord) -> bool {
+        self.host.as_ref().is_none_or(|h| &record.host == h)
+            && self.app.as_ref().is_none_or(|a| record.apps.contains(a))
+            && (!self.failed_only || !record.success)
+    }
+}
+
+/// The deploy journal: one JSON file per record under
+/// `<data_dir>/deploys/`, named by its id so a directory listing sorts
+/// chronologically and a record never needs rewriting once written.
+pub struct DeployJournal {
+    dir: PathBuf,
+}
+
+impl DeployJournal {
+    pub fn open() -> Result<Self> {
+        Ok(Self::at(Profile::active().data_dir()?.join(JOURNAL_DIR)))
+    }
+
+    pub fn at(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Write `record` under its id. Ids only resolve to the second, so a
+    /// second record for the same Host in the same second gets a `-2`, `-3`…
+    /// suffix instead of overwriting the first.
+    pub fn append(&self, record: &mut DeployRecord) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)
+            .wrap_err_with(|| format!("Failed to create {}", self.dir.display()))?;
+        let base = record.id.clone();
+        for n in 1.. {
+            if n > 1 {
+                record.id = format!("{base}-{n}");
+            }
+            let path = self.dir.join(format!("{}.json", record.id));
+            let file = match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(file) => file,
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => {
+                    return Err(e).wrap_err_with(|| format!("Failed to write {}", path.display()));
+                }
+            };
+            serde_json::to_writer_pretty(file, record)
+                .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+            return Ok(path);
+        }
+        unreachable!("an unbounded range always yields a free id")
+    }
+
+    /// Every record, oldest first. A missing journal directory is an empty
+    /// journal, not an error: nothing has been deployed from this machine yet.
+    pub fn records(&self) -> Result<Vec<DeployRecord>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).wrap_err_with(|| format!("Failed to read {}", self.dir.display()));
+            }
+        };
+
+        let mut records = Vec::new();
+        for entry in entries {
+            let path = entry.wrap_err("Failed to read journal entry")?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let contents = std::fs::read_to_string(&path)
+                .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+            let record: DeployRecord = serde_json::from_str(&contents)
+                .wrap_err_with(|| format!("Failed to parse {}", path.display()))?;
+            records.push(record);
+        }
+        records.sort_by(|a, b| a.started_at.cmp(&b.started_at).then(a.id.cmp(&b.id)));
+        Ok(records)
+    }
+
+    /// Matching records, newest first, at most `limit` of them.
+    pub fn history(&self, filter: &HistoryFilter, limit: usize) -> Result<Vec<DeployRecord>> {
+        Ok(self
+            .records()?
+            .into_iter()
+            .rev()
+            .filter(|r| filter.matches(r))
+            .take(limit)
+            .collect())
+    }
+
+    /// Look up a record by id, or the newest one for `latest`.
+    pub fn find(&self, id: &str) -> Result<DeployRecord> {
+        let mut records = self.records()?;
+        if id == "latest" {
+            return records
+                .pop()
+                .ok_or_else(|| eyre::eyre!("The deploy journal is empty"));
+        }
+        records.into_iter().find(|r| r.id == id).ok_or_else(|| {
+            eyre::eyre!("No deploy record '{id}'. List them with `auberge deploy history`.")
+        })
+    }
+}
+
+/// Run `run`, then journal `entry` with its timing and outcome. `run` may
+/// add to the entry what only the run itself learns, such as the backup it
+/// took. Its own result is returned untouched: failing to write the journal
+/// is warned about, never allowed to turn a good deploy into a failed one.
+pub fn journaled(
+    mut entry: DeployEntry,
+    run: impl FnOnce(&mut DeployEntry) -> Result<()>,
+) -> Result<()> {
+    let started_at = Utc::now();
+    let outcome = run(&mut entry);
+    let mut record = DeployRecord::new(entry, started_at, Utc::now(), &outcome);
+    if let Err(e) = DeployJournal::open().and_then(|journal| journal.append(&mut record)) {
+        output::warn(&format!("Failed to record deploy in the journal: {e:#}"));
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(host: &str, apps: &[&str]) -> DeployEntry {
+        DeployEntry {
+            source: DeploySource::Deploy,
+            host: host.to_string(),
+            apps: apps.iter().map(|a| a.to_string()).collect(),
+            runs: vec![
+                RecordedRun {
+                    playbook: "hardening".to_string(),
+                    tags: vec![],
+                },
+                RecordedRun {
+                    playbook: "apps".to_string(),
+                    tags: apps.iter().map(|a| a.to_string()).collect(),
+                },
+            ],
+            app_versions: vec![("paperless_version".to_string(), "2.18.4".to_string())],
+            memory_budgets: vec![(
+                "paperless_webserver_memory_max".to_string(),
+                "768M".to_string(),
+            )],
+            check: false,
+            pre_upgrade_backup: None,
+        }
+    }
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        timestamp.parse().unwrap()
+    }
+
+    fn record(host: &str, apps: &[&str], started: &str, outcome: &Result<()>) -> DeployRecord {
+        DeployRecord::new(
+            entry(host, apps),
+            at(started),
+            at(started) + chrono::TimeDelta::seconds(42),
+            outcome,
+        )
+    }
+
+    #[test]
+    fn record_id_joins_start_timestamp_and_host() {
+        let r = record("prod", &["paperless"], "2026-09-01T10:20:30Z", &Ok(()));
+        assert_eq!(r.id, "2026-09-01_10-20-30_prod");
+        assert_eq!(r.duration_secs(), 42);
+        assert!(r.success);
+        assert!(r.failure.is_none());
+    }
+
+    #[test]
+    fn failed_record_keeps_the_failure_digest() {
+        let outcome = Err(eyre::eyre!(
+            "apps failed with exit code 2:\nfatal: [prod]: FAILED! => {{\"msg\": \"boom\"}}"
+        ));
+        let r = record("prod", &["paperless"], "2026-09-01T10:20:30Z", &outcome);
+        assert!(!r.success);
+        assert!(r.failure.unwrap().contains("fatal: [prod]: FAILED!"));
+    }
+
+    #[test]
+    fn record_carries_injected_versions_and_budgets() {
+        let r = record("prod", &["paperless"], "2026-09-01T10:20:30Z", &Ok(()));
+        assert_eq!(r.app_versions["paperless_version"], "2.18.4");
+        assert_eq!(r.memory_budgets["paperless_webserver_memory_max"], "768M");
+        assert_eq!(r.runs[1].tags, vec!["paperless"]);
+    }
+
+    #[test]
+    fn journal_round_trips_records_oldest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = DeployJournal::at(dir.path().join("deploys"));
+        let mut newer = record("prod", &["freshrss"], "2026-09-02T08:00:00Z", &Ok(()));
+        let mut older = record("prod", &["paperless"], "2026-09-01T08:00:00Z", &Ok(()));
+        journal.append(&mut newer).unwrap();
+        journal.append(&mut older).unwrap();
+
+        assert_eq!(journal.records().unwrap(), vec![older, newer]);
+    }
+
+    #[test]
+    fn same_second_records_for_a_host_get_distinct_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = DeployJournal::at(dir.path().to_path_buf());
+        let mut first = record("prod", &["paperless"], "2026-09-01T08:00:00Z", &Ok(()));
+        let mut second = record("prod", &["freshrss"], "2026-09-01T08:00:00Z", &Ok(()));
+        journal.append(&mut first).unwrap();
+        journal.append(&mut second).unwrap();
+
+        assert_eq!(first.id, "2026-09-01_08-00-00_prod");
+        assert_eq!(second.id, "2026-09-01_08-00-00_prod-2");
+        assert_eq!(journal.records().unwrap(), vec![first, second]);
+    }
+
+    #[test]
+    fn missing_journal_directory_is_an_empty_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = DeployJournal::at(dir.path().join("never-created"));
+        assert!(journal.records().unwrap().is_empty());
+        assert!(journal.find("latest").is_err());
+    }
+
+    #[test]
+    fn history_filters_newest_first_and_honours_the_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = DeployJournal::at(dir.path().to_path_buf());
+        let failed = Err(eyre::eyre!("apps failed"));
+        for mut r in [
+            record("prod", &["paperless"], "2026-09-01T08:00:00Z", &Ok(())),
+            record("staging", &["paperless"], "2026-09-02T08:00:00Z", &failed),
+            record("prod", &["freshrss"], "2026-09-03T08:00:00Z", &failed),
+            record("prod", &["paperless"], "2026-09-04T08:00:00Z", &Ok(())),
+        ] {
+            journal.append(&mut r).unwrap();
+        }
+
+        let ids = |filter: HistoryFilter, limit| -> Vec<String> {
+            journal
+                .history(&filter, limit)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.id)
+                .collect()
+        };
+
+        assert_eq!(
+            ids(HistoryFilter::default(), 2),
+            vec!["2026-09-04_08-00-00_prod", "2026-09-03_08-00-00_prod"]
+        );
+        assert_eq!(
+            ids(
+                HistoryFilter {
+                    host: Some("prod".to_string()),
+                    app: Some("paperless".to_string()),
+                    failed_only: false,
+                },
+                10
+            ),
+            vec!["2026-09-04_08-00-00_prod", "2026-09-01_08-00-00_prod"]
+        );
+        assert_eq!(
+            ids(
+                HistoryFilter {
+                    failed_only: true,
+                    ..Default::default()
+                },
+                10
+            ),
+            vec!["2026-09-03_08-00-00_prod", "2026-09-02_08-00-00_staging"]
+        );
+    }
+
+    #[test]
+    fn find_resolves_latest_and_explicit_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = DeployJournal::at(dir.path().to_path_buf());
+        let mut first = record("prod", &["paperless"], "2026-09-01T08:00:00Z", &Ok(()));
+        let mut second = record("prod", &["freshrss"], "2026-09-02T08:00:00Z", &Ok(()));
+        journal.append(&mut first).unwrap();
+        journal.append(&mut second).unwrap();
+
+        assert_eq!(journal.find("latest").unwrap(), second);
+        assert_eq!(journal.find(&first.id).unwrap(), first);
+        let err = journal.find("nope").unwrap_err().to_string();
+        assert!(err.contains("auberge deploy history"));
+    }
+
+    fn versioned(
+        host: &str,
+        version: &str,
+        started: &str,
+        success: bool,
+        check: bool,
+    ) -> DeployRecord {
+        let outcome = if success {
+            Ok(())
+        } else {
+            Err(eyre::eyre!("apps failed"))
+        };
+        let mut r = record(host, &["paperless"], started, &outcome);
+        r.app_versions
+            .insert("paperless_version".to_string(), version.to_string());
+        r.check = check;
+        r
+    }
+
+    #[test]
+    fn rollback_target_skips_deploys_of_the_current_version() {
+        let records = vec![
+            versioned("prod", "2.17.0", "2026-09-01T08:00:00Z", true, false),
+            versioned("prod", "2.18.0", "2026-09-02T08:00:00Z", true, false),
+            versioned("prod", "3.0.0", "2026-09-03T08:00:00Z", true, false),
+            versioned("prod", "3.0.0", "2026-09-04T08:00:00Z", true, false),
+        ];
+        let target =
+            rollback_target(&records, "prod", "paperless", "paperless_version", "3.0.0").unwrap();
+        assert_eq!(target.version, "2.18.0");
+        assert_eq!(target.deployed, &records[1]);
+        assert_eq!(target.upgrade, Some(&records[2]));
+    }
+
+    #[test]
+    fn rollback_target_ignores_failed_check_and_other_host_deploys() {
+        let records = vec![
+            versioned("prod", "2.17.0", "2026-09-01T08:00:00Z", true, false),
+            versioned("staging", "2.18.0", "2026-09-02T08:00:00Z", true, false),
+            versioned("prod", "2.18.0", "2026-09-02T09:00:00Z", true, true),
+            versioned("prod", "2.18.0", "2026-09-02T10:00:00Z", false, false),
+            versioned("prod", "3.0.0", "2026-09-03T08:00:00Z", false, false),
+        ];
+        let target =
+            rollback_target(&records, "prod", "paperless", "paperless_version", "3.0.0").unwrap();
+        assert_eq!(target.version, "2.17.0");
+        assert_eq!(target.upgrade, Some(&records[3]));
+    }
+
+    #[test]
+    fn rollback_target_is_none_without_an_earlier_version() {
+        let records = vec![versioned(
+            "prod",
+            "3.0.0",
+            "2026-09-03T08:00:00Z",
+            true,
+            false,
+        )];
+        assert!(
+            rollback_target(&records, "prod", "paperless", "paperless_version", "3.0.0").is_none()
+        );
+        assert!(rollback_target(&records, "prod", "freshrss", "freshrss_version", "1.0").is_none());
+    }
+
+    #[test]
+    fn rollback_target_finds_the_backup_the_upgrade_took() {
+        let mut upgrade = versioned("prod", "3.0.0", "2026-09-03T08:00:00Z", true, false);
+        upgrade.pre_upgrade_backup = Some(PreUpgradeBackup {
+            id: "2026-09-03_08-00-00".to_string(),
+            apps: vec!["paperless".to_string()],
+        });
+        let records = vec![
+            versioned("prod", "2.18.0", "2026-09-02T08:00:00Z", true, false),
+            upgrade,
+        ];
+        let target =
+            rollback_target(&records, "prod", "paperless", "paperless_version", "3.0.0").unwrap();
+        assert_eq!(
+            target.pre_upgrade_backup("paperless"),
+            Some("2026-09-03_08-00-00")
+        );
+        assert_eq!(target.pre_upgrade_backup("freshrss"), None);
+    }
+
+    #[test]
+    fn last_deployed_version_is_the_newest_successful_real_deploy() {
+        let records = vec![
+            versioned("prod", "2.18.0", "2026-09-02T08:00:00Z", true, false),
+            versioned("prod", "3.0.0", "2026-09-03T08:00:00Z", false, false),
+            versioned("prod", "3.1.0", "2026-09-04T08:00:00Z", true, true),
+        ];
+        assert_eq!(
+            last_deployed_version(&records, "prod", "paperless", "paperless_version"),
+            Some("2.18.0")
+        );
+        assert_eq!(
+            last_deployed_version(&records, "staging", "paperless", "paperless_version"),
+            None
+        );
+    }
+
+    #[test]
+    fn records_skip_non_json_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("README"), "not a record").unwrap();
+        let journal = DeployJournal::at(dir.path().to_path_buf());
+        assert!(journal.records().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::config::Config;
+use chrono::{DateTime, Utc};
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const CACHE_DIR: &str = "upstream-cache";
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// One upstream response as last received: enough to serve it again and to
+/// revalidate it with `If-None-Match`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// The `Link` header, which carries the next page of a paginated API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+    pub body: String,
+}
+
+/// Upstream responses cached under `<data_dir>/upstream-cache/`, one JSON
+/// file per request, named by a hash of its key. The key itself is stored
+/// too, so a hash collision reads as a miss rather than a wrong answer.
+pub struct ResponseCache {
+    dir: PathBuf,
+}
+
+impl ResponseCache {
+    pub fn open() -> Result<Self> {
+        Ok(Self::at(Config::data_dir()?.join(CACHE_DIR)))
+    }
+
+    pub fn at(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// The entry stored for `key`. An unreadable or corrupt entry is a miss:
+    /// the cache only ever saves a request, it never answers wrongly.
+    pub fn get(&self, key: &str) -> Option<CachedResponse> {
+        let content = std::fs::read_to_string(self.path(key)).ok()?;
+        serde_json::from_str::<CachedResponse>(&content)
+            .ok()
+            .filter(|entry| entry.key == key)
+    }
+
+    pub fn put(&self, entry: &CachedResponse) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .wrap_err_with(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = self.path(&entry.key);
+        let json = serde_json::to_string(entry).wrap_err("Failed to serialize cached response")?;
+        std::fs::write(&path, json).wrap_err_with(|| format!("Failed to write {}", path.display()))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}.json", fnv1a(key.as_bytes())))
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str, body: &str) -> CachedResponse {
+        CachedResponse {
+            key: key.to_string(),
+            etag: Some("\"abc\"".to_string()),
+            link: None,
+            fetched_at: Utc::now(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn put_then_get_round_trips_an_entry() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::at(tmp.path().join("cache"));
+        let stored = entry("GET https://api.github.com/repos/a/b/releases", "[]");
+
+        cache.put(&stored).unwrap();
+
+        assert_eq!(cache.get(&stored.key), Some(stored));
+    }
+
+    #[test]
+    fn get_misses_an_unknown_key() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::at(tmp.path().to_path_buf());
+        cache.put(&entry("GET https://a", "1")).unwrap();
+
+        assert_eq!(cache.get("GET https://b"), None);
+    }
+
+    #[test]
+    fn get_treats_a_corrupt_entry_as_a_miss() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::at(tmp.path().to_path_buf());
+        std::fs::write(cache.path("GET https://a"), "not json").unwrap();
+
+        assert_eq!(cache.get("GET https://a"), None);
+    }
+
+    #[test]
+    fn get_rejects_an_entry_stored_under_another_key() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::at(tmp.path().to_path_buf());
+        let other = entry("GET https://other", "1");
+        std::fs::write(
+            cache.path("GET https://a"),
+            serde_json::to_string(&other).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(cache.get("GET https://a"), None);
+    }
+}
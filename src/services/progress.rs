@@ -88,6 +88,20 @@ impl Progress for TerminalProgress {
     }
 }
 
+/// Drops every event. For runners fanned out across Hosts on several threads,
+/// where one spinner per thread would redraw over the others' lines.
+pub struct SilentProgress;
+
+impl Progress for SilentProgress {
+    fn task_started(&mut self, _name: &str) {}
+    fn task_done(&mut self) {}
+    fn bytes_transferred(&mut self, _n: u64) {}
+    fn set_total(&mut self, _n: Option<u64>) {}
+    fn info(&mut self, _msg: &str) {}
+    fn warn(&mut self, _msg: &str) {}
+    fn cancel(&mut self) {}
+}
+
 fn format_info_line(msg: &str, use_colors: bool) -> String {
     if use_colors {
         format!("{CYAN}\u{2192}{RESET} {msg}")
@@ -1,11 +1,16 @@
 use crate::hosts::Host;
+use crate::profile::Profile;
 use eyre::{Context, Result};
 use std::path::{Path, PathBuf};
 
-pub const INCLUDE_LINE: &str = "Include ~/.ssh/config.d/auberge.conf";
+/// Each Profile has its own include file, so several can be included side
+/// by side: `auberge.conf` for the default, `auberge-<profile>.conf` else.
+pub fn include_line(profile: &Profile) -> String {
+    format!("Include ~/.ssh/config.d/{}", profile.ssh_include_name())
+}
 
-pub fn include_file_path(ssh_dir: &Path) -> PathBuf {
-    ssh_dir.join("config.d/auberge.conf")
+pub fn include_file_path(ssh_dir: &Path, profile: &Profile) -> PathBuf {
+    ssh_dir.join("config.d").join(profile.ssh_include_name())
 }
 
 pub fn render(hosts: &[Host]) -> String {
@@ -38,8 +43,8 @@ fn identity_file(host: &Host) -> String {
 
 /// ssh applies the ~/.ssh/config ownership/permission check to included files
 /// too, so the directory is created 0700 and the file forced to 0600.
-pub fn write_include_file(ssh_dir: &Path, hosts: &[Host]) -> Result<PathBuf> {
-    let path = include_file_path(ssh_dir);
+pub fn write_include_file(ssh_dir: &Path, profile: &Profile, hosts: &[Host]) -> Result<PathBuf> {
+    let path = include_file_path(ssh_dir, profile);
     let dir = path.parent().expect("include path has a parent");
     create_private_dir(dir)?;
     std::fs::write(&path, render(hosts))
@@ -68,10 +73,10 @@ fn create_private_dir(dir: &Path) -> Result<()> {
 
 /// A missing ~/.ssh/config legitimately means "no include yet"; any other
 /// read failure is a real problem the caller must surface, not a nag trigger.
-pub fn main_config_has_include(ssh_dir: &Path) -> Result<bool> {
+pub fn main_config_has_include(ssh_dir: &Path, profile: &Profile) -> Result<bool> {
     let path = ssh_dir.join("config");
     match std::fs::read_to_string(&path) {
-        Ok(content) => Ok(has_include(&content, ssh_dir)),
+        Ok(content) => Ok(has_include(&content, ssh_dir, &profile.ssh_include_name())),
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
         Err(e) => Err(e).wrap_err_with(|| format!("Failed to read {}", path.display())),
     }
@@ -79,7 +84,7 @@ pub fn main_config_has_include(ssh_dir: &Path) -> Result<bool> {
 
 /// A glob include (`Include ~/.ssh/config.d/*.conf`) also loads the auberge
 /// file, so it counts: a false "missing" would nag on every host subcommand.
-pub fn has_include(main_config: &str, ssh_dir: &Path) -> bool {
+pub fn has_include(main_config: &str, ssh_dir: &Path, file_name: &str) -> bool {
     main_config.lines().any(|line| {
         let mut tokens = line.split_whitespace();
         let Some(directive) = tokens.next() else {
@@ -88,20 +93,20 @@ pub fn has_include(main_config: &str, ssh_dir: &Path) -> bool {
         if !directive.eq_ignore_ascii_case("include") {
             return false;
         }
-        tokens.any(|arg| include_arg_loads_auberge_conf(arg.trim_matches('"'), ssh_dir))
+        tokens.any(|arg| include_arg_loads(arg.trim_matches('"'), ssh_dir, file_name))
     })
 }
 
 /// The directory must be ~/.ssh/config.d itself — tilde, absolute, or
 /// ssh_dir-relative form (relative Include paths resolve against ~/.ssh) —
 /// so an unrelated `Include /etc/ssh/config.d/*` never counts.
-fn include_arg_loads_auberge_conf(arg: &str, ssh_dir: &Path) -> bool {
+fn include_arg_loads(arg: &str, ssh_dir: &Path, file_name: &str) -> bool {
     let Some((dir, file)) = arg.rsplit_once('/') else {
         return false;
     };
     let dir_matches =
         dir == "~/.ssh/config.d" || dir == "config.d" || Path::new(dir) == ssh_dir.join("config.d");
-    dir_matches && glob_matches(file, "auberge.conf")
+    dir_matches && glob_matches(file, file_name)
 }
 
 /// The glob(3) subset ssh Include arguments use in practice: `*` and `?`.
@@ -181,6 +186,11 @@ mod tests {
     }
 
     const SSH_DIR: &str = "/home/x/.ssh";
+    const CONF: &str = "auberge.conf";
+
+    fn default_profile() -> Profile {
+        Profile::named(crate::profile::DEFAULT_PROFILE)
+    }
 
     fn ssh_dir() -> &'static Path {
         Path::new(SSH_DIR)
@@ -188,52 +198,103 @@ mod tests {
 
     #[test]
     fn has_include_matches_exact_line() {
-        assert!(has_include(INCLUDE_LINE, ssh_dir()));
+        assert!(has_include(
+            &include_line(&default_profile()),
+            ssh_dir(),
+            CONF
+        ));
         assert!(has_include(
             "  Include ~/.ssh/config.d/auberge.conf",
-            ssh_dir()
+            ssh_dir(),
+            CONF
         ));
         assert!(has_include(
             "include ~/.ssh/config.d/auberge.conf",
-            ssh_dir()
+            ssh_dir(),
+            CONF
         ));
         assert!(has_include(
             "Include \"~/.ssh/config.d/auberge.conf\"",
-            ssh_dir()
+            ssh_dir(),
+            CONF
+        ));
+        assert!(has_include(
+            "Include config.d/auberge.conf",
+            ssh_dir(),
+            CONF
         ));
-        assert!(has_include("Include config.d/auberge.conf", ssh_dir()));
         assert!(has_include(
             &format!("Include {SSH_DIR}/config.d/auberge.conf"),
-            ssh_dir()
+            ssh_dir(),
+            CONF
+        ));
+    }
+
+    #[test]
+    fn has_include_tells_profiles_apart() {
+        let family = Profile::named("family");
+        assert_eq!(
+            include_line(&family),
+            "Include ~/.ssh/config.d/auberge-family.conf"
+        );
+        let name = family.ssh_include_name();
+        assert!(has_include(&include_line(&family), ssh_dir(), &name));
+        assert!(has_include(
+            "Include ~/.ssh/config.d/*.conf",
+            ssh_dir(),
+            &name
+        ));
+        assert!(!has_include(
+            &include_line(&default_profile()),
+            ssh_dir(),
+            &name
         ));
     }
 
     #[test]
     fn has_include_matches_glob_over_own_config_d() {
-        assert!(has_include("Include ~/.ssh/config.d/*.conf", ssh_dir()));
-        assert!(has_include("Include ~/.ssh/config.d/*", ssh_dir()));
-        assert!(has_include("Include config.d/auberge.*", ssh_dir()));
+        assert!(has_include(
+            "Include ~/.ssh/config.d/*.conf",
+            ssh_dir(),
+            CONF
+        ));
+        assert!(has_include("Include ~/.ssh/config.d/*", ssh_dir(), CONF));
+        assert!(has_include("Include config.d/auberge.*", ssh_dir(), CONF));
     }
 
     #[test]
     fn has_include_rejects_comments_and_other_includes() {
         assert!(!has_include(
             "# Include ~/.ssh/config.d/auberge.conf",
-            ssh_dir()
+            ssh_dir(),
+            CONF
+        ));
+        assert!(!has_include("Include ~/.ssh/other.conf", ssh_dir(), CONF));
+        assert!(!has_include(
+            "Host auberge\n  HostName 1.2.3.4",
+            ssh_dir(),
+            CONF
         ));
-        assert!(!has_include("Include ~/.ssh/other.conf", ssh_dir()));
-        assert!(!has_include("Host auberge\n  HostName 1.2.3.4", ssh_dir()));
-        assert!(!has_include("", ssh_dir()));
+        assert!(!has_include("", ssh_dir(), CONF));
     }
 
     #[test]
     fn has_include_rejects_globs_over_foreign_config_d_dirs() {
-        assert!(!has_include("Include /etc/ssh/config.d/*", ssh_dir()));
-        assert!(!has_include("Include ~/other/config.d/*.conf", ssh_dir()));
-        assert!(!has_include("Include ~/.ssh/config.d/*.bak", ssh_dir()));
+        assert!(!has_include("Include /etc/ssh/config.d/*", ssh_dir(), CONF));
+        assert!(!has_include(
+            "Include ~/other/config.d/*.conf",
+            ssh_dir(),
+            CONF
+        ));
+        assert!(!has_include(
+            "Include ~/.ssh/config.d/*.bak",
+            ssh_dir(),
+            CONF
+        ));
         assert!(!has_include(
             "Include /etc/ssh/config.d/auberge.conf",
-            ssh_dir()
+            ssh_dir(),
+            CONF
         ));
     }
 
@@ -255,7 +316,7 @@ mod tests {
         let ssh_dir = tmp.path().join(".ssh");
 
         let hosts = [fixture_host("auberge", None)];
-        let path = write_include_file(&ssh_dir, &hosts).unwrap();
+        let path = write_include_file(&ssh_dir, &default_profile(), &hosts).unwrap();
 
         assert_eq!(path, ssh_dir.join("config.d/auberge.conf"));
         assert_eq!(std::fs::read_to_string(&path).unwrap(), render(&hosts));
@@ -279,8 +340,18 @@ mod tests {
         let tmp = tempfile::tempdir().unwrap();
         let ssh_dir = tmp.path().join(".ssh");
 
-        write_include_file(&ssh_dir, &[fixture_host("old-name", None)]).unwrap();
-        let path = write_include_file(&ssh_dir, &[fixture_host("new-name", None)]).unwrap();
+        write_include_file(
+            &ssh_dir,
+            &default_profile(),
+            &[fixture_host("old-name", None)],
+        )
+        .unwrap();
+        let path = write_include_file(
+            &ssh_dir,
+            &default_profile(),
+            &[fixture_host("new-name", None)],
+        )
+        .unwrap();
 
         let content = std::fs::read_to_string(&path).unwrap();
         assert!(content.contains("Host new-name"));
@@ -293,20 +364,26 @@ mod tests {
         let ssh_dir = tmp.path().join(".ssh");
         std::fs::create_dir_all(&ssh_dir).unwrap();
 
-        assert!(!main_config_has_include(&ssh_dir).unwrap(), "missing file");
+        assert!(
+            !main_config_has_include(&ssh_dir, &default_profile()).unwrap(),
+            "missing file"
+        );
 
         std::fs::write(ssh_dir.join("config"), "Host x\n  HostName 1.2.3.4\n").unwrap();
         assert!(
-            !main_config_has_include(&ssh_dir).unwrap(),
+            !main_config_has_include(&ssh_dir, &default_profile()).unwrap(),
             "no include line"
         );
 
         std::fs::write(
             ssh_dir.join("config"),
-            format!("{INCLUDE_LINE}\n\nHost x\n  HostName 1.2.3.4\n"),
+            format!(
+                "{}\n\nHost x\n  HostName 1.2.3.4\n",
+                include_line(&default_profile())
+            ),
         )
         .unwrap();
-        assert!(main_config_has_include(&ssh_dir).unwrap());
+        assert!(main_config_has_include(&ssh_dir, &default_profile()).unwrap());
     }
 
     #[test]
@@ -315,6 +392,6 @@ mod tests {
         let ssh_dir = tmp.path().join(".ssh");
         std::fs::create_dir_all(ssh_dir.join("config")).unwrap();
 
-        assert!(main_config_has_include(&ssh_dir).is_err());
+        assert!(main_config_has_include(&ssh_dir, &default_profile()).is_err());
     }
 }
@@ -1,4 +1,4 @@
-use crate::playbook_meta::VersionPin;
+use crate::playbook_meta::{VersionPin, replace_scalar};
 use eyre::{Result, WrapErr};
 use std::path::Path;
 
@@ -78,6 +78,28 @@ fn tool_version(role: &str, annotation: &str, pinned: &str) -> Result<ToolVersio
     })
 }
 
+/// Rewrite the `<role>_<tool>_version` line under its `# renovate:`
+/// annotation to `to`, keeping quoting and every other line as committed.
+pub fn rewrite_tool_version(content: &str, role: &str, tool: &str, to: &str) -> Result<String> {
+    let variable = format!("{role}_{tool}_version:");
+    let mut annotated = false;
+    let mut rewritten = false;
+    let mut out = String::with_capacity(content.len());
+    for line in content.split_inclusive('\n') {
+        if annotated && !rewritten && line.starts_with(&variable) {
+            out.push_str(&replace_scalar(line, to)?);
+            rewritten = true;
+        } else {
+            out.push_str(line);
+        }
+        annotated = line.starts_with(ANNOTATION);
+    }
+    if !rewritten {
+        eyre::bail!("no annotated `{variable}` line to rewrite");
+    }
+    Ok(out)
+}
+
 /// Parse the `key=value` coordinates of a `# renovate:` annotation — the
 /// vocabulary of Renovate's regex manager, same as a Playbook Meta
 /// `version:` block.
@@ -172,6 +194,29 @@ mod tests {
         assert!(scan_defaults("caddy", content).is_err());
     }
 
+    #[test]
+    fn test_rewrite_tool_version_touches_only_the_annotated_line() {
+        let content = "---\n\
+            # renovate: datasource=go depName=github.com/mholt/caddy-l4\n\
+            caddy_l4_version: \"v0.1.2\"\n\
+            # renovate: datasource=go depName=github.com/caddy-dns/cloudflare\n\
+            caddy_cloudflare_plugin_version: \"v0.2.4\"\n\
+            caddy_user: caddy\n";
+
+        let rewritten = rewrite_tool_version(content, "caddy", "l4", "v0.2.0").unwrap();
+
+        assert_eq!(rewritten, content.replace("v0.1.2", "v0.2.0"));
+        let tools = scan_defaults("caddy", &rewritten).unwrap();
+        assert_eq!(tools[0].pin.value, "v0.2.0");
+        assert_eq!(tools[1].pin.value, "v0.2.4");
+    }
+
+    #[test]
+    fn test_rewrite_tool_version_ignores_unannotated_variables() {
+        let content = "blocky_lego_version: \"5.3.1\"\n";
+        assert!(rewrite_tool_version(content, "blocky", "lego", "5.4.0").is_err());
+    }
+
     #[test]
     fn test_declared_tool_versions_matches_the_repo_allowlist() {
         let tools = declared_tool_versions(&roles_dir()).unwrap();